@@ -57,7 +57,10 @@ plugin-update-already-latest = { $id } ist bereits auf der neuesten Version ({ $
 plugin-update-available = Aktualisiere { $id } von { $current } auf { $latest }...
 plugin-update-downloading = Lade { $id } v{ $version } herunter...
 plugin-update-success = { $id } auf v{ $version } aktualisiert
-plugin-update-all-start = Aktualisiere { $count } Plugin(s)...
+plugin-update-all-start = Aktualisiere { $count } { $count ->
+    [one] Plugin
+   *[other] Plugins
+}...
 plugin-update-all-done = Aktualisierung abgeschlossen!
 plugin-update-all-warning = Aktualisierung von { $id } fehlgeschlagen: { $error }
 
@@ -3,16 +3,26 @@
 # ============================================================================
 
 self-update-checking = Checking for updates...
+self-update-checking-channel = Checking for updates on the { $channel } channel...
 self-update-already-latest = You are already on the latest version ({ $version })
 self-update-new-version = New version available: { $current } → { $latest }
 self-update-downloading = Downloading update...
 self-update-extracting = Extracting update...
 self-update-installing = Installing update...
+self-update-verifying = Verifying downloaded binary...
+self-update-verifying-archive = Verifying download...
+self-update-downgrade = Downgrading from { $current } to { $target }
 self-update-success = Successfully updated to version { $version }
 self-update-error-platform = Unsupported operating system
 self-update-error-arch = Unsupported architecture
 self-update-error-no-asset = No release asset found for platform: { $platform }
+self-update-musl-fallback = This release has no { $platform } build; using { $fallback }, which needs glibc
 self-update-error-no-release = No CLI manager release found
+self-update-error-no-version = No CLI release { $version } found
+self-update-error-verify = Downloaded binary failed verification, keeping the current version: { $reason }
+self-update-error-checksum = Checksum mismatch for { $asset } (expected { $expected }, got { $actual }), keeping the current version
+self-update-error-checksum-missing = { $source } has no checksum for { $asset }, keeping the current version
+self-update-error-no-checksum = The release publishes no checksum for { $asset }, keeping the current version (--no-verify installs it unchecked)
 
 # ============================================================================
 # SHELL COMPLETIONS DOMAIN
@@ -40,26 +50,148 @@ plugin-installed-hint = Install plugins with: adi plugin install <plugin-id>
 # Plugin installation
 plugin-install-downloading = Downloading { $id } v{ $version } for { $platform }...
 plugin-install-extracting = Extracting to { $path }...
+plugin-install-local = Installing { $id } v{ $version } from { $path }...
 plugin-install-success = Installed { $id } v{ $version } successfully!
 plugin-install-already-installed = { $id } v{ $version } is already installed
 plugin-install-dependency = Installing dependency: { $id }
+plugin-install-optional-skipped = Skipping optional dependency { $id } (use --optional-deps to install it)
+plugin-install-system-used = Using system { $name } at { $path }
+plugin-install-system-bundled = Using bundled { $name }: { $reason }
+plugin-install-system-not-found = not found on PATH
+plugin-install-system-too-old = system version { $found } is older than the required { $required }
+plugin-install-system-unknown-version = couldn't determine the version of { $path }
+plugin-install-system-failed = Could not record system binary for { $id }: { $error }
 plugin-install-error-platform = Plugin { $id } does not support platform { $platform }
 plugin-install-pattern-searching = Searching for plugins matching pattern "{ $pattern }"...
-plugin-install-pattern-found = Found { $count } plugin(s) matching pattern
+plugin-install-pattern-found = Found { $count } { $count ->
+    [one] plugin
+   *[other] plugins
+} matching pattern
 plugin-install-pattern-none = No plugins found matching pattern "{ $pattern }"
-plugin-install-pattern-installing = Installing { $count } plugin(s)...
-plugin-install-pattern-success = { $count } plugin(s) installed successfully!
+plugin-install-pattern-select = Select plugins to install (space to toggle, enter to confirm)
+plugin-install-pattern-cancelled = Nothing selected, no plugins installed.
+plugin-install-pattern-installing = Installing { $count } { $count ->
+    [one] plugin
+   *[other] plugins
+}...
+plugin-install-pattern-success = { $count } { $count ->
+    [one] plugin
+   *[other] plugins
+} installed successfully!
 plugin-install-pattern-failed = Failed to install:
+plugin-install-retry-prompt = { $count ->
+    [one] { $count } plugin failed to install. Retry it?
+   *[other] { $count } plugins failed to install. Retry them?
+}
+plugin-install-retrying = Retrying { $count } failed { $count ->
+    [one] plugin
+   *[other] plugins
+} (attempt { $attempt })...
+plugin-install-resuming = Resuming interrupted install: { $pending } pending, { $completed } already done
+plugin-install-resume-available = A previous install of "{ $pattern }" stopped with { $count } { $count ->
+    [one] plugin
+   *[other] plugins
+} pending (use --resume to continue it)
+plugin-install-resume-none = No interrupted install of "{ $pattern }" to resume, starting fresh
+plugin-install-resume-hint = Run `adi plugin install "{ $pattern }" --resume` to continue with the rest
+plugin-install-locked-success = Installed { $count } locked { $count ->
+    [one] plugin
+   *[other] plugins
+}, all checksums verified
+plugin-freeze-written = Wrote { $count } plugin(s) to { $path }
 
 # Plugin updates
 plugin-update-checking = Checking for updates to { $id }...
 plugin-update-already-latest = { $id } is already at latest version ({ $version })
 plugin-update-available = Updating { $id } from { $current } to { $latest }...
+plugin-update-downgrade = Downgrading { $id } from { $current } to { $target }
 plugin-update-downloading = Downloading { $id } v{ $version }...
 plugin-update-success = Updated { $id } to v{ $version }
-plugin-update-all-start = Updating { $count } plugin(s)...
+plugin-update-all-checking = Checking { $count } { $count ->
+    [one] plugin
+   *[other] plugins
+} for updates...
+plugin-update-all-summary = { $current } up to date, { $outdated } to update
+plugin-update-all-start = Updating { $count } { $count ->
+    [one] plugin
+   *[other] plugins
+}...
 plugin-update-all-done = Update complete!
 plugin-update-all-warning = Failed to update { $id }: { $error }
+plugin-update-all-pinned = Skipped { $count } pinned { $count ->
+    [one] plugin
+   *[other] plugins
+}
+plugin-plan-title = Dry run (nothing was downloaded)
+plugin-plan-empty = Nothing to install
+plugin-plan-new = new
+plugin-plan-upgrade = upgrade from v{ $from }
+plugin-plan-downgrade = downgrade from v{ $from }
+plugin-plan-satisfied = already satisfied
+plugin-plan-size-unknown = unknown
+plugin-plan-summary = { $count } plugin(s) to download, { $size } total
+
+# Plugin pinning
+plugin-pin-success = Pinned { $id } at v{ $version }
+plugin-pin-already = { $id } is already pinned at v{ $version }
+plugin-unpin-success = Unpinned { $id }
+plugin-unpin-not-pinned = { $id } is not pinned
+plugin-installed-pinned = pinned
+
+# Registry cache
+plugin-registry-using-stale-cache = Using cached registry data, the registry request failed: { $error }
+plugin-registry-unreachable-using-cache = Using cached data (registry unreachable)
+plugin-cache-cleared = Cleared the registry cache ({ $size } freed from { $path })
+plugin-prune-removed = Removed { $path } ({ $size })
+plugin-prune-would-remove = Would remove { $path } ({ $size })
+plugin-prune-reclaimed = Reclaimed { $size }
+plugin-prune-would-reclaim = Would reclaim { $size }
+plugin-prune-nothing = Nothing to prune
+plugin-prune-needs-attention = Skipped { $id }: no valid .version marker, so its active version is unknown. Reinstall it to repair
+plugin-state-lock-waiting = Waiting for another adi operation to finish (lock: { $path })...
+
+# Plugin info
+plugin-info-id = ID
+plugin-info-description = Description
+plugin-info-latest = Latest version
+plugin-info-platforms = Platforms
+plugin-info-types = Type
+plugin-info-tags = Tags
+plugin-info-dependencies = Dependencies
+plugin-info-installed = Installed
+plugin-info-not-installed = no
+plugin-info-none = none
+plugin-info-did-you-mean = Did you mean `{ $id }`?
+
+# Plugin changelog
+plugin-changelog-no-source = { $id } has no GitHub repository in its manifest, so no release notes are available
+plugin-changelog-empty = No release notes found for { $id }
+plugin-changelog-no-notes = Release { $tag } has no notes
+
+# Plugin loading
+plugin-load-failed-summary = { $count } { $count ->
+    [one] plugin
+   *[other] plugins
+} failed to load:
+plugin-load-failed-hint = Reinstall a broken plugin with `adi plugin update <id>`, or run with RUST_LOG=debug for details
+plugin-load-auto-disabled = { $id } failed to load { $count } times in a row and has been disabled. Fix or reinstall it, then run `adi plugin doctor { $id }`
+
+# Plugin doctor
+plugin-doctor-checking = Checking { $id }...
+plugin-doctor-ok = { $id } loads successfully
+plugin-doctor-reenabled = { $id } has been re-enabled
+plugin-doctor-failed = { $id } failed to load: { $error }
+plugin-doctor-system-ok = System binary { $path } is present
+plugin-doctor-system-missing = { $id } uses the system binary { $path }, which no longer exists. Reinstall it with `adi plugin install { $id } --prefer-system` or without the flag to use the bundled copy
+
+# Plugin self-test
+plugin-test-passed = { $id } self-test passed
+plugin-test-failed = { $id } self-test failed (exit code { $code })
+plugin-test-load-only = { $id } has no self-test; it loads and lists { $count } { $count ->
+    [one] command
+   *[other] commands
+}
+plugin-test-error = { $id } self-test could not run: { $error }
 
 # Plugin uninstallation
 plugin-uninstall-prompt = Uninstall plugin { $id }?
@@ -74,6 +206,7 @@ plugin-uninstall-error-not-installed = Plugin { $id } is not installed
 
 search-searching = Searching for "{ $query }"...
 search-no-results = No results found.
+search-no-results-filtered = No results found with { $filters }.
 search-packages-title = Packages:
 search-plugins-title = Plugins:
 search-results-summary = Found { $packages } package(s) and { $plugins } plugin(s)
@@ -94,10 +227,13 @@ run-title = Runnable Plugins:
 run-empty = No plugins with CLI interface installed.
 run-hint-install = Install plugins with: adi plugin install <plugin-id>
 run-hint-usage = Run a plugin with: adi run <plugin-id> [args...]
+run-select-plugin = Select a plugin to run
+run-prompt-args = Arguments for { $id } (space-separated, leave empty for none)
 run-error-not-found = Plugin '{ $id }' not found or has no CLI interface
 run-error-no-plugins = No runnable plugins installed.
 run-error-available = Runnable plugins:
 run-error-failed = Failed to run plugin: { $error }
+run-error-invalid-output = Plugin returned invalid output ({ $reason }): { $preview }
 
 # ============================================================================
 # EXTERNAL COMMANDS DOMAIN
@@ -105,6 +241,7 @@ run-error-failed = Failed to run plugin: { $error }
 
 external-error-no-command = No command provided
 external-error-unknown = Unknown command: { $command }
+external-did-you-mean = Did you mean `adi { $command }`?
 external-error-no-installed = No plugin commands installed.
 external-hint-install = Install plugins with: adi plugin install <plugin-id>
 external-available-title = Available plugin commands:
@@ -225,6 +362,9 @@ common-downloading-prefix = →
 common-checkmark = ✓
 common-arrow = →
 
+root-refused = adi should not be run as root. Files it creates in your config and data directories would be owned by root and break later runs as your normal user.
+root-refused-hint = Run adi as your normal user; privileged work goes through the daemon (`adi daemon setup`). Pass --allow-root or set ADI_ALLOW_ROOT=1 to run as root anyway.
+
 # ============================================================================
 # ERRORS DOMAIN
 # ============================================================================
@@ -232,6 +372,11 @@ common-arrow = →
 error-component-not-found = Component '{ $name }' not found
 error-installation-failed = Installation failed for '{ $component }': { $reason }
 error-dependency-missing = Dependency '{ $dependency }' required by '{ $component }' is not installed
+error-dependency-conflict = '{ $plugin }' requires { $dependency } { $requirement }, but { $dependency } { $installed } is installed{ $held_by ->
+    [none] {""}
+   *[other] {" "}({ $held_by })
+}
+error-dependency-pinned = '{ $plugin }' requires { $dependency } { $requirement }, but { $dependency } is pinned at { $version }. Run `adi plugin unpin { $dependency }` to let it be upgraded
 error-config = Configuration error: { $detail }
 error-io = IO error: { $detail }
 error-serialization = Serialization error: { $detail }
@@ -239,6 +384,20 @@ error-already-installed = Component '{ $name }' is already installed
 error-uninstallation-failed = Uninstallation failed for '{ $component }': { $reason }
 error-registry = Registry error: { $detail }
 error-plugin-not-found = Plugin not found: { $id }
+error-plugin-disabled = Plugin '{ $id }' is disabled after failing to load repeatedly. Run `adi plugin doctor { $id }` to check and re-enable it
+error-plugin-pinned = '{ $id }' is pinned at { $version }. Run `adi plugin unpin { $id }` to allow updates
 error-plugin-host = Plugin host error: { $detail }
 error-service = Service error: { $detail }
+error-checksum-mismatch = Checksum mismatch for '{ $id }': expected { $expected }, got { $actual }
+error-unsafe-archive-entry = Refusing to extract archive entry '{ $entry }': it would be written outside the target directory
+error-archive-too-large = Archive exceeds the extraction limit of { $limit } { $kind ->
+    [entries] entries
+   *[bytes] bytes
+} and was not installed
+error-insufficient-space = Not enough disk space: about { $needed } needed, { $available } available
+error-retries-exhausted = Gave up after { $attempts } attempts: { $detail }
+error-offline-not-cached = Offline: { $what } is not in the registry cache. Run the command once without --offline to cache it
+error-operation-in-progress = Another adi operation is in progress (holding { $path }). Try again once it finishes
+error-registry-unreachable = Can't reach the plugin registry at { $url }. Check your connection, point ADI_REGISTRY_URL at another registry, or use --offline to work from cached data
+error-plugin-timeout = Plugin { $plugin } did not respond within { $ms } ms (raise ADI_PLUGIN_TIMEOUT, or set it to 0 to wait indefinitely)
 error-other = Error: { $detail }
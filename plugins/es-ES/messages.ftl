@@ -57,7 +57,10 @@ plugin-update-already-latest = { $id } ya está en la última versión ({ $versi
 plugin-update-available = Actualizando { $id } de { $current } a { $latest }...
 plugin-update-downloading = Descargando { $id } v{ $version }...
 plugin-update-success = { $id } actualizado a v{ $version }
-plugin-update-all-start = Actualizando { $count } plugin(s)...
+plugin-update-all-start = Actualizando { $count } { $count ->
+    [one] plugin
+   *[other] plugins
+}...
 plugin-update-all-done = ¡Actualización completada!
 plugin-update-all-warning = Error al actualizar { $id }: { $error }
 
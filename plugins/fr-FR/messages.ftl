@@ -57,7 +57,10 @@ plugin-update-already-latest = { $id } est déjà à la dernière version ({ $ve
 plugin-update-available = Mise à jour de { $id } de { $current } vers { $latest }...
 plugin-update-downloading = Téléchargement de { $id } v{ $version }...
 plugin-update-success = { $id } mis à jour vers v{ $version }
-plugin-update-all-start = Mise à jour de { $count } plugin(s)...
+plugin-update-all-start = Mise à jour de { $count } { $count ->
+    [one] plugin
+   *[other] plugins
+}...
 plugin-update-all-done = Mise à jour terminée !
 plugin-update-all-warning = Échec de la mise à jour de { $id } : { $error }
 
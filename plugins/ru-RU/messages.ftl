@@ -45,10 +45,21 @@ plugin-install-already-installed = { $id } v{ $version } уже установл
 plugin-install-dependency = Установка зависимости: { $id }
 plugin-install-error-platform = Плагин { $id } не поддерживает платформу { $platform }
 plugin-install-pattern-searching = Поиск плагинов по шаблону "{ $pattern }"...
-plugin-install-pattern-found = Найдено { $count } плагин(ов) по шаблону
+plugin-install-pattern-found = Найдено { $count } { $count ->
+    [one] плагин
+    [few] плагина
+   *[many] плагинов
+} по шаблону
 plugin-install-pattern-none = Не найдено плагинов по шаблону "{ $pattern }"
-plugin-install-pattern-installing = Установка { $count } плагин(ов)...
-plugin-install-pattern-success = Успешно установлено { $count } плагин(ов)!
+plugin-install-pattern-installing = Установка { $count } { $count ->
+    [one] плагина
+   *[other] плагинов
+}...
+plugin-install-pattern-success = { $count ->
+    [one] Успешно установлен { $count } плагин!
+    [few] Успешно установлено { $count } плагина!
+   *[many] Успешно установлено { $count } плагинов!
+}
 plugin-install-pattern-failed = Не удалось установить:
 
 # Обновление плагинов
@@ -57,7 +68,10 @@ plugin-update-already-latest = { $id } уже последней версии ({
 plugin-update-available = Обновление { $id } с { $current } до { $latest }...
 plugin-update-downloading = Загрузка { $id } v{ $version }...
 plugin-update-success = Обновлён { $id } до v{ $version }
-plugin-update-all-start = Обновление { $count } плагин(ов)...
+plugin-update-all-start = Обновление { $count } { $count ->
+    [one] плагина
+   *[other] плагинов
+}...
 plugin-update-all-done = Обновление завершено!
 plugin-update-all-warning = Не удалось обновить { $id }: { $error }
 
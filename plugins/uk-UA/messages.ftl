@@ -45,10 +45,21 @@ plugin-install-already-installed = { $id } v{ $version } вже встановл
 plugin-install-dependency = Встановлення залежності: { $id }
 plugin-install-error-platform = Плагін { $id } не підтримує платформу { $platform }
 plugin-install-pattern-searching = Пошук плагінів за шаблоном "{ $pattern }"...
-plugin-install-pattern-found = Знайдено { $count } плагін(ів) за шаблоном
+plugin-install-pattern-found = Знайдено { $count } { $count ->
+    [one] плагін
+    [few] плагіни
+   *[many] плагінів
+} за шаблоном
 plugin-install-pattern-none = Не знайдено плагінів за шаблоном "{ $pattern }"
-plugin-install-pattern-installing = Встановлення { $count } плагін(ів)...
-plugin-install-pattern-success = Успішно встановлено { $count } плагін(ів)!
+plugin-install-pattern-installing = Встановлення { $count } { $count ->
+    [one] плагіна
+   *[other] плагінів
+}...
+plugin-install-pattern-success = { $count ->
+    [one] Успішно встановлено { $count } плагін!
+    [few] Успішно встановлено { $count } плагіни!
+   *[many] Успішно встановлено { $count } плагінів!
+}
 plugin-install-pattern-failed = Не вдалося встановити:
 
 # Оновлення плагінів
@@ -57,7 +68,10 @@ plugin-update-already-latest = { $id } вже останньої версії ({
 plugin-update-available = Оновлення { $id } з { $current } до { $latest }...
 plugin-update-downloading = Завантаження { $id } v{ $version }...
 plugin-update-success = Оновлено { $id } до v{ $version }
-plugin-update-all-start = Оновлення { $count } плагін(ів)...
+plugin-update-all-start = Оновлення { $count } { $count ->
+    [one] плагіна
+   *[other] плагінів
+}...
 plugin-update-all-done = Оновлення завершено!
 plugin-update-all-warning = Не вдалося оновити { $id }: { $error }
 
@@ -0,0 +1,408 @@
+//! Extraction of untrusted tar.gz and zip archives.
+//!
+//! Entries are unpacked one at a time. Each path must be relative, must not
+//! contain `..` and must land inside the destination directory. Symlinks and
+//! hard links may only point at something inside it too, and no entry is
+//! written through a symlink an earlier entry created (otherwise `a -> ..`
+//! followed by `a/b -> ..` climbs out one level per link). Anything else
+//! fails with [`InstallerError::UnsafeArchiveEntry`] before it is written.
+//!
+//! [`ExtractLimits`] bound the number of entries and the total unpacked size
+//! so a decompression bomb fails with [`InstallerError::ArchiveTooLarge`]
+//! instead of filling the disk. On any failure a destination directory the
+//! unpack created is removed again.
+//!
+//! Registry plugin installs are not covered: lib-plugin-host downloads and
+//! unpacks those itself, so [`check_extracted`] only measures the result
+//! afterwards and the install is rolled back if it is over the limits.
+
+use std::io::{Read, Seek};
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{InstallerError, Result};
+
+pub const DEFAULT_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+pub const DEFAULT_MAX_ENTRIES: u64 = 20_000;
+/// How far an archive may expand beyond its declared download size.
+const MAX_COMPRESSION_RATIO: u64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractLimits {
+    pub max_bytes: u64,
+    pub max_entries: u64,
+}
+
+impl Default for ExtractLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_entries: DEFAULT_MAX_ENTRIES,
+        }
+    }
+}
+
+impl ExtractLimits {
+    /// Defaults, overridden by $ADI_MAX_EXTRACT_BYTES and $ADI_MAX_EXTRACT_ENTRIES.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_bytes: crate::clienv::max_extract_bytes().unwrap_or(defaults.max_bytes),
+            max_entries: crate::clienv::max_extract_entries().unwrap_or(defaults.max_entries),
+        }
+    }
+
+    /// Tighten the byte limit for an archive whose download size is known
+    /// (the registry's `size_bytes`). Zero means unknown.
+    pub fn with_declared_size(mut self, size_bytes: u64) -> Self {
+        if size_bytes > 0 {
+            self.max_bytes = self.max_bytes.min(size_bytes.saturating_mul(MAX_COMPRESSION_RATIO));
+        }
+        self
+    }
+}
+
+/// Running totals checked against [`ExtractLimits`] while unpacking.
+struct Budget {
+    limits: ExtractLimits,
+    bytes: u64,
+    entries: u64,
+}
+
+impl Budget {
+    fn new(limits: &ExtractLimits) -> Self {
+        Self {
+            limits: *limits,
+            bytes: 0,
+            entries: 0,
+        }
+    }
+
+    fn add_entry(&mut self) -> Result<()> {
+        self.entries += 1;
+        if self.entries > self.limits.max_entries {
+            return Err(too_large("entries", self.limits.max_entries));
+        }
+        Ok(())
+    }
+
+    fn add_bytes(&mut self, bytes: u64) -> Result<()> {
+        self.bytes = self.bytes.saturating_add(bytes);
+        if self.bytes > self.limits.max_bytes {
+            return Err(too_large("bytes", self.limits.max_bytes));
+        }
+        Ok(())
+    }
+
+    fn remaining_bytes(&self) -> u64 {
+        self.limits.max_bytes.saturating_sub(self.bytes)
+    }
+}
+
+/// Unpack a gzipped tarball into `dest`, creating it if needed.
+pub fn unpack_tar_gz(reader: impl Read, dest: &Path, limits: &ExtractLimits) -> Result<()> {
+    unpack_tar(tar::Archive::new(flate2::read::GzDecoder::new(reader)), dest, limits)
+}
+
+pub fn unpack_tar<R: Read>(archive: tar::Archive<R>, dest: &Path, limits: &ExtractLimits) -> Result<()> {
+    remove_on_error(dest, || unpack_tar_entries(archive, dest, limits))
+}
+
+fn unpack_tar_entries<R: Read>(mut archive: tar::Archive<R>, dest: &Path, limits: &ExtractLimits) -> Result<()> {
+    tracing::trace!(dest = %dest.display(), "Unpacking tar archive");
+    std::fs::create_dir_all(dest)?;
+    let mut budget = Budget::new(limits);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        budget.add_entry()?;
+        // A tar entry's data is exactly its header size, so this is checked before anything is written.
+        budget.add_bytes(entry.size())?;
+        let path = entry.path()?.into_owned();
+        let relative = entry_path(&path)?;
+        no_symlink_ancestors(dest, &relative, &path)?;
+
+        let kind = entry.header().entry_type();
+        if kind.is_symlink() || kind.is_hard_link() {
+            let target = entry.link_name()?.ok_or_else(|| unsafe_entry(&path))?;
+            // Symlinks resolve against their own directory, hard links against the archive root.
+            let base = if kind.is_symlink() {
+                relative.parent().unwrap_or(Path::new(""))
+            } else {
+                Path::new("")
+            };
+            link_target(&path, base, &target)?;
+        }
+
+        let out = dest.join(&relative);
+        if let Some(parent) = out.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        tracing::trace!(entry = %path.display(), "Extracting archive entry");
+        entry.unpack(&out)?;
+    }
+    Ok(())
+}
+
+/// Unpack a zip archive into `dest`, creating it if needed.
+pub fn unpack_zip(reader: impl Read + Seek, dest: &Path, limits: &ExtractLimits) -> Result<()> {
+    remove_on_error(dest, || unpack_zip_entries(reader, dest, limits))
+}
+
+fn unpack_zip_entries(reader: impl Read + Seek, dest: &Path, limits: &ExtractLimits) -> Result<()> {
+    tracing::trace!(dest = %dest.display(), "Unpacking zip archive");
+    let zip_err = |e: zip::result::ZipError| InstallerError::Other(e.to_string());
+    let mut archive = zip::ZipArchive::new(reader).map_err(zip_err)?;
+    if archive.len() as u64 > limits.max_entries {
+        return Err(too_large("entries", limits.max_entries));
+    }
+    std::fs::create_dir_all(dest)?;
+    let mut budget = Budget::new(limits);
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(zip_err)?;
+        budget.add_entry()?;
+        let path = PathBuf::from(file.name());
+        let relative = entry_path(&path)?;
+        no_symlink_ancestors(dest, &relative, &path)?;
+        let out = dest.join(&relative);
+
+        if file.is_dir() {
+            std::fs::create_dir_all(&out)?;
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // Zip stores a symlink as a file whose contents are the target.
+        let is_symlink = file.unix_mode().is_some_and(|mode| mode & 0o170000 == 0o120000);
+        if is_symlink {
+            let mut target = String::new();
+            (&mut file).take(budget.remaining_bytes() + 1).read_to_string(&mut target)?;
+            budget.add_bytes(target.len() as u64)?;
+            link_target(&path, relative.parent().unwrap_or(Path::new("")), Path::new(&target))?;
+            tracing::trace!(entry = %path.display(), target = %target, "Extracting symlink");
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&target, &out)?;
+            #[cfg(not(unix))]
+            std::fs::write(&out, target)?;
+            continue;
+        }
+
+        tracing::trace!(entry = %path.display(), "Extracting archive entry");
+        // The size in the zip header can lie; count what actually decompresses.
+        let mut writer = std::fs::File::create(&out)?;
+        let written = std::io::copy(&mut (&mut file).take(budget.remaining_bytes() + 1), &mut writer)?;
+        budget.add_bytes(written)?;
+
+        #[cfg(unix)]
+        if let Some(mode) = file.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&out, std::fs::Permissions::from_mode(mode & 0o777))?;
+        }
+    }
+    Ok(())
+}
+
+/// Check an already extracted directory against `limits` (for archives
+/// unpacked by code outside this module). Symlinks are counted, not followed.
+pub fn check_extracted(dir: &Path, limits: &ExtractLimits) -> Result<()> {
+    let mut budget = Budget::new(limits);
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            budget.add_entry()?;
+            let meta = entry.path().symlink_metadata()?;
+            if meta.is_dir() {
+                pending.push(entry.path());
+            } else {
+                budget.add_bytes(meta.len())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run `unpack`, removing `dest` again if it didn't exist before and the
+/// unpack failed partway.
+fn remove_on_error(dest: &Path, unpack: impl FnOnce() -> Result<()>) -> Result<()> {
+    let existed = dest.exists();
+    let result = unpack();
+    if result.is_err() && !existed {
+        tracing::trace!(dest = %dest.display(), "Removing partially unpacked archive");
+        let _ = std::fs::remove_dir_all(dest);
+    }
+    result
+}
+
+/// Normalized relative path of an archive entry. Absolute paths and `..`
+/// components are rejected; `.` components are dropped.
+pub fn entry_path(path: &Path) -> Result<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(unsafe_entry(path));
+            }
+        }
+    }
+    if relative.as_os_str().is_empty() {
+        return Err(unsafe_entry(path));
+    }
+    Ok(relative)
+}
+
+/// Check that a link `target`, resolved against `base` (relative to the
+/// archive root), stays inside the archive root. `..` is allowed as long as
+/// it never climbs above the root.
+fn link_target(entry: &Path, base: &Path, target: &Path) -> Result<()> {
+    let mut depth = 0usize;
+    for component in base.join(target).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => depth = depth.checked_sub(1).ok_or_else(|| unsafe_entry(entry))?,
+            Component::RootDir | Component::Prefix(_) => return Err(unsafe_entry(entry)),
+        }
+    }
+    Ok(())
+}
+
+/// Refuse to write `relative` if any of its parent directories inside `dest`
+/// is a symlink, i.e. never follow links while extracting.
+fn no_symlink_ancestors(dest: &Path, relative: &Path, entry: &Path) -> Result<()> {
+    for ancestor in relative.ancestors().skip(1) {
+        if ancestor.as_os_str().is_empty() {
+            break;
+        }
+        let is_symlink = std::fs::symlink_metadata(dest.join(ancestor))
+            .map(|meta| meta.file_type().is_symlink())
+            .unwrap_or(false);
+        if is_symlink {
+            return Err(unsafe_entry(entry));
+        }
+    }
+    Ok(())
+}
+
+fn too_large(kind: &str, limit: u64) -> InstallerError {
+    tracing::trace!(kind = %kind, limit = limit, "Archive exceeds extraction limit");
+    InstallerError::ArchiveTooLarge {
+        kind: kind.to_string(),
+        limit,
+    }
+}
+
+fn unsafe_entry(path: &Path) -> InstallerError {
+    tracing::trace!(entry = %path.display(), "Rejecting unsafe archive entry");
+    InstallerError::UnsafeArchiveEntry {
+        entry: path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A tarball with one entry whose raw name is written straight into the
+    /// header, bypassing the checks `tar::Builder` does on paths.
+    fn tarball(name: &str, kind: tar::EntryType, link: Option<&str>, data: &[u8]) -> Vec<u8> {
+        tarball_of(&[(name, kind, link, data)])
+    }
+
+    fn tarball_of(entries: &[(&str, tar::EntryType, Option<&str>, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, kind, link, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            let raw = &mut header.as_gnu_mut().unwrap().name;
+            raw[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_entry_type(*kind);
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            if let Some(link) = link {
+                header.set_link_name(link).unwrap();
+            }
+            header.set_cksum();
+            builder.append(&header, *data).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_entry_path_rejects_traversal() {
+        assert_eq!(entry_path(Path::new("./bin/plugin.so")).unwrap(), PathBuf::from("bin/plugin.so"));
+        assert!(entry_path(Path::new("../../.ssh/authorized_keys")).is_err());
+        assert!(entry_path(Path::new("lib/../../escape")).is_err());
+        assert!(entry_path(Path::new("/etc/passwd")).is_err());
+        assert!(entry_path(Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn test_unpack_rejects_traversal_entry() {
+        let root = tempfile::tempdir().unwrap();
+        let dest = root.path().join("plugins").join("adi.test");
+        let archive = tarball("../../evil.txt", tar::EntryType::Regular, None, b"pwned");
+
+        let err = unpack_tar(tar::Archive::new(archive.as_slice()), &dest, &ExtractLimits::default()).unwrap_err();
+        assert!(matches!(err, InstallerError::UnsafeArchiveEntry { .. }));
+        assert!(!root.path().join("evil.txt").exists());
+    }
+
+    #[test]
+    fn test_unpack_checks_symlink_targets() {
+        let dest = tempfile::tempdir().unwrap();
+        let escaping = tarball("lib/keys", tar::EntryType::Symlink, Some("../../.ssh"), b"");
+        assert!(unpack_tar(tar::Archive::new(escaping.as_slice()), dest.path(), &ExtractLimits::default()).is_err());
+
+        let inside = tarball("lib/current", tar::EntryType::Symlink, Some("../bin"), b"");
+        unpack_tar(tar::Archive::new(inside.as_slice()), dest.path(), &ExtractLimits::default()).unwrap();
+
+        let regular = tarball("./bin/plugin.so", tar::EntryType::Regular, None, b"elf");
+        unpack_tar(tar::Archive::new(regular.as_slice()), dest.path(), &ExtractLimits::default()).unwrap();
+        assert_eq!(std::fs::read(dest.path().join("bin/plugin.so")).unwrap(), b"elf");
+    }
+
+    #[test]
+    fn test_unpack_does_not_write_through_symlinks() {
+        let root = tempfile::tempdir().unwrap();
+        let dest = root.path().join("plugins").join("adi.test");
+        // Each link looks harmless on its own, but a/b/c resolves to dest/..
+        let archive = tarball_of(&[
+            ("a/b", tar::EntryType::Symlink, Some(".."), b""),
+            ("a/b/c", tar::EntryType::Symlink, Some(".."), b""),
+            ("a/b/c/evil.txt", tar::EntryType::Regular, None, b"pwned"),
+        ]);
+
+        let err = unpack_tar(tar::Archive::new(archive.as_slice()), &dest, &ExtractLimits::default()).unwrap_err();
+        assert!(matches!(err, InstallerError::UnsafeArchiveEntry { ref entry } if entry == "a/b/c"));
+        assert!(!root.path().join("plugins").join("evil.txt").exists());
+    }
+
+    #[test]
+    fn test_unpack_enforces_limits() {
+        let root = tempfile::tempdir().unwrap();
+        let dest = root.path().join("adi.test");
+        let archive = tarball_of(&[
+            ("a.txt", tar::EntryType::Regular, None, &[0; 600]),
+            ("b.txt", tar::EntryType::Regular, None, &[0; 600]),
+        ]);
+
+        let few_entries = ExtractLimits { max_bytes: 10_000, max_entries: 1 };
+        let err = unpack_tar(tar::Archive::new(archive.as_slice()), &dest, &few_entries).unwrap_err();
+        assert!(matches!(err, InstallerError::ArchiveTooLarge { ref kind, limit: 1 } if kind == "entries"));
+        assert!(!dest.exists());
+
+        let small = ExtractLimits::default().with_declared_size(10);
+        assert_eq!(small.max_bytes, 10 * MAX_COMPRESSION_RATIO);
+        let err = unpack_tar(tar::Archive::new(archive.as_slice()), &dest, &small).unwrap_err();
+        assert!(matches!(err, InstallerError::ArchiveTooLarge { ref kind, .. } if kind == "bytes"));
+        assert!(!dest.exists());
+
+        unpack_tar(tar::Archive::new(archive.as_slice()), &dest, &ExtractLimits::default()).unwrap();
+        check_extracted(&dest, &ExtractLimits::default()).unwrap();
+        assert!(check_extracted(&dest, &small).is_err());
+    }
+}
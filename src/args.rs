@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "adi")]
@@ -9,6 +9,34 @@ pub(crate) struct Cli {
     #[arg(long, global = true)]
     pub lang: Option<String>,
 
+    /// Use a different plugins directory. Can also be set via ADI_PLUGINS_DIR env var.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub plugins_dir: Option<std::path::PathBuf>,
+
+    /// Use throwaway plugin and cache dirs that are removed on exit (for CI)
+    #[arg(long, global = true, conflicts_with = "plugins_dir")]
+    pub ephemeral: bool,
+
+    /// Max parallel downloads/updates for bulk operations (1 = serial). Can also be set via ADI_JOBS env var.
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
+
+    /// Fit tables to this many columns (defaults to the terminal width). Can also be set via ADI_WIDTH env var.
+    #[arg(long, global = true, value_name = "COLS")]
+    pub width: Option<u16>,
+
+    /// Run even as root (not recommended). Can also be set via ADI_ALLOW_ROOT env var.
+    #[arg(long, global = true)]
+    pub allow_root: bool,
+
+    /// Answer registry lookups from the local cache only, without network access. Can also be set via ADI_OFFLINE env var.
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Print JSON instead of tables (plugin list/installed/search, daemon status/services); list and search print JSON Lines, one entry per line. Can also be set via ADI_JSON env var.
+    #[arg(long, global = true)]
+    pub json: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -20,6 +48,18 @@ pub(crate) enum Commands {
         /// Force update even if already on latest version
         #[arg(long)]
         force: bool,
+
+        /// Skip the checksum check and the --version run of the downloaded binary (emergencies only)
+        #[arg(long)]
+        no_verify: bool,
+
+        /// Release channel to update from; remembered for later updates
+        #[arg(long, value_enum)]
+        channel: Option<cli::self_update::Channel>,
+
+        /// Install this exact CLI version instead of the latest (downgrades allowed)
+        #[arg(long, value_name = "VERSION", conflicts_with = "channel")]
+        to: Option<String>,
     },
 
     /// Start local ADI server for browser connection
@@ -37,6 +77,14 @@ pub(crate) enum Commands {
 
     /// Run a plugin's CLI interface
     Run {
+        /// JSON object merged into the context passed to the plugin
+        #[arg(long, value_name = "FILE")]
+        context_file: Option<std::path::PathBuf>,
+
+        /// Append newline-separated args from FILE (also: `@FILE` in args, $ADI_PLUGIN_ARGS_FILE)
+        #[arg(long, value_name = "FILE")]
+        args_file: Option<std::path::PathBuf>,
+
         /// Plugin ID to run (shows available plugins if omitted)
         plugin_id: Option<String>,
 
@@ -82,20 +130,54 @@ pub(crate) enum Commands {
 
     /// Manage background daemon and services
     Daemon {
+        /// Seconds to wait for the daemon to answer before reporting it as not responding (default: $ADI_DAEMON_TIMEOUT or 5)
+        #[arg(long, global = true, value_name = "SECS")]
+        timeout: Option<u64>,
+
         #[command(subcommand)]
         command: DaemonCommands,
     },
 
+    /// Open an ADI directory in the file manager
+    Open {
+        /// Directory to open
+        target: OpenTarget,
+
+        /// Print the path instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Manage shell completion scripts
+    Completions {
+        #[command(subcommand)]
+        command: CompletionsCommands,
+    },
+
     /// Plugin-provided commands (dynamically discovered from installed plugins)
     #[command(external_subcommand)]
     External(Vec<String>),
 }
 
+#[derive(Subcommand)]
+pub(crate) enum CompletionsCommands {
+    /// Regenerate installed completion scripts and syntax-check them
+    Regenerate,
+
+    /// Remove installed completion scripts and the rc file setup.
+    /// They are set up again the next time adi runs, so do this when removing adi.
+    Uninstall,
+}
+
 #[derive(Subcommand)]
 pub(crate) enum DaemonCommands {
     /// Run the daemon in foreground (for debugging)
     #[command(visible_alias = "fg")]
-    Run,
+    Run {
+        /// Log output format (default: $ADI_LOG_FORMAT or text)
+        #[arg(long, value_enum)]
+        log_format: Option<cli::logging::LogFormat>,
+    },
 
     /// Start the daemon in background
     #[command(visible_alias = "up")]
@@ -112,6 +194,10 @@ pub(crate) enum DaemonCommands {
     /// Restart the daemon
     Restart,
 
+    /// Rescan installed plugins for added, changed or removed services
+    /// without restarting the daemon or its running services
+    Reload,
+
     /// Show daemon and services status
     #[command(visible_alias = "ps")]
     Status,
@@ -143,7 +229,21 @@ pub(crate) enum DaemonCommands {
 
     /// List all registered services
     #[command(visible_alias = "ls")]
-    Services,
+    Services {
+        /// List services declared by installed plugin manifests (no daemon needed)
+        #[arg(long)]
+        declared: bool,
+    },
+
+    /// Live dashboard of services with memory and CPU usage (q to quit)
+    Top {
+        /// Refresh interval in seconds
+        #[arg(short = 'i', long, default_value = "1")]
+        interval: u64,
+    },
+
+    /// Show memory and CPU usage of running services
+    Metrics,
 
     /// View service logs
     Logs {
@@ -157,6 +257,20 @@ pub(crate) enum DaemonCommands {
         /// Follow log output (stream continuously)
         #[arg(short, long)]
         follow: bool,
+
+        /// Minimum log level (trace, debug, info, warn, error, fatal)
+        #[arg(long)]
+        level: Option<cli::daemon::log_filter::LogLevel>,
+
+        /// Only lines since a duration ago (10m, 1h30m) or a time (2024-05-01T12:00:00Z)
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Print the path of a service's log file
+    LogPath {
+        /// Service name
+        service: String,
     },
 
     /// Run a specific plugin's daemon service (internal, used by daemon supervisor)
@@ -166,7 +280,11 @@ pub(crate) enum DaemonCommands {
     },
 
     /// Set up system users and privileges for the daemon
-    Setup,
+    Setup {
+        /// Command the daemon may run as root for plugins, by name or full path (repeatable; replaces the current list)
+        #[arg(long = "sudo-allow", value_name = "COMMAND")]
+        sudo_allow: Vec<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -179,6 +297,33 @@ pub(crate) enum ConfigCommands {
         /// Set to "true" to enable or "false" to disable
         enable: String,
     },
+
+    /// Print one setting (empty when unset)
+    Get {
+        /// language, theme, power_user, jobs, registry_url or channel
+        key: String,
+    },
+
+    /// Change one setting
+    Set {
+        /// language, theme, power_user, jobs, registry_url or channel
+        key: String,
+
+        /// New value, checked against what the key accepts
+        value: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum OpenTarget {
+    /// Configuration directory (config.toml, credentials.toml)
+    Config,
+    /// Data directory (daemon socket, PID file)
+    Data,
+    /// Installed plugins
+    Plugins,
+    /// Daemon logs
+    Logs,
 }
 
 #[derive(Subcommand)]
@@ -187,6 +332,22 @@ pub(crate) enum PluginCommands {
     Search {
         /// Search query
         query: String,
+
+        /// Only show packages
+        #[arg(long, conflicts_with = "plugin")]
+        package: bool,
+
+        /// Only show plugins
+        #[arg(long)]
+        plugin: bool,
+
+        /// Only show plugins of this type (e.g. translation)
+        #[arg(long = "type", value_name = "PLUGIN_TYPE")]
+        plugin_type: Option<String>,
+
+        /// Only show results with this tag (repeatable; all must match)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
     },
 
     /// List all available plugins
@@ -195,24 +356,123 @@ pub(crate) enum PluginCommands {
     /// List installed plugins
     Installed,
 
+    /// Show registry details for a plugin and whether it is installed
+    Info {
+        /// Plugin ID
+        plugin_id: String,
+    },
+
     /// Install a plugin or multiple plugins matching a pattern
     Install {
         /// Plugin ID (e.g., com.example.my-plugin) or pattern (e.g., adi.lang.*)
-        plugin_id: String,
+        #[arg(required_unless_present_any = ["locked", "from"])]
+        plugin_id: Option<String>,
 
         /// Specific version to install
         #[arg(short, long)]
         version: Option<String>,
+
+        /// Also install optional dependencies declared by the plugin
+        #[arg(long)]
+        optional_deps: bool,
+
+        /// Use a compatible system-wide copy of the tool a plugin wraps, if it declares one
+        #[arg(long)]
+        prefer_system: bool,
+
+        /// Install all plugins matching a pattern without prompting
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Continue an interrupted pattern install with the plugins it hadn't installed yet
+        #[arg(long, conflicts_with = "version")]
+        resume: bool,
+
+        /// Retry failed pattern installs up to N times without prompting
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        retry: u32,
+
+        /// Install exactly the plugins in a lock file, verifying checksums
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["plugin_id", "version"])]
+        locked: Option<std::path::PathBuf>,
+
+        /// Install a local build: a directory with plugin.toml or a .tar.gz of one
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["plugin_id", "version", "locked", "resume"])]
+        from: Option<std::path::PathBuf>,
+
+        /// Resolve what would be installed (with dependencies) and print the plan without downloading
+        #[arg(long, conflicts_with_all = ["locked", "from", "resume"])]
+        dry_run: bool,
+    },
+
+    /// Write a lock file of installed plugins with checksums
+    Freeze {
+        /// Lock file to write
+        #[arg(short, long, value_name = "FILE", default_value = cli::plugin_lock::DEFAULT_LOCK_FILE)]
+        output: std::path::PathBuf,
+
+        /// Print the lock file instead of writing it
+        #[arg(long, conflicts_with = "output")]
+        stdout: bool,
+    },
+
+    /// Install exactly the plugins and versions in a lock file, verifying checksums
+    Restore {
+        /// Lock file to restore from
+        #[arg(default_value = cli::plugin_lock::DEFAULT_LOCK_FILE)]
+        file: std::path::PathBuf,
+
+        /// Fail if plugins not listed in the lock file are installed
+        #[arg(long)]
+        locked: bool,
     },
 
     /// Update a plugin to latest version
     Update {
         /// Plugin ID
         plugin_id: String,
+
+        /// Show release notes for the new version(s) before updating
+        #[arg(long)]
+        changelog: bool,
+
+        /// Switch to this exact version instead of latest (can downgrade)
+        #[arg(long, value_name = "VERSION")]
+        to: Option<String>,
+
+        /// Print what would be downloaded without updating
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show release notes for a plugin (versions newer than installed by default)
+    Changelog {
+        /// Plugin ID
+        plugin_id: String,
+
+        /// Show notes for this version only
+        #[arg(short, long)]
+        version: Option<String>,
     },
 
     /// Update all installed plugins
-    UpdateAll,
+    UpdateAll {
+        /// Print what would be downloaded without updating
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Pin a plugin at its installed version so updates leave it alone
+    Pin {
+        /// Plugin ID
+        plugin_id: String,
+    },
+
+    /// Allow a pinned plugin to be updated again
+    Unpin {
+        /// Plugin ID
+        plugin_id: String,
+    },
 
     /// Uninstall a plugin
     Uninstall {
@@ -220,9 +480,40 @@ pub(crate) enum PluginCommands {
         plugin_id: String,
     },
 
+    /// Check that an installed plugin loads, re-enabling it if it was auto-disabled
+    Doctor {
+        /// Plugin ID
+        plugin_id: String,
+    },
+
+    /// Run a plugin's self-test (or check it loads if it declares none)
+    Test {
+        /// Plugin ID
+        plugin_id: String,
+    },
+
     /// Show installation path for a plugin
     Path {
         /// Plugin ID
         plugin_id: String,
     },
+
+    /// Delete old version dirs left next to each plugin's active version
+    Prune {
+        /// List what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Manage the local cache of registry responses
+    Cache {
+        #[command(subcommand)]
+        command: PluginCacheCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum PluginCacheCommands {
+    /// Delete cached registry responses so the next lookup refetches them
+    Clear,
 }
@@ -10,7 +10,26 @@ env_vars! {
     Lang               => "LANG",
     AdiAutoInstall     => "ADI_AUTO_INSTALL",
     AdiRegistryUrl     => "ADI_REGISTRY_URL",
+    AdiRegistryToken   => "ADI_REGISTRY_TOKEN",
     SignalingServerUrl  => "SIGNALING_SERVER_URL",
+    AdiPluginsDir      => "ADI_PLUGINS_DIR",
+    AdiCacheDir        => "ADI_CACHE_DIR",
+    AdiJobs            => "ADI_JOBS",
+    AdiInstallConcurrency => "ADI_INSTALL_CONCURRENCY",
+    AdiWidth           => "ADI_WIDTH",
+    AdiPluginArgsFile  => "ADI_PLUGIN_ARGS_FILE",
+    AdiAllowRoot       => "ADI_ALLOW_ROOT",
+    AdiUserAgentSuffix => "ADI_USER_AGENT_SUFFIX",
+    AdiProxy           => "ADI_PROXY",
+    AdiMaxExtractBytes => "ADI_MAX_EXTRACT_BYTES",
+    AdiMaxExtractEntries => "ADI_MAX_EXTRACT_ENTRIES",
+    AdiDownloadRetries => "ADI_DOWNLOAD_RETRIES",
+    AdiLogFormat       => "ADI_LOG_FORMAT",
+    AdiOffline         => "ADI_OFFLINE",
+    AdiRegistryCacheTtl => "ADI_REGISTRY_CACHE_TTL",
+    AdiLockTimeout     => "ADI_LOCK_TIMEOUT",
+    AdiJson            => "ADI_JSON",
+    AdiPluginTimeout   => "ADI_PLUGIN_TIMEOUT",
     // Daemon env vars
     AdiDaemonSocket    => "ADI_DAEMON_SOCKET",
     AdiDaemonPid       => "ADI_DAEMON_PID",
@@ -18,6 +37,11 @@ env_vars! {
     AdiUser            => "ADI_USER",
     AdiRootUser        => "ADI_ROOT_USER",
     AdiDaemonTcpPort   => "ADI_DAEMON_TCP_PORT",
+    AdiDaemonRefreshSecs => "ADI_DAEMON_REFRESH_SECS",
+    AdiDaemonRestoreServices => "ADI_DAEMON_RESTORE_SERVICES",
+    AdiDaemonTimeout   => "ADI_DAEMON_TIMEOUT",
+    AdiServiceLogMaxBytes => "ADI_SERVICE_LOG_MAX_BYTES",
+    AdiServiceLogKeep  => "ADI_SERVICE_LOG_KEEP",
 }
 
 const FALLBACK_CONFIG_DIR: &str = "~/.config";
@@ -25,6 +49,9 @@ const ADI_SUBDIR: &str = "adi";
 const DEFAULT_REGISTRY_URL: &str = "https://adi-plugin-registry.the-ihor.com";
 const DEFAULT_SIGNALING_URL: &str = "wss://adi.the-ihor.com/api/signaling/ws";
 pub const CLI_PLUGIN_PREFIX: &str = "adi.cli.";
+const DEFAULT_DOWNLOAD_RETRIES: u32 = 3;
+const HTTP_CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+const HTTP_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
 
 /// ADI config directory ($ADI_CONFIG_DIR or ~/.config/adi)
 pub fn config_dir() -> PathBuf {
@@ -91,20 +118,170 @@ pub fn auto_install_disabled() -> bool {
     disabled
 }
 
-/// Plugin registry URL ($ADI_REGISTRY_URL or default)
+/// Attempts per registry request before giving up on network errors ($ADI_DOWNLOAD_RETRIES, default 3)
+pub fn download_retries() -> u32 {
+    let attempts = env_opt(EnvVar::AdiDownloadRetries.as_str())
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_DOWNLOAD_RETRIES)
+        .max(1);
+    tracing::trace!(attempts = attempts, "Download retry attempts");
+    attempts
+}
+
+/// Log output format ($ADI_LOG_FORMAT=text|json, default text)
+pub fn log_format() -> crate::logging::LogFormat {
+    env_opt(EnvVar::AdiLogFormat.as_str())
+        .and_then(|v| crate::logging::LogFormat::parse(&v))
+        .unwrap_or_default()
+}
+
+/// Cap on the total unpacked size of one archive ($ADI_MAX_EXTRACT_BYTES)
+pub fn max_extract_bytes() -> Option<u64> {
+    let max = env_opt(EnvVar::AdiMaxExtractBytes.as_str()).and_then(|v| v.parse().ok());
+    tracing::trace!(max = ?max, "ADI_MAX_EXTRACT_BYTES env var");
+    max
+}
+
+/// Cap on the number of entries in one archive ($ADI_MAX_EXTRACT_ENTRIES)
+pub fn max_extract_entries() -> Option<u64> {
+    let max = env_opt(EnvVar::AdiMaxExtractEntries.as_str()).and_then(|v| v.parse().ok());
+    tracing::trace!(max = ?max, "ADI_MAX_EXTRACT_ENTRIES env var");
+    max
+}
+
+/// Whether running as root is allowed ($ADI_ALLOW_ROOT=1|true|yes|on)
+pub fn allow_root() -> bool {
+    let allow = env_opt(EnvVar::AdiAllowRoot.as_str()).is_some_and(|v| lib_env_parse::is_truthy(&v));
+    tracing::trace!(allow = allow, "ADI_ALLOW_ROOT env var");
+    allow
+}
+
+/// Whether registry lookups must come from the local cache ($ADI_OFFLINE=1|true|yes|on)
+pub fn offline() -> bool {
+    let offline = env_opt(EnvVar::AdiOffline.as_str()).is_some_and(|v| lib_env_parse::is_truthy(&v));
+    tracing::trace!(offline = offline, "ADI_OFFLINE env var");
+    offline
+}
+
+/// How long cached registry responses are used before refetching
+/// ($ADI_REGISTRY_CACHE_TTL in seconds, default one hour; 0 always refetches)
+pub fn registry_cache_ttl() -> std::time::Duration {
+    let ttl = env_opt(EnvVar::AdiRegistryCacheTtl.as_str())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(std::time::Duration::from_secs)
+        .unwrap_or(crate::registry_cache::DEFAULT_TTL);
+    tracing::trace!(ttl = ?ttl, "Resolved registry cache TTL");
+    ttl
+}
+
+/// How long installs, updates and uninstalls wait for another adi process
+/// to release the plugins dir lock ($ADI_LOCK_TIMEOUT in seconds, default 60;
+/// 0 fails straight away)
+pub fn lock_timeout() -> std::time::Duration {
+    let timeout = env_opt(EnvVar::AdiLockTimeout.as_str())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(std::time::Duration::from_secs)
+        .unwrap_or(crate::state_lock::DEFAULT_TIMEOUT);
+    tracing::trace!(timeout = ?timeout, "Resolved plugin state lock timeout");
+    timeout
+}
+
+/// How long a single plugin command may run before it is abandoned
+/// ($ADI_PLUGIN_TIMEOUT in seconds, default 30; 0 means no limit)
+pub fn plugin_invoke_timeout() -> Option<std::time::Duration> {
+    let secs = env_opt(EnvVar::AdiPluginTimeout.as_str())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(crate::plugin_runtime::DEFAULT_INVOKE_TIMEOUT.as_secs());
+    let timeout = (secs > 0).then(|| std::time::Duration::from_secs(secs));
+    tracing::trace!(timeout = ?timeout, "Resolved plugin invoke timeout");
+    timeout
+}
+
+/// Whether listing commands print JSON instead of tables ($ADI_JSON=1|true|yes|on)
+pub fn json_output() -> bool {
+    env_opt(EnvVar::AdiJson.as_str()).is_some_and(|v| lib_env_parse::is_truthy(&v))
+}
+
+/// Turn on JSON output for this process (used by `--json`)
+pub fn set_json_output() {
+    std::env::set_var(EnvVar::AdiJson.as_str(), "1");
+}
+
+/// Turn on offline mode for this process (used by `--offline`)
+pub fn set_offline() {
+    std::env::set_var(EnvVar::AdiOffline.as_str(), "1");
+}
+
+const DEFAULT_INSTALL_CONCURRENCY: usize = 4;
+
+/// Parallelism for bulk plugin operations (env var > config > CPU count)
+pub fn jobs() -> usize {
+    let jobs = configured_jobs()
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+        .max(1);
+    tracing::trace!(jobs = jobs, "Resolved job count");
+    jobs
+}
+
+/// Concurrent downloads for pattern installs ($ADI_INSTALL_CONCURRENCY > jobs setting > 4)
+pub fn install_concurrency() -> usize {
+    let concurrency = env_opt(EnvVar::AdiInstallConcurrency.as_str())
+        .and_then(|v| v.parse::<usize>().ok())
+        .or_else(configured_jobs)
+        .unwrap_or(DEFAULT_INSTALL_CONCURRENCY)
+        .max(1);
+    tracing::trace!(concurrency = concurrency, "Resolved install concurrency");
+    concurrency
+}
+
+/// Explicit `--jobs` / $ADI_JOBS, else the `jobs` config value
+fn configured_jobs() -> Option<usize> {
+    env_opt(EnvVar::AdiJobs.as_str())
+        .and_then(|v| v.parse::<usize>().ok())
+        .or_else(|| crate::user_config::UserConfig::load().ok().and_then(|c| c.jobs))
+}
+
+/// Override bulk-operation parallelism for this process (used by `--jobs`)
+pub fn set_jobs(jobs: usize) {
+    std::env::set_var(EnvVar::AdiJobs.as_str(), jobs.to_string());
+}
+
+/// Table output width override ($ADI_WIDTH)
+pub fn output_width() -> Option<usize> {
+    let width = env_opt(EnvVar::AdiWidth.as_str())
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&w| w > 0);
+    tracing::trace!(width = ?width, "ADI_WIDTH env var");
+    width
+}
+
+/// Override table output width for this process (used by `--width`)
+pub fn set_output_width(width: usize) {
+    std::env::set_var(EnvVar::AdiWidth.as_str(), width.to_string());
+}
+
+/// Plugin registry URL ($ADI_REGISTRY_URL > `registry_url` in config > default)
 pub fn registry_url() -> String {
-    let url = env_or(EnvVar::AdiRegistryUrl.as_str(), DEFAULT_REGISTRY_URL);
+    let url = registry_url_override().unwrap_or_else(|| DEFAULT_REGISTRY_URL.to_string());
     tracing::trace!(url = %url, "Registry URL");
     url
 }
 
-/// Optional plugin registry URL override ($ADI_REGISTRY_URL)
+/// Optional plugin registry URL override ($ADI_REGISTRY_URL > `registry_url` in config)
 pub fn registry_url_override() -> Option<String> {
-    let val = env_opt(EnvVar::AdiRegistryUrl.as_str());
+    let val = env_opt(EnvVar::AdiRegistryUrl.as_str())
+        .or_else(|| crate::user_config::UserConfig::load().ok().and_then(|c| c.registry_url));
     tracing::trace!(value = ?val, "Registry URL override");
     val
 }
 
+/// Registry auth token ($ADI_REGISTRY_TOKEN). Never logged.
+pub fn registry_token() -> Option<String> {
+    let val = env_opt(EnvVar::AdiRegistryToken.as_str());
+    tracing::trace!(set = val.is_some(), "ADI_REGISTRY_TOKEN env var");
+    val
+}
+
 /// Signaling server URL ($SIGNALING_SERVER_URL or default)
 pub fn signaling_url() -> String {
     let url = env_or(EnvVar::SignalingServerUrl.as_str(), DEFAULT_SIGNALING_URL);
@@ -112,6 +289,55 @@ pub fn signaling_url() -> String {
     url
 }
 
+/// Extra user-agent text identifying an organization or deployment ($ADI_USER_AGENT_SUFFIX)
+pub fn user_agent_suffix() -> Option<String> {
+    let val = env_opt(EnvVar::AdiUserAgentSuffix.as_str());
+    tracing::trace!(value = ?val, "ADI_USER_AGENT_SUFFIX env var");
+    val
+}
+
+/// Proxy for all outbound HTTP, overriding HTTP(S)_PROXY ($ADI_PROXY)
+pub fn proxy() -> Option<String> {
+    let val = env_opt(EnvVar::AdiProxy.as_str());
+    tracing::trace!(set = val.is_some(), "ADI_PROXY env var");
+    val
+}
+
+/// Make $ADI_PROXY win over HTTP_PROXY/HTTPS_PROXY for this process, so
+/// clients built inside the plugin host and GitHub libraries (which read the
+/// standard variables) go through it as well. NO_PROXY still applies.
+pub fn apply_proxy_override() {
+    let Some(proxy) = proxy() else { return };
+    for var in ["HTTP_PROXY", "http_proxy", "HTTPS_PROXY", "https_proxy"] {
+        std::env::set_var(var, &proxy);
+    }
+}
+
+/// `reqwest::ClientBuilder` every in-tree HTTP client starts from: adi's
+/// user-agent, connect/read timeouts, and $ADI_PROXY if set (otherwise
+/// reqwest's default HTTP(S)_PROXY handling). NO_PROXY is honored either way.
+pub fn http_client_builder() -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder()
+        .user_agent(crate::http::user_agent())
+        .connect_timeout(HTTP_CONNECT_TIMEOUT)
+        .read_timeout(HTTP_READ_TIMEOUT);
+    let Some(url) = proxy() else { return builder };
+    match reqwest::Proxy::all(&url) {
+        Ok(proxy) => builder.proxy(proxy.no_proxy(reqwest::NoProxy::from_env())),
+        Err(e) => {
+            tracing::warn!("Ignoring invalid ADI_PROXY {}: {}", url, e);
+            builder
+        }
+    }
+}
+
+/// Newline-separated extra args for `adi run` ($ADI_PLUGIN_ARGS_FILE, unset by default)
+pub fn plugin_args_file() -> Option<PathBuf> {
+    let path = env_opt(EnvVar::AdiPluginArgsFile.as_str()).map(PathBuf::from);
+    tracing::trace!(path = ?path, "Plugin args file");
+    path
+}
+
 // ============================================================================
 // Daemon configuration
 // ============================================================================
@@ -119,6 +345,7 @@ pub fn signaling_url() -> String {
 const DEFAULT_DAEMON_USER: &str = "adi";
 const DEFAULT_DAEMON_ROOT_USER: &str = "adi-root";
 const DEFAULT_DAEMON_TCP_PORT: u16 = 14731;
+const DEFAULT_DAEMON_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
 
 /// ADI data directory (~/.local/share/adi)
 pub fn data_dir() -> PathBuf {
@@ -129,9 +356,48 @@ pub fn data_dir() -> PathBuf {
     dir
 }
 
-/// Plugins directory (~/.local/share/adi/plugins)
+/// Plugins directory ($ADI_PLUGINS_DIR or ~/.local/share/adi/plugins)
 pub fn plugins_dir() -> PathBuf {
-    data_dir().join("plugins")
+    let dir = env_opt(EnvVar::AdiPluginsDir.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_else(lib_plugin_host::PluginConfig::default_plugins_dir);
+    tracing::trace!(dir = %dir.display(), "Resolved plugins directory");
+    dir
+}
+
+/// Plugin download cache directory ($ADI_CACHE_DIR or the plugin host default)
+pub fn cache_dir() -> PathBuf {
+    let dir = env_opt(EnvVar::AdiCacheDir.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_else(lib_plugin_host::PluginConfig::default_cache_dir);
+    tracing::trace!(dir = %dir.display(), "Resolved cache directory");
+    dir
+}
+
+/// Point plugin and cache dirs at explicit locations for this process and
+/// any child it spawns (used by `--plugins-dir` and `--ephemeral`)
+pub fn set_plugin_dirs(plugins_dir: &std::path::Path, cache_dir: Option<&std::path::Path>) {
+    tracing::trace!(plugins_dir = %plugins_dir.display(), cache_dir = ?cache_dir, "Overriding plugin directories");
+    std::env::set_var(EnvVar::AdiPluginsDir.as_str(), plugins_dir);
+    if let Some(cache_dir) = cache_dir {
+        std::env::set_var(EnvVar::AdiCacheDir.as_str(), cache_dir);
+    }
+}
+
+/// How long CLI commands wait for the daemon to answer
+/// ($ADI_DAEMON_TIMEOUT in seconds, default 5)
+pub fn daemon_timeout() -> std::time::Duration {
+    let timeout = env_opt(EnvVar::AdiDaemonTimeout.as_str())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(std::time::Duration::from_secs)
+        .unwrap_or(DEFAULT_DAEMON_TIMEOUT);
+    tracing::trace!(timeout = ?timeout, "Resolved daemon timeout");
+    timeout
+}
+
+/// Override the daemon timeout for this process (used by `adi daemon --timeout`)
+pub fn set_daemon_timeout(secs: u64) {
+    std::env::set_var(EnvVar::AdiDaemonTimeout.as_str(), secs.to_string());
 }
 
 /// Daemon socket path ($ADI_DAEMON_SOCKET or ~/.local/share/adi/daemon.sock)
@@ -161,6 +427,30 @@ pub fn daemon_log_path() -> PathBuf {
     path
 }
 
+/// Outcome of the daemon's last SIGHUP reload (~/.local/share/adi/daemon-reload.json)
+pub fn daemon_reload_report_path() -> PathBuf {
+    data_dir().join("daemon-reload.json")
+}
+
+/// Directory of per-service log files (~/.local/share/adi/logs)
+pub fn service_logs_dir() -> PathBuf {
+    data_dir().join("logs")
+}
+
+/// Size at which a service log file is rotated ($ADI_SERVICE_LOG_MAX_BYTES)
+pub fn service_log_max_bytes() -> Option<u64> {
+    let max = env_opt(EnvVar::AdiServiceLogMaxBytes.as_str()).and_then(|v| v.parse().ok());
+    tracing::trace!(max = ?max, "ADI_SERVICE_LOG_MAX_BYTES env var");
+    max
+}
+
+/// Rotated service log files kept per service ($ADI_SERVICE_LOG_KEEP)
+pub fn service_log_keep() -> Option<usize> {
+    let keep = env_opt(EnvVar::AdiServiceLogKeep.as_str()).and_then(|v| v.parse().ok());
+    tracing::trace!(keep = ?keep, "ADI_SERVICE_LOG_KEEP env var");
+    keep
+}
+
 /// Regular daemon user ($ADI_USER or "adi")
 pub fn daemon_user() -> String {
     let user = env_or(EnvVar::AdiUser.as_str(), DEFAULT_DAEMON_USER);
@@ -181,3 +471,25 @@ pub fn daemon_tcp_port() -> u16 {
         .and_then(|s| s.parse().ok())
         .unwrap_or(DEFAULT_DAEMON_TCP_PORT)
 }
+
+/// Interval for rescanning installed plugins in the daemon ($ADI_DAEMON_REFRESH_SECS, off by default)
+pub fn daemon_refresh_interval() -> Option<std::time::Duration> {
+    let secs = env_opt(EnvVar::AdiDaemonRefreshSecs.as_str())
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|&s| s > 0);
+    tracing::trace!(secs = ?secs, "Daemon refresh interval");
+    secs.map(std::time::Duration::from_secs)
+}
+
+/// Whether the daemon brings back the services that were running when it
+/// last stopped ($ADI_DAEMON_RESTORE_SERVICES, on unless set falsy)
+pub fn daemon_restore_services() -> bool {
+    let restore = env_opt(EnvVar::AdiDaemonRestoreServices.as_str()).is_none_or(|v| lib_env_parse::is_truthy(&v));
+    tracing::trace!(restore = restore, "ADI_DAEMON_RESTORE_SERVICES env var");
+    restore
+}
+
+/// Services the daemon should bring back on its next start (~/.local/share/adi/daemon-services.json)
+pub fn daemon_services_snapshot_path() -> PathBuf {
+    data_dir().join("daemon-services.json")
+}
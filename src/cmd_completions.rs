@@ -0,0 +1,60 @@
+use cli::completions::{self, Validation};
+use lib_console_output::theme;
+use lib_console_output::{out_error, out_info, out_success, out_warn};
+
+use crate::args::{Cli, CompletionsCommands};
+
+pub(crate) fn cmd_completions(command: CompletionsCommands) -> anyhow::Result<()> {
+    match command {
+        CompletionsCommands::Regenerate => cmd_completions_regenerate(),
+        CompletionsCommands::Uninstall => cmd_completions_uninstall(),
+    }
+}
+
+fn cmd_completions_regenerate() -> anyhow::Result<()> {
+    let results = completions::regenerate_and_validate::<Cli>("adi");
+    if results.is_empty() {
+        out_info!("No installed completion scripts found");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for entry in &results {
+        let shell = format!("{:?}", entry.shell).to_lowercase();
+        let path = theme::muted(entry.path.display());
+        match &entry.result {
+            Ok(Validation::Passed) => out_success!("{} {}", theme::brand(&shell), path),
+            Ok(Validation::Skipped) => {
+                out_warn!("{} {} (not validated: {} is not installed)", theme::brand(&shell), path, shell)
+            }
+            Err(e) => {
+                failed += 1;
+                out_error!("{} {}: {}", theme::brand(&shell), path, e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} completion scripts are invalid", failed, results.len());
+    }
+    Ok(())
+}
+
+fn cmd_completions_uninstall() -> anyhow::Result<()> {
+    let uninstalled = completions::uninstall_completions("adi")?;
+    if uninstalled.is_empty() {
+        out_info!("No installed completions found");
+        return Ok(());
+    }
+
+    for entry in &uninstalled {
+        let shell = format!("{:?}", entry.shell).to_lowercase();
+        for path in &entry.removed_files {
+            out_success!("{} removed {}", theme::brand(&shell), theme::muted(path.display()));
+        }
+        for path in &entry.cleaned_configs {
+            out_success!("{} removed completions block from {}", theme::brand(&shell), theme::muted(path.display()));
+        }
+    }
+    Ok(())
+}
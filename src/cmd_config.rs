@@ -1,22 +1,19 @@
-use cli::user_config::UserConfig;
+use cli::user_config::{self, UserConfig};
 use dialoguer::console::{style, Key, Term};
 use lib_console_output::blocks::{KeyValue, Renderable, Section};
 use lib_console_output::theme;
-use lib_console_output::{out_info, out_success};
+use lib_console_output::input::Input;
+use lib_console_output::theme::generated::THEMES;
+use lib_console_output::{out_error, out_info, out_success};
 
 use crate::args::ConfigCommands;
 
 pub(crate) async fn cmd_config(command: Option<ConfigCommands>) -> anyhow::Result<()> {
     match command {
         Some(ConfigCommands::Show) => cmd_config_show(),
-        Some(ConfigCommands::PowerUser { enable }) => {
-            let value = match enable.to_lowercase().as_str() {
-                "true" | "1" | "yes" | "on" => true,
-                "false" | "0" | "no" | "off" => false,
-                _ => anyhow::bail!("Invalid value '{}'. Use 'true' or 'false'.", enable),
-            };
-            cmd_config_power_user_set(value)
-        }
+        Some(ConfigCommands::PowerUser { enable }) => cmd_config_power_user_set(user_config::parse_bool(&enable)?),
+        Some(ConfigCommands::Get { key }) => cmd_config_get(&key),
+        Some(ConfigCommands::Set { key, value }) => cmd_config_set(&key, &value),
         None => {
             // No subcommand: interactive in TTY, show otherwise
             if UserConfig::is_interactive() {
@@ -28,6 +25,25 @@ pub(crate) async fn cmd_config(command: Option<ConfigCommands>) -> anyhow::Resul
     }
 }
 
+fn cmd_config_get(key: &str) -> anyhow::Result<()> {
+    if let Some(value) = UserConfig::load()?.get(key)? {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+fn cmd_config_set(key: &str, value: &str) -> anyhow::Result<()> {
+    if key == "power_user" {
+        return cmd_config_power_user_set(user_config::parse_bool(value)?);
+    }
+    let mut config = UserConfig::load()?;
+    config.set(key, value)?;
+    config.save()?;
+    let saved = config.get(key)?.unwrap_or_default();
+    out_success!("Set {} to {}", key, saved);
+    Ok(())
+}
+
 fn cmd_config_show() -> anyhow::Result<()> {
     let config = UserConfig::load()?;
     let config_path = UserConfig::config_path()?;
@@ -52,10 +68,29 @@ fn cmd_config_show() -> anyhow::Result<()> {
         .map(|t| theme::brand(t).to_string())
         .unwrap_or_else(|| theme::muted("default").to_string());
 
+    let jobs_status = config
+        .jobs
+        .map(|j| theme::foreground(j.to_string()).to_string())
+        .unwrap_or_else(|| theme::muted(format!("default ({})", cli::clienv::jobs())).to_string());
+
+    let registry_status = config
+        .registry_url
+        .as_deref()
+        .map(|u| theme::foreground(u).to_string())
+        .unwrap_or_else(|| theme::muted("default").to_string());
+
+    let channel_status = config
+        .update_channel
+        .map(|c| theme::foreground(c.to_string()).to_string())
+        .unwrap_or_else(|| theme::muted("default (stable)").to_string());
+
     KeyValue::new()
         .entry("Power User", power_user_status)
         .entry("Language", language_status)
         .entry("Theme", theme_status)
+        .entry("Jobs", jobs_status)
+        .entry("Registry", registry_status)
+        .entry("Channel", channel_status)
         .entry(
             "Config File",
             theme::muted(config_path.display()).to_string(),
@@ -68,25 +103,64 @@ fn cmd_config_show() -> anyhow::Result<()> {
 struct ConfigOption {
     key: &'static str,
     label: &'static str,
-    value_fn: fn(&UserConfig) -> String,
 }
 
 const CONFIG_OPTIONS: &[ConfigOption] = &[
-    ConfigOption {
-        key: "power_user",
-        label: "Power User",
-        value_fn: |c| match c.power_user {
-            Some(true) => "enabled".to_string(),
-            Some(false) | None => "disabled".to_string(),
-        },
-    },
+    ConfigOption { key: "power_user", label: "Power User" },
+    ConfigOption { key: "language", label: "Language" },
+    ConfigOption { key: "theme", label: "Theme" },
+    ConfigOption { key: "jobs", label: "Jobs" },
+    ConfigOption { key: "registry_url", label: "Registry" },
+    ConfigOption { key: "channel", label: "Channel" },
 ];
 
-fn cmd_config_interactive() -> anyhow::Result<()> {
+const CHANNELS: &[&str] = &["stable", "beta", "nightly"];
+
+fn option_value(config: &UserConfig, key: &str) -> String {
+    if key == "power_user" {
+        return if config.power_user == Some(true) { "enabled" } else { "disabled" }.to_string();
+    }
+    config.get(key).ok().flatten().unwrap_or_else(|| "default".to_string())
+}
+
+/// Enter on an option: toggle power user, cycle theme and channel, prompt
+/// for the rest.
+fn edit_option(opt: &ConfigOption) -> anyhow::Result<()> {
     let config = UserConfig::load()?;
+    let current = config.get(opt.key)?;
+    match opt.key {
+        "power_user" => toggle_power_user(),
+        "theme" => {
+            let ids: Vec<&str> = THEMES.iter().map(|t| t.id).collect();
+            cmd_config_set(opt.key, next_choice(&ids, current.as_deref()))
+        }
+        "channel" => cmd_config_set(opt.key, next_choice(CHANNELS, current.as_deref())),
+        _ => {
+            let current = current.unwrap_or_default();
+            let Some(value) = Input::new(opt.label).default(&current).run() else {
+                return Ok(());
+            };
+            if value.trim().is_empty() || value == current {
+                return Ok(());
+            }
+            cmd_config_set(opt.key, &value)
+        }
+    }
+}
+
+/// The choice after `current`, wrapping around; the first if unset.
+fn next_choice<'a>(choices: &[&'a str], current: Option<&str>) -> &'a str {
+    let next = current
+        .and_then(|c| choices.iter().position(|choice| *choice == c))
+        .map_or(0, |i| (i + 1) % choices.len());
+    choices[next]
+}
+
+fn cmd_config_interactive() -> anyhow::Result<()> {
+    let mut config = UserConfig::load()?;
 
     Section::new("Configuration").width(50).print();
-    out_info!("Use arrows to navigate, Enter to toggle, q to quit");
+    out_info!("Use arrows to navigate, Enter to change, q to quit");
     println!();
 
     let term = Term::stdout();
@@ -110,14 +184,12 @@ fn cmd_config_interactive() -> anyhow::Result<()> {
             }
             Ok(Key::Enter | Key::Char(' ')) => {
                 let _ = term.clear_last_lines(CONFIG_OPTIONS.len());
-                let opt = &CONFIG_OPTIONS[cursor];
-                
-                if opt.key == "power_user" {
-                    toggle_power_user()?;
+                if let Err(e) = edit_option(&CONFIG_OPTIONS[cursor]) {
+                    out_error!("{}", e);
                 }
-                
+
                 // Reload config and re-render
-                let config = UserConfig::load()?;
+                config = UserConfig::load()?;
                 render_config_list(&term, &config, cursor, false);
             }
             Ok(Key::Escape | Key::Char('q')) => {
@@ -143,12 +215,12 @@ fn render_config_list(term: &Term, config: &UserConfig, cursor: usize, clear: bo
 
     for (i, opt) in CONFIG_OPTIONS.iter().enumerate() {
         let selected = i == cursor;
-        let value = (opt.value_fn)(config);
+        let value = option_value(config, opt.key);
 
-        let value_styled = if value == "enabled" {
-            theme::success(&value).to_string()
-        } else {
-            theme::muted(&value).to_string()
+        let value_styled = match value.as_str() {
+            "enabled" => theme::success(&value).to_string(),
+            "disabled" | "default" => theme::muted(&value).to_string(),
+            _ => theme::foreground(&value).to_string(),
         };
 
         let label_padded = format!(
@@ -1,31 +1,63 @@
 use crate::args::DaemonCommands;
+use crate::cmd_search::print_json;
 use anyhow::Result;
 use cli::clienv;
+use cli::daemon::client::{is_responding, with_timeout};
+use cli::daemon::log_files;
+use cli::daemon::log_filter::{self, LogFilter};
 use cli::daemon::server::DaemonConfig;
-use cli::daemon::{DaemonClient, DaemonServer};
+#[cfg(unix)]
+use cli::daemon::services::ReloadReport;
+use cli::daemon::services::ServiceRegistry;
+use cli::daemon::{proc_stats, DaemonClient, DaemonServer, ServiceInfo};
+use dialoguer::console::{Key, Term};
+use indicatif::{ProgressBar, ProgressStyle};
 use lib_console_output::{
     blocks::{KeyValue, Renderable, Section, Table},
     theme,
 };
+use std::collections::HashMap;
+use std::time::Duration;
+
+const SERVICE_SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Gap between the two samples `adi daemon metrics` takes to measure CPU.
+const METRICS_SAMPLE_WINDOW: Duration = Duration::from_millis(500);
+/// Lines fetched for a filtered `adi daemon logs`, the daemon's default buffer size.
+const FILTERED_LOG_SCAN_LINES: usize = 10_000;
 
 pub async fn cmd_daemon(command: DaemonCommands) -> Result<()> {
     match command {
-        DaemonCommands::Run => cmd_daemon_run().await,
+        DaemonCommands::Run { .. } => cmd_daemon_run().await,
         DaemonCommands::Start => cmd_daemon_start().await,
         DaemonCommands::Stop { force } => cmd_daemon_stop(force).await,
         DaemonCommands::Restart => cmd_daemon_restart().await,
+        DaemonCommands::Reload => cmd_daemon_reload().await,
         DaemonCommands::Status => cmd_daemon_status().await,
         DaemonCommands::StartService { service } => cmd_start_service(&service).await,
         DaemonCommands::StopService { service, force } => cmd_stop_service(&service, force).await,
         DaemonCommands::RestartService { service } => cmd_restart_service(&service).await,
-        DaemonCommands::Services => cmd_list_services().await,
+        DaemonCommands::Services { declared: true } => cmd_list_declared_services().await,
+        DaemonCommands::Services { declared: false } => cmd_list_services().await,
+        DaemonCommands::Top { interval } => cmd_daemon_top(interval).await,
+        DaemonCommands::Metrics => cmd_daemon_metrics().await,
         DaemonCommands::Logs {
             service,
             lines,
             follow,
-        } => cmd_service_logs(&service, lines, follow).await,
+            level,
+            since,
+        } => {
+            let filter = LogFilter {
+                min_level: level,
+                since: since
+                    .map(|since| log_filter::parse_since(&since, std::time::SystemTime::now()))
+                    .transpose()?,
+            };
+            cmd_service_logs(&service, lines, follow, filter).await
+        }
+        DaemonCommands::LogPath { service } => cmd_service_log_path(&service),
         DaemonCommands::RunService { plugin_id } => cmd_daemon_run_service(&plugin_id).await,
-        DaemonCommands::Setup => cmd_daemon_setup().await,
+        DaemonCommands::Setup { sudo_allow } => cmd_daemon_setup(&sudo_allow).await,
     }
 }
 
@@ -59,8 +91,8 @@ async fn cmd_daemon_run() -> Result<()> {
 async fn cmd_daemon_start() -> Result<()> {
     let client = DaemonClient::new();
 
-    if client.is_running().await {
-        let (uptime, version) = client.ping().await?;
+    if is_responding(&client).await {
+        let (uptime, version) = with_timeout(client.ping()).await?;
         println!(
             "{} Daemon already running (v{}, uptime: {})",
             theme::icons::INFO,
@@ -70,10 +102,11 @@ async fn cmd_daemon_start() -> Result<()> {
         return Ok(());
     }
 
-    println!("{} Starting daemon...", theme::icons::INFO);
-    client.ensure_running().await?;
+    let spinner = progress_spinner("Starting daemon...".to_string());
+    with_timeout(client.ensure_running()).await?;
+    spinner.finish_and_clear();
 
-    let (_uptime, version) = client.ping().await?;
+    let (_uptime, version) = with_timeout(client.ping()).await?;
     println!(
         "{} Daemon started (v{}, PID written to {})",
         theme::icons::SUCCESS,
@@ -87,7 +120,7 @@ async fn cmd_daemon_start() -> Result<()> {
 async fn cmd_daemon_stop(force: bool) -> Result<()> {
     let client = DaemonClient::new();
 
-    if !client.is_running().await {
+    if !is_responding(&client).await {
         println!("{} Daemon is not running", theme::icons::INFO);
         return Ok(());
     }
@@ -98,7 +131,7 @@ async fn cmd_daemon_stop(force: bool) -> Result<()> {
         println!("{} Stopping daemon gracefully...", theme::icons::INFO);
     }
 
-    client.shutdown(!force).await?;
+    with_timeout(client.shutdown(!force)).await?;
 
     // Wait for daemon to actually stop
     for _ in 0..50 {
@@ -123,9 +156,90 @@ async fn cmd_daemon_restart() -> Result<()> {
     cmd_daemon_start().await
 }
 
+/// Ask the daemon to rescan plugin services (it reloads on SIGHUP) and print
+/// the report it writes back.
+#[cfg(unix)]
+async fn cmd_daemon_reload() -> Result<()> {
+    let Some(pid) = lib_daemon_core::PidFile::new(&clienv::daemon_pid_path()).is_running()? else {
+        println!("{} Daemon is not running", theme::icons::INFO);
+        return Ok(());
+    };
+
+    let report_path = clienv::daemon_reload_report_path();
+    match std::fs::remove_file(&report_path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    // SAFETY: kill(2) has no memory-safety preconditions.
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGHUP) } != 0 {
+        anyhow::bail!("Failed to signal daemon (PID {}): {}", pid, std::io::Error::last_os_error());
+    }
+
+    let spinner = progress_spinner("Reloading plugin services...".to_string());
+    let report = wait_for_reload_report(&report_path).await;
+    spinner.finish_and_clear();
+    let report = report?;
+
+    if clienv::json_output() {
+        return print_json(&serde_json::to_value(&report)?);
+    }
+    if report.is_empty() {
+        println!("{} Plugin services reloaded, nothing changed", theme::icons::SUCCESS);
+        return Ok(());
+    }
+    println!("{} Plugin services reloaded", theme::icons::SUCCESS);
+    print_reload_group("Added", &report.added);
+    print_reload_group("Changed (restart to apply)", &report.changed);
+    print_reload_group("Orphaned (plugin uninstalled, still running)", &report.orphaned);
+    print_reload_group("Removed", &report.removed);
+    if !report.changed.is_empty() {
+        println!(
+            "{} Run `adi daemon restart <service>` to apply a changed config",
+            theme::icons::INFO
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn cmd_daemon_reload() -> Result<()> {
+    anyhow::bail!("Reloading the daemon is only supported on Unix; use `adi daemon restart`")
+}
+
+#[cfg(unix)]
+async fn wait_for_reload_report(path: &std::path::Path) -> Result<ReloadReport> {
+    let deadline = std::time::Instant::now() + clienv::daemon_timeout();
+    loop {
+        if let Ok(content) = std::fs::read(path) {
+            let result: std::result::Result<ReloadReport, String> = serde_json::from_slice(&content)?;
+            return result.map_err(|e| anyhow::anyhow!("Daemon failed to reload: {}", e));
+        }
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!(
+                "Daemon did not finish reloading within {:?} (see {})",
+                clienv::daemon_timeout(),
+                clienv::daemon_log_path().display()
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+#[cfg(unix)]
+fn print_reload_group(label: &str, names: &[String]) {
+    if !names.is_empty() {
+        println!("  {}: {}", label, theme::muted(names.join(", ")));
+    }
+}
+
 async fn cmd_daemon_status() -> Result<()> {
     let client = DaemonClient::new();
 
+    if clienv::json_output() {
+        return print_json(&daemon_status_json(&client).await?);
+    }
+
     Section::new("Daemon Status").print();
 
     if !client.socket_exists() {
@@ -141,7 +255,7 @@ async fn cmd_daemon_status() -> Result<()> {
         return Ok(());
     }
 
-    match client.ping().await {
+    match with_timeout(client.ping()).await {
         Ok((uptime, version)) => {
             println!();
             KeyValue::new()
@@ -155,7 +269,7 @@ async fn cmd_daemon_status() -> Result<()> {
             println!();
 
             // Show services
-            let services = client.list_services().await?;
+            let services = with_timeout(client.list_services()).await?;
             if !services.is_empty() {
                 Section::new("Managed Services").print();
                 println!();
@@ -204,16 +318,52 @@ async fn cmd_daemon_status() -> Result<()> {
     Ok(())
 }
 
+/// `adi daemon status --json`: `status` is `running`, `not_running` or
+/// `unhealthy` (socket present, no answer).
+async fn daemon_status_json(client: &DaemonClient) -> Result<serde_json::Value> {
+    let paths = serde_json::json!({
+        "socket": clienv::daemon_socket_path(),
+        "pid_file": clienv::daemon_pid_path(),
+        "log_file": clienv::daemon_log_path(),
+    });
+    if !client.socket_exists() {
+        return Ok(serde_json::json!({ "status": "not_running", "paths": paths }));
+    }
+    let (uptime, version) = match with_timeout(client.ping()).await {
+        Ok(pong) => pong,
+        Err(e) => {
+            return Ok(serde_json::json!({ "status": "unhealthy", "detail": e.to_string(), "paths": paths }));
+        }
+    };
+    let services = with_timeout(client.list_services()).await?;
+    Ok(serde_json::json!({
+        "status": "running",
+        "version": version,
+        "uptime_secs": uptime,
+        "paths": paths,
+        "services": services.iter().map(service_json).collect::<Vec<_>>(),
+    }))
+}
+
+fn service_json(svc: &ServiceInfo) -> serde_json::Value {
+    serde_json::json!({
+        "name": svc.name,
+        "state": svc.state.as_str(),
+        "pid": svc.pid,
+        "uptime_secs": svc.uptime_secs,
+        "restarts": svc.restarts,
+        "last_error": svc.last_error,
+    })
+}
+
 async fn cmd_start_service(name: &str) -> Result<()> {
     let client = DaemonClient::new();
-    client.ensure_running().await?;
+    with_timeout(client.ensure_running()).await?;
 
-    println!(
-        "{} Starting service {}...",
-        theme::icons::INFO,
-        theme::bold(name)
-    );
-    client.start_service(name, None).await?;
+    let spinner = progress_spinner(format!("Starting service {}...", name));
+    with_timeout(client.start_service(name, None)).await?;
+    wait_until_settled(&client, name, &spinner).await?;
+    spinner.finish_and_clear();
     println!(
         "{} Service {} started",
         theme::icons::SUCCESS,
@@ -226,7 +376,7 @@ async fn cmd_start_service(name: &str) -> Result<()> {
 async fn cmd_stop_service(name: &str, force: bool) -> Result<()> {
     let client = DaemonClient::new();
 
-    if !client.is_running().await {
+    if !is_responding(&client).await {
         anyhow::bail!("Daemon is not running. Start it with `adi daemon start`");
     }
 
@@ -244,7 +394,7 @@ async fn cmd_stop_service(name: &str, force: bool) -> Result<()> {
         );
     }
 
-    client.stop_service(name, force).await?;
+    with_timeout(client.stop_service(name, force)).await?;
     println!(
         "{} Service {} stopped",
         theme::icons::SUCCESS,
@@ -256,14 +406,12 @@ async fn cmd_stop_service(name: &str, force: bool) -> Result<()> {
 
 async fn cmd_restart_service(name: &str) -> Result<()> {
     let client = DaemonClient::new();
-    client.ensure_running().await?;
+    with_timeout(client.ensure_running()).await?;
 
-    println!(
-        "{} Restarting service {}...",
-        theme::icons::INFO,
-        theme::bold(name)
-    );
-    client.restart_service(name).await?;
+    let spinner = progress_spinner(format!("Restarting service {}...", name));
+    with_timeout(client.restart_service(name)).await?;
+    wait_until_settled(&client, name, &spinner).await?;
+    spinner.finish_and_clear();
     println!(
         "{} Service {} restarted",
         theme::icons::SUCCESS,
@@ -276,11 +424,15 @@ async fn cmd_restart_service(name: &str) -> Result<()> {
 async fn cmd_list_services() -> Result<()> {
     let client = DaemonClient::new();
 
-    if !client.is_running().await {
+    if !is_responding(&client).await {
         anyhow::bail!("Daemon is not running. Start it with `adi daemon start`");
     }
 
-    let services = client.list_services().await?;
+    let services = with_timeout(client.list_services()).await?;
+
+    if clienv::json_output() {
+        return print_json(&serde_json::Value::Array(services.iter().map(service_json).collect()));
+    }
 
     if services.is_empty() {
         println!("{} No services registered", theme::icons::INFO);
@@ -321,10 +473,223 @@ async fn cmd_list_services() -> Result<()> {
     Ok(())
 }
 
-async fn cmd_service_logs(name: &str, lines: usize, follow: bool) -> Result<()> {
+/// Services declared in plugin manifests, read without the daemon or loading
+/// any plugin binary.
+async fn cmd_list_declared_services() -> Result<()> {
+    let mut registry = ServiceRegistry::new();
+    registry.discover_plugins().await?;
+
+    let names = registry.list();
+    if clienv::json_output() {
+        let auto_start = registry.auto_start_names();
+        let entries = names
+            .iter()
+            .map(|name| serde_json::json!({ "name": name, "auto_start": auto_start.contains(name) }))
+            .collect();
+        return print_json(&serde_json::Value::Array(entries));
+    }
+    if names.is_empty() {
+        println!("{} No services declared by installed plugins", theme::icons::INFO);
+        return Ok(());
+    }
+
+    Section::new("Declared Services").print();
+    println!();
+
+    let mut table = Table::new().header(["Service", "Auto-start"]);
+    for name in names {
+        let auto_start = if registry.auto_start_names().contains(&name) {
+            theme::success("yes").to_string()
+        } else {
+            theme::muted("no").to_string()
+        };
+        table = table.row([name, auto_start]);
+    }
+
+    table.print();
+    println!();
+
+    Ok(())
+}
+
+async fn cmd_daemon_top(interval_secs: u64) -> Result<()> {
+    let client = DaemonClient::new();
+
+    if !is_responding(&client).await {
+        anyhow::bail!("Daemon is not running. Start it with `adi daemon start`");
+    }
+
+    let term = Term::stdout();
+    if !term.is_term() {
+        // Nothing to refresh in a pipe: print a single snapshot.
+        let services = with_timeout(client.list_services()).await?;
+        render_top_table(&services, &sample_services(&services), &HashMap::new(), Duration::ZERO);
+        return Ok(());
+    }
+
+    let (quit_tx, mut quit_rx) = tokio::sync::mpsc::unbounded_channel();
+    let key_term = term.clone();
+    std::thread::spawn(move || loop {
+        match key_term.read_key() {
+            Ok(Key::Char('q') | Key::Escape | Key::CtrlC) | Err(_) => {
+                let _ = quit_tx.send(());
+                break;
+            }
+            _ => {}
+        }
+    });
+
+    let interval = Duration::from_secs(interval_secs.max(1));
+    let mut ticker = tokio::time::interval(interval);
+    let mut previous: HashMap<u32, proc_stats::ProcessStats> = HashMap::new();
+    let mut last_tick = std::time::Instant::now();
+    let _ = term.hide_cursor();
+
+    let result = loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let services = match with_timeout(client.list_services()).await {
+                    Ok(services) => services,
+                    Err(e) => break Err(e.into()),
+                };
+                let elapsed = last_tick.elapsed();
+                last_tick = std::time::Instant::now();
+
+                let _ = term.clear_screen();
+                println!(
+                    "{} {}  {}",
+                    theme::icons::INFO,
+                    theme::bold("ADI services"),
+                    theme::muted(format!("every {}s, q to quit", interval.as_secs()))
+                );
+                println!();
+                let current = sample_services(&services);
+                render_top_table(&services, &current, &previous, elapsed);
+                previous = current;
+            }
+            _ = quit_rx.recv() => break Ok(()),
+            _ = tokio::signal::ctrl_c() => break Ok(()),
+        }
+    };
+
+    let _ = term.show_cursor();
+    result
+}
+
+async fn cmd_daemon_metrics() -> Result<()> {
+    let client = DaemonClient::new();
+
+    if !is_responding(&client).await {
+        anyhow::bail!("Daemon is not running. Start it with `adi daemon start`");
+    }
+
+    let mut services: Vec<_> = with_timeout(client.list_services()).await?.into_iter().filter(|svc| svc.pid.is_some()).collect();
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let first = sample_services(&services);
+    tokio::time::sleep(METRICS_SAMPLE_WINDOW).await;
+    let second = sample_services(&services);
+
+    // A service that exited since the list was fetched has no sample and shows as "-".
+    let metrics: Vec<_> = services
+        .iter()
+        .map(|svc| {
+            let pid = svc.pid.expect("filtered to services with a PID");
+            let rss = second.get(&pid).map(|s| s.rss_bytes);
+            let cpu = first
+                .get(&pid)
+                .zip(second.get(&pid))
+                .map(|(prev, next)| proc_stats::cpu_percent(prev, next, METRICS_SAMPLE_WINDOW));
+            (svc, pid, rss, cpu)
+        })
+        .collect();
+
+    if clienv::json_output() {
+        let entries = metrics
+            .iter()
+            .map(|(svc, pid, rss, cpu)| {
+                serde_json::json!({
+                    "name": svc.name,
+                    "pid": pid,
+                    "rss_bytes": rss,
+                    "cpu_percent": cpu,
+                })
+            })
+            .collect();
+        return print_json(&serde_json::Value::Array(entries));
+    }
+
+    if metrics.is_empty() {
+        println!("{} No running services", theme::icons::INFO);
+        return Ok(());
+    }
+
+    let mut table = Table::new().header(["Service", "PID", "Memory", "CPU"]);
+    for (svc, pid, rss, cpu) in metrics {
+        table = table.row([
+            svc.name.clone(),
+            pid.to_string(),
+            rss.map(proc_stats::format_bytes).unwrap_or_else(|| "-".to_string()),
+            cpu.map(|c| format!("{:.1}%", c)).unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    table.print();
+    Ok(())
+}
+
+fn sample_services(services: &[ServiceInfo]) -> HashMap<u32, proc_stats::ProcessStats> {
+    services
+        .iter()
+        .filter_map(|svc| svc.pid)
+        .filter_map(|pid| proc_stats::sample(pid).map(|stats| (pid, stats)))
+        .collect()
+}
+
+fn render_top_table(
+    services: &[ServiceInfo],
+    current: &HashMap<u32, proc_stats::ProcessStats>,
+    previous: &HashMap<u32, proc_stats::ProcessStats>,
+    elapsed: Duration,
+) {
+    let mut table = Table::new().header(["Service", "State", "PID", "Uptime", "Restarts", "Memory", "CPU"]);
+
+    let mut services: Vec<_> = services.iter().collect();
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for svc in services {
+        let stats = svc.pid.and_then(|pid| current.get(&pid));
+        let memory = stats
+            .map(|s| proc_stats::format_bytes(s.rss_bytes))
+            .unwrap_or_else(|| "-".to_string());
+        let cpu = svc
+            .pid
+            .and_then(|pid| Some((previous.get(&pid)?, current.get(&pid)?)))
+            .map(|(prev, next)| format!("{:.1}%", proc_stats::cpu_percent(prev, next, elapsed)))
+            .unwrap_or_else(|| "-".to_string());
+
+        table = table.row([
+            svc.name.clone(),
+            format_state(svc.state.as_str()),
+            svc.pid.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string()),
+            svc.uptime_secs.map(format_duration).unwrap_or_else(|| "-".to_string()),
+            svc.restarts.to_string(),
+            memory,
+            cpu,
+        ]);
+    }
+
+    table.print();
+}
+
+fn cmd_service_log_path(name: &str) -> Result<()> {
+    println!("{}", log_files::service_log_path(&clienv::service_logs_dir(), name).display());
+    Ok(())
+}
+
+async fn cmd_service_logs(name: &str, lines: usize, follow: bool, filter: LogFilter) -> Result<()> {
     let client = DaemonClient::new();
 
-    if !client.is_running().await {
+    if !is_responding(&client).await {
         anyhow::bail!("Daemon is not running. Start it with `adi daemon start`");
     }
 
@@ -334,12 +699,18 @@ async fn cmd_service_logs(name: &str, lines: usize, follow: bool) -> Result<()>
             theme::icons::INFO,
             theme::bold(name)
         );
-        println!(
-            "{} Log streaming not yet implemented",
-            theme::icons::WARNING
-        );
+        follow_logs(name, lines, filter).await?;
     } else {
-        let logs = client.service_logs(name, lines).await?;
+        let logs = if filter.is_empty() {
+            with_timeout(client.service_logs(name, lines)).await?
+        } else {
+            // The daemon only knows "last N", so filter its whole buffer here.
+            let logs = with_timeout(client.service_logs(name, FILTERED_LOG_SCAN_LINES)).await?;
+            let mut matches = filter.matcher();
+            let mut logs: Vec<String> = logs.into_iter().filter(|line| matches(line)).collect();
+            logs.drain(..logs.len().saturating_sub(lines));
+            logs
+        };
 
         if logs.is_empty() {
             println!("{} No logs available for {}", theme::icons::INFO, name);
@@ -356,8 +727,30 @@ async fn cmd_service_logs(name: &str, lines: usize, follow: bool) -> Result<()>
     Ok(())
 }
 
-async fn cmd_daemon_setup() -> Result<()> {
-    cli::daemon::setup::run_setup().await
+#[cfg(unix)]
+async fn follow_logs(name: &str, lines: usize, filter: LogFilter) -> Result<()> {
+    let mut matches = filter.matcher();
+    let follow = cli::daemon::client::follow_service_logs(name, lines, |line| {
+        if matches(line) {
+            println!("  {}", line);
+        }
+    });
+    tokio::select! {
+        result = follow => result,
+        _ = tokio::signal::ctrl_c() => {
+            tracing::trace!(service = %name, "Stopped following logs");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn follow_logs(_name: &str, _lines: usize, _filter: LogFilter) -> Result<()> {
+    anyhow::bail!("Following service logs is only supported on Unix")
+}
+
+async fn cmd_daemon_setup(sudo_allow: &[String]) -> Result<()> {
+    cli::daemon::setup::run_setup(sudo_allow).await
 }
 
 async fn cmd_daemon_run_service(plugin_id: &str) -> Result<()> {
@@ -371,7 +764,8 @@ async fn cmd_daemon_run_service(plugin_id: &str) -> Result<()> {
     );
 
     let runtime = PluginRuntime::new(RuntimeConfig::default()).await?;
-    runtime.load_all_plugins().await?;
+    let failures = runtime.load_all_plugins().await?;
+    cli::plugin_runtime::print_load_failures(&failures);
 
     let daemon_service = runtime
         .get_daemon_service(plugin_id)
@@ -406,6 +800,46 @@ async fn cmd_daemon_run_service(plugin_id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Spinner for daemon operations that take a while. The daemon protocol has no
+/// progress frames, so callers update it with whatever state they can observe.
+fn progress_spinner(message: String) -> ProgressBar {
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg} {elapsed:.dim}")
+            .unwrap(),
+    );
+    spinner.set_message(message);
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    spinner
+}
+
+/// Poll the service until it leaves `starting`, reflecting each state change
+/// on the spinner. Fails if the service ends up `failed`.
+async fn wait_until_settled(client: &DaemonClient, name: &str, spinner: &ProgressBar) -> Result<()> {
+    let deadline = std::time::Instant::now() + SERVICE_SETTLE_TIMEOUT;
+    while std::time::Instant::now() < deadline {
+        let services = with_timeout(client.list_services()).await?;
+        let Some(svc) = services.iter().find(|s| s.name == name) else {
+            return Ok(());
+        };
+        match svc.state.as_str() {
+            "starting" => spinner.set_message(format!("Waiting for {} to become ready...", name)),
+            "failed" => {
+                spinner.finish_and_clear();
+                anyhow::bail!(
+                    "Service {} failed to start: {}",
+                    name,
+                    svc.last_error.as_deref().unwrap_or("unknown error")
+                );
+            }
+            _ => return Ok(()),
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    Ok(())
+}
+
 fn format_state(state: &str) -> String {
     match state {
         "running" => theme::success("running").to_string(),
@@ -1,5 +1,6 @@
-use cli::plugin_registry::PluginManager;
+use cli::plugin_registry::{cancel_on_interrupt, PluginManager};
 use cli::plugin_runtime::{PluginCliCommand, PluginRuntime, RuntimeConfig};
+use cli::suggest;
 use lib_console_output::{theme, blocks::{Columns, Section, Renderable}, out_info, out_warn, out_error, out_success};
 use lib_i18n_core::{t, LocalizedError};
 
@@ -76,7 +77,7 @@ async fn execute_external_command(
 
     match runtime.run_cli_command(plugin_id, &context.to_string()).await {
         Ok(result) => {
-            handle_cli_result(&result);
+            handle_cli_result(result.as_bytes());
             Ok(())
         }
         Err(e) => {
@@ -130,7 +131,7 @@ async fn prompt_and_install(manager: &PluginManager, command: &str, plugin_id: &
 
     out_info!("{}", t!("external-autoinstall-installing", "id" => plugin_id));
 
-    match manager.install_with_dependencies(plugin_id, None).await {
+    match cancel_on_interrupt(manager.install_with_dependencies(plugin_id, None)).await {
         Ok(()) => {
             out_success!("{} {}", t!("common-success-prefix"), t!("external-autoinstall-success"));
             AutoinstallResult::Installed(plugin_id.to_string())
@@ -152,8 +153,29 @@ fn confirm_install() -> bool {
     io::stdin().read_line(&mut input).is_ok() && matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
 }
 
+/// Builtin subcommands and plugin commands, with their aliases.
+fn known_commands(cli_commands: &[PluginCliCommand]) -> Vec<String> {
+    use clap::CommandFactory;
+
+    let builtins = crate::args::Cli::command();
+    let mut known: Vec<String> = builtins
+        .get_subcommands()
+        .flat_map(|sub| std::iter::once(sub.get_name()).chain(sub.get_all_aliases()))
+        .map(String::from)
+        .collect();
+    for cmd in cli_commands {
+        known.push(cmd.command.clone());
+        known.extend(cmd.aliases.iter().cloned());
+    }
+    known
+}
+
 fn show_unknown_command(command: &str, cli_commands: &[PluginCliCommand]) {
     out_error!("{} {}", t!("common-error-prefix"), t!("external-error-unknown", "command" => command));
+    let known = known_commands(cli_commands);
+    if let Some(suggestion) = suggest::closest(command, known.iter().map(String::as_str)) {
+        out_info!("{}", t!("external-did-you-mean", "command" => suggestion));
+    }
     out_info!("{}", t!("external-autoinstall-not-found", "command" => command));
 
     if cli_commands.is_empty() {
@@ -163,15 +185,17 @@ fn show_unknown_command(command: &str, cli_commands: &[PluginCliCommand]) {
     }
 
     Section::new(t!("external-available-title")).print();
+    let header = ["Command", "Description"];
+    let rows = cli_commands.iter().map(|cmd| {
+        let desc = if cmd.aliases.is_empty() {
+            cmd.description.clone()
+        } else {
+            format!("{}{}", cmd.description, theme::muted(format!(" (aliases: {})", cmd.aliases.join(", "))))
+        };
+        [theme::brand_bold(&cmd.command).to_string(), desc]
+    }).collect();
     Columns::new()
-        .header(["Command", "Description"])
-        .rows(cli_commands.iter().map(|cmd| {
-            let desc = if cmd.aliases.is_empty() {
-                cmd.description.clone()
-            } else {
-                format!("{}{}", cmd.description, theme::muted(format!(" (aliases: {})", cmd.aliases.join(", "))))
-            };
-            [theme::brand_bold(&cmd.command).to_string(), desc]
-        }))
+        .header(header)
+        .rows(crate::layout::fit_column(header, rows, 1))
         .print();
 }
@@ -6,7 +6,7 @@ use lib_i18n_core::t;
 pub(crate) async fn cmd_info() -> anyhow::Result<()> {
     let version = env!("CARGO_PKG_VERSION");
     let config_dir = cli::clienv::config_dir();
-    let plugins_dir = lib_plugin_host::PluginConfig::default_plugins_dir();
+    let plugins_dir = cli::clienv::plugins_dir();
     let registry_url = cli::clienv::registry_url();
     let active_theme = lib_console_output::theme::active();
     let lang = cli::clienv::lang()
@@ -33,16 +33,10 @@ pub(crate) async fn cmd_info() -> anyhow::Result<()> {
 }
 
 async fn print_installed_plugins(plugins_dir: &std::path::Path) {
-    let plugin_dirs: Vec<String> = std::fs::read_dir(plugins_dir)
-        .ok()
-        .into_iter()
-        .flatten()
-        .flatten()
-        .filter(|e| {
-            e.path().is_dir()
-                && e.file_name() != lib_plugin_host::command_index::COMMANDS_DIR_NAME
-        })
-        .filter_map(|e| Some(e.file_name().to_str()?.to_string()))
+    let plugin_dirs: Vec<String> = cli::plugin_runtime::scan_plugin_dirs(plugins_dir)
+        .dirs
+        .iter()
+        .filter_map(|dir| Some(dir.file_name()?.to_str()?.to_string()))
         .collect();
 
     Section::new(t!("info-installed-plugins", "count" => plugin_dirs.len().to_string()))
@@ -117,6 +117,8 @@ fn prompt_builtin_args(cmd: BuiltinCommand) -> Option<Commands> {
         BuiltinCommand::Start => prompt_start(),
         BuiltinCommand::Plugin => prompt_plugin(),
         BuiltinCommand::Run => Some(Commands::Run {
+            context_file: None,
+            args_file: None,
             plugin_id: None,
             args: vec![],
         }),
@@ -131,7 +133,7 @@ fn prompt_self_update() -> Option<Commands> {
         .default(false)
         .run()
         .unwrap_or(false);
-    Some(Commands::SelfUpdate { force })
+    Some(Commands::SelfUpdate { force, no_verify: false, channel: None, to: None })
 }
 
 fn prompt_start() -> Option<Commands> {
@@ -165,17 +167,34 @@ fn dispatch_plugin_subcmd(subcmd: &str) -> Option<Commands> {
         "installed" => PluginCommands::Installed,
         "search" => {
             let query = Input::new(t!("interactive-search-query")).required().run()?;
-            PluginCommands::Search { query }
+            PluginCommands::Search {
+                query,
+                package: false,
+                plugin: false,
+                plugin_type: None,
+                tags: Vec::new(),
+            }
         }
         "install" => {
             let plugin_id = Input::new(t!("interactive-plugin-install-id")).required().run()?;
-            PluginCommands::Install { plugin_id, version: None }
+            PluginCommands::Install {
+                plugin_id: Some(plugin_id),
+                version: None,
+                optional_deps: false,
+                prefer_system: false,
+                yes: false,
+                resume: false,
+                retry: 0,
+                locked: None,
+                from: None,
+                dry_run: false,
+            }
         }
         "update" => {
             let plugin_id = Input::new(t!("interactive-plugin-update-id")).required().run()?;
-            PluginCommands::Update { plugin_id }
+            PluginCommands::Update { plugin_id, changelog: false, to: None, dry_run: false }
         }
-        "update-all" => PluginCommands::UpdateAll,
+        "update-all" => PluginCommands::UpdateAll { dry_run: false },
         "uninstall" => {
             let plugin_id = Input::new(t!("interactive-plugin-uninstall-id")).required().run()?;
             PluginCommands::Uninstall { plugin_id }
@@ -233,9 +252,9 @@ fn dispatch_daemon_subcmd(subcmd: &str) -> Option<Commands> {
         "start" => DaemonCommands::Start,
         "stop" => DaemonCommands::Stop { force: false },
         "restart" => DaemonCommands::Restart,
-        "services" => DaemonCommands::Services,
-        "run" => DaemonCommands::Run,
+        "services" => DaemonCommands::Services { declared: false },
+        "run" => DaemonCommands::Run { log_format: None },
         _ => return None,
     };
-    Some(Commands::Daemon { command: cmd })
+    Some(Commands::Daemon { timeout: None, command: cmd })
 }
@@ -0,0 +1,48 @@
+use std::path::{Path, PathBuf};
+
+use cli::clienv;
+use lib_console_output::{theme, out_info};
+
+use crate::args::OpenTarget;
+
+pub(crate) fn cmd_open(target: OpenTarget, print: bool) -> anyhow::Result<()> {
+    let dir = target_dir(target);
+    if print {
+        println!("{}", dir.display());
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    out_info!("Opening {}", theme::muted(dir.display()));
+    open_in_file_manager(&dir)
+}
+
+fn target_dir(target: OpenTarget) -> PathBuf {
+    match target {
+        OpenTarget::Config => clienv::config_dir(),
+        OpenTarget::Data => clienv::data_dir(),
+        OpenTarget::Plugins => clienv::plugins_dir(),
+        OpenTarget::Logs => {
+            let log = clienv::daemon_log_path();
+            log.parent().map(Path::to_path_buf).unwrap_or(log)
+        }
+    }
+}
+
+fn open_in_file_manager(dir: &Path) -> anyhow::Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    tracing::trace!(opener = opener, dir = %dir.display(), "Opening directory");
+
+    // explorer exits non-zero even on success, so only spawn failures count
+    std::process::Command::new(opener)
+        .arg(dir)
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run {} (use --print to get the path): {}", opener, e))?;
+    Ok(())
+}
@@ -1,36 +1,92 @@
 use cli::completions;
-use cli::plugin_registry::PluginManager;
-use lib_console_output::{theme, blocks::{Columns, Section, Renderable}, out_info, out_warn, out_error, out_success};
+use cli::plugin_changelog;
+use cli::plugin_health;
+use cli::plugin_pin;
+use cli::daemon::proc_stats::format_bytes;
+use cli::plugin_registry::{PlanAction, PlannedInstall, PluginManager, SearchFilter, SearchKind};
+use cli::plugin_runtime::{PluginRuntime, RuntimeConfig, SelfTestOutcome};
+use cli::suggest;
+use cli::system_binary;
+use cli::tn;
+use lib_console_output::{theme, blocks::{Columns, KeyValue, Section, Renderable}, out_info, out_warn, out_error, out_success};
 use lib_console_output::input::Confirm;
 use lib_i18n_core::{t, LocalizedError};
+use lib_plugin_host::UpdateCheck;
 
-use crate::args::{Cli, PluginCommands};
+use crate::args::{Cli, PluginCacheCommands, PluginCommands};
+use crate::cmd_search::{entry_json, print_json, JsonLines};
+use crate::layout;
 
 pub(crate) async fn cmd_plugin(command: PluginCommands) -> anyhow::Result<()> {
     tracing::trace!("cmd_plugin invoked");
     let manager = PluginManager::new();
 
     match command {
-        PluginCommands::Search { query } => handle_search(&query).await,
+        PluginCommands::Search { query, package, plugin, plugin_type, tags } => {
+            let kind = match (package, plugin) {
+                (true, _) => SearchKind::Packages,
+                (_, true) => SearchKind::Plugins,
+                _ => SearchKind::All,
+            };
+            handle_search(&query, kind, &SearchFilter { plugin_type, tags }).await
+        }
         PluginCommands::List => handle_list(&manager).await,
         PluginCommands::Installed => handle_installed(&manager).await,
-        PluginCommands::Install { plugin_id, version } => {
-            handle_install(&manager, &plugin_id, version.as_deref()).await
+        PluginCommands::Info { plugin_id } => handle_info(&manager, &plugin_id).await,
+        PluginCommands::Install { locked: Some(lock_path), .. } => handle_install_locked(&manager, &lock_path).await,
+        PluginCommands::Install { from: Some(path), prefer_system, .. } => {
+            handle_install_from(&manager.with_prefer_system(prefer_system), &path).await
+        }
+        PluginCommands::Install { plugin_id, version, optional_deps, prefer_system, yes, resume, retry, dry_run, .. } => {
+            let plugin_id = plugin_id.unwrap_or_default();
+            let manager = manager
+                .with_optional_deps(optional_deps)
+                .with_prefer_system(prefer_system)
+                .with_resume(resume)
+                .with_retries(retry)
+                .with_dry_run(dry_run);
+            handle_install(&manager, &plugin_id, version.as_deref(), yes).await
         }
-        PluginCommands::Update { plugin_id } => handle_update(&manager, &plugin_id).await,
-        PluginCommands::UpdateAll => handle_update_all(&manager).await,
+        PluginCommands::Freeze { output, stdout } => handle_freeze(&manager, &output, stdout).await,
+        PluginCommands::Restore { file, locked } => handle_restore(&manager, &file, locked).await,
+        PluginCommands::Update { plugin_id, changelog, to, dry_run } => {
+            if changelog {
+                handle_changelog(&manager, &plugin_id, to.as_deref()).await?;
+            }
+            handle_update(&manager.with_dry_run(dry_run), &plugin_id, to.as_deref()).await
+        }
+        PluginCommands::Changelog { plugin_id, version } => handle_changelog(&manager, &plugin_id, version.as_deref()).await,
+        PluginCommands::UpdateAll { dry_run } => handle_update_all(&manager.with_dry_run(dry_run)).await,
+        PluginCommands::Pin { plugin_id } => handle_pin(&manager, &plugin_id),
+        PluginCommands::Unpin { plugin_id } => handle_unpin(&manager, &plugin_id),
         PluginCommands::Uninstall { plugin_id } => handle_uninstall(&manager, &plugin_id).await,
+        PluginCommands::Doctor { plugin_id } => handle_doctor(&manager, &plugin_id).await,
+        PluginCommands::Test { plugin_id } => handle_test(&plugin_id).await,
         PluginCommands::Path { plugin_id } => handle_path(&manager, &plugin_id).await,
+        PluginCommands::Prune { dry_run } => handle_prune(&manager.with_dry_run(dry_run)).await,
+        PluginCommands::Cache { command: PluginCacheCommands::Clear } => handle_cache_clear(&manager),
     }
 }
 
-async fn handle_search(query: &str) -> anyhow::Result<()> {
-    tracing::trace!(query = %query, "Searching plugins");
-    crate::cmd_search::cmd_search(query).await
+async fn handle_search(query: &str, kind: SearchKind, filter: &SearchFilter) -> anyhow::Result<()> {
+    tracing::trace!(query = %query, kind = ?kind, filter = ?filter, "Searching plugins");
+    crate::cmd_search::cmd_search(query, kind, filter).await
 }
 
 async fn handle_list(manager: &PluginManager) -> anyhow::Result<()> {
     tracing::trace!("Listing available plugins");
+
+    if cli::clienv::json_output() {
+        let plugins = manager.list_plugins().await?;
+        let mut out = JsonLines::stdout();
+        for p in &plugins {
+            if !out.emit(&entry_json("plugin", &p.id, &p.latest_version, &p.description, &p.plugin_types, &p.tags))? {
+                break;
+            }
+        }
+        return Ok(());
+    }
+
     Section::new(t!("plugin-list-title")).print();
 
     let plugins = manager.list_plugins().await?;
@@ -40,14 +96,16 @@ async fn handle_list(manager: &PluginManager) -> anyhow::Result<()> {
         return Ok(());
     }
 
+    let header = ["Plugin", "Version", "Description", "Type"];
+    let rows = plugins.iter().map(|p| [
+        theme::brand_bold(&p.id).to_string(),
+        theme::muted(format!("v{}", p.latest_version)).to_string(),
+        p.description.clone(),
+        theme::warning(p.plugin_types.join(", ")).to_string(),
+    ]).collect();
     Columns::new()
-        .header(["Plugin", "Version", "Description", "Type"])
-        .rows(plugins.iter().map(|p| [
-            theme::brand_bold(&p.id).to_string(),
-            theme::muted(format!("v{}", p.latest_version)).to_string(),
-            p.description.clone(),
-            theme::warning(p.plugin_types.join(", ")).to_string(),
-        ]))
+        .header(header)
+        .rows(layout::fit_column(header, rows, 2))
         .print();
 
     for plugin in &plugins {
@@ -61,6 +119,19 @@ async fn handle_list(manager: &PluginManager) -> anyhow::Result<()> {
 
 async fn handle_installed(manager: &PluginManager) -> anyhow::Result<()> {
     tracing::trace!("Listing installed plugins");
+    if cli::clienv::json_output() {
+        let installed = manager.list_installed().await?;
+        let entries = installed
+            .iter()
+            .map(|(id, version)| serde_json::json!({
+                "id": id,
+                "version": version,
+                "pinned": plugin_pin::is_pinned(&manager.plugin_path(id)),
+            }))
+            .collect();
+        return print_json(&serde_json::Value::Array(entries));
+    }
+
     Section::new(t!("plugin-installed-title")).print();
 
     let installed = manager.list_installed().await?;
@@ -72,30 +143,229 @@ async fn handle_installed(manager: &PluginManager) -> anyhow::Result<()> {
     }
 
     Columns::new()
-        .header(["Plugin", "Version"])
+        .header(["Plugin", "Version", ""])
         .rows(installed.iter().map(|(id, version)| [
             theme::brand_bold(id).to_string(),
             theme::muted(format!("v{}", version)).to_string(),
+            if plugin_pin::is_pinned(&manager.plugin_path(id)) {
+                theme::warning(t!("plugin-installed-pinned")).to_string()
+            } else {
+                String::new()
+            },
         ]))
         .print();
 
     Ok(())
 }
 
-async fn handle_install(manager: &PluginManager, plugin_id: &str, version: Option<&str>) -> anyhow::Result<()> {
-    tracing::trace!(plugin_id = %plugin_id, version = ?version, "Installing plugin");
-    manager.install_plugins_matching(plugin_id, version).await?;
+async fn handle_info(manager: &PluginManager, plugin_id: &str) -> anyhow::Result<()> {
+    tracing::trace!(plugin_id = %plugin_id, "Showing plugin info");
+    let Some(info) = manager.get_plugin_info(plugin_id).await? else {
+        // Only a miss needs the whole catalogue, to suggest a close id
+        if let Ok(plugins) = manager.list_plugins().await {
+            if let Some(suggestion) = suggest::closest(plugin_id, plugins.iter().map(|p| p.id.as_str())) {
+                out_info!("{}", t!("plugin-info-did-you-mean", "id" => suggestion));
+            }
+        }
+        return Err(cli::InstallerError::PluginNotFound { id: plugin_id.to_string() }.into());
+    };
+    // The info endpoint has versions and platforms only; description, types
+    // and tags come from a search for the id
+    let results = manager.search_kind(plugin_id, SearchKind::Plugins, &SearchFilter::default()).await?;
+    let entry = results.plugins.into_iter().find(|p| p.id == plugin_id);
+
+    let none = || theme::muted(t!("plugin-info-none")).to_string();
+    let list = |items: &[String]| if items.is_empty() { none() } else { items.join(", ") };
+    let installed = match manager.is_installed(plugin_id) {
+        Some(version) if plugin_pin::is_pinned(&manager.plugin_path(plugin_id)) => {
+            format!("v{} ({})", version, theme::warning(t!("plugin-installed-pinned")))
+        }
+        Some(version) => format!("v{}", version),
+        None => theme::muted(t!("plugin-info-not-installed")).to_string(),
+    };
+    let platforms: Vec<String> = info.platforms.into_iter().map(|p| p.platform).collect();
+    let dependencies = match manager.is_installed(plugin_id) {
+        Some(_) => list(&manager.installed_dependencies(plugin_id)),
+        None => none(),
+    };
+
+    Section::new(plugin_id).width(50).print();
+    KeyValue::new()
+        .entry(t!("plugin-info-id"), theme::brand_bold(plugin_id).to_string())
+        .entry(t!("plugin-info-description"), entry.as_ref().map_or_else(none, |p| p.description.clone()))
+        .entry(t!("plugin-info-latest"), format!("v{}", info.version))
+        .entry(t!("plugin-info-platforms"), list(&platforms))
+        .entry(t!("plugin-info-types"), entry.as_ref().map_or_else(none, |p| list(&p.plugin_types)))
+        .entry(t!("plugin-info-tags"), entry.as_ref().map_or_else(none, |p| list(&p.tags)))
+        .entry(t!("plugin-info-dependencies"), dependencies)
+        .entry(t!("plugin-info-installed"), installed)
+        .print();
+    Ok(())
+}
+
+async fn handle_prune(manager: &PluginManager) -> anyhow::Result<()> {
+    tracing::trace!(dry_run = manager.is_dry_run(), "Pruning plugins dir");
+    let report = manager.prune().await?;
+
+    for id in &report.needs_attention {
+        out_warn!("{}", t!("plugin-prune-needs-attention", "id" => id));
+    }
+    if report.removed.is_empty() {
+        out_info!("{}", t!("plugin-prune-nothing"));
+        return Ok(());
+    }
+
+    for item in &report.removed {
+        let path = item.path.display().to_string();
+        let size = format_bytes(item.bytes);
+        if manager.is_dry_run() {
+            out_info!("{}", t!("plugin-prune-would-remove", "path" => &path, "size" => &size));
+        } else {
+            out_info!("{}", t!("plugin-prune-removed", "path" => &path, "size" => &size));
+        }
+    }
+    let size = format_bytes(report.reclaimed_bytes());
+    if manager.is_dry_run() {
+        out_info!("{}", t!("plugin-prune-would-reclaim", "size" => &size));
+    } else {
+        out_success!("{}", t!("plugin-prune-reclaimed", "size" => &size));
+    }
+    Ok(())
+}
+
+fn handle_cache_clear(manager: &PluginManager) -> anyhow::Result<()> {
+    tracing::trace!("Clearing registry cache");
+    let freed = manager.clear_registry_cache()?;
+    out_success!("{}", t!("plugin-cache-cleared",
+        "size" => &format_bytes(freed),
+        "path" => &manager.registry_cache_dir().display().to_string()
+    ));
+    Ok(())
+}
+
+async fn handle_install(manager: &PluginManager, plugin_id: &str, version: Option<&str>, yes: bool) -> anyhow::Result<()> {
+    tracing::trace!(plugin_id = %plugin_id, version = ?version, yes = yes, "Installing plugin");
+    manager.install_plugins_matching(plugin_id, version, yes).await?;
+    if manager.is_dry_run() {
+        print_plan(&manager.take_plan());
+        return Ok(());
+    }
     regenerate_completions_quiet();
     Ok(())
 }
 
-async fn handle_update(manager: &PluginManager, plugin_id: &str) -> anyhow::Result<()> {
-    tracing::trace!(plugin_id = %plugin_id, "Updating plugin");
-    manager.update_plugin(plugin_id).await?;
+/// The steps a `--dry-run` resolved, in install order.
+fn print_plan(plan: &[PlannedInstall]) {
+    Section::new(t!("plugin-plan-title")).print();
+    if plan.is_empty() {
+        out_info!("{}", t!("plugin-plan-empty"));
+        return;
+    }
+
+    let header = ["Plugin", "Version", "Download", "Action"];
+    let rows = plan.iter().map(|step| {
+        let size = match (&step.action, step.size_bytes) {
+            (PlanAction::Satisfied, _) => "-".to_string(),
+            (_, Some(bytes)) => format_bytes(bytes),
+            (_, None) => t!("plugin-plan-size-unknown"),
+        };
+        let action = match &step.action {
+            PlanAction::New => theme::success(t!("plugin-plan-new")).to_string(),
+            PlanAction::Upgrade { from } => theme::warning(t!("plugin-plan-upgrade", "from" => from)).to_string(),
+            PlanAction::Downgrade { from } => theme::warning(t!("plugin-plan-downgrade", "from" => from)).to_string(),
+            PlanAction::Satisfied => theme::muted(t!("plugin-plan-satisfied")).to_string(),
+        };
+        [
+            theme::brand_bold(&step.id).to_string(),
+            theme::muted(format!("v{}", step.version)).to_string(),
+            size,
+            action,
+        ]
+    }).collect();
+    Columns::new().header(header).rows(rows).print();
+
+    let downloads: Vec<&PlannedInstall> = plan.iter().filter(|step| step.action != PlanAction::Satisfied).collect();
+    let total: u64 = downloads.iter().filter_map(|step| step.size_bytes).sum();
+    out_info!("{}", t!("plugin-plan-summary",
+        "count" => &downloads.len().to_string(),
+        "size" => &format_bytes(total)
+    ));
+}
+
+async fn handle_install_locked(manager: &PluginManager, lock_path: &std::path::Path) -> anyhow::Result<()> {
+    tracing::trace!(lock = %lock_path.display(), "Installing plugins from lock file");
+    manager.install_from_lockfile(lock_path, false).await?;
     regenerate_completions_quiet();
     Ok(())
 }
 
+async fn handle_restore(manager: &PluginManager, lock_path: &std::path::Path, exact: bool) -> anyhow::Result<()> {
+    tracing::trace!(lock = %lock_path.display(), exact = exact, "Restoring plugins from lock file");
+    manager.install_from_lockfile(lock_path, exact).await?;
+    regenerate_completions_quiet();
+    Ok(())
+}
+
+async fn handle_install_from(manager: &PluginManager, path: &std::path::Path) -> anyhow::Result<()> {
+    tracing::trace!(path = %path.display(), "Installing plugin from local path");
+    manager.install_from_path(path).await?;
+    regenerate_completions_quiet();
+    Ok(())
+}
+
+async fn handle_freeze(manager: &PluginManager, output: &std::path::Path, stdout: bool) -> anyhow::Result<()> {
+    tracing::trace!(output = %output.display(), stdout = stdout, "Freezing installed plugins");
+    if stdout {
+        print!("{}", manager.freeze().await?.to_toml_string()?);
+        return Ok(());
+    }
+    let lock = manager.write_lockfile(output).await?;
+    out_success!("{}", t!("plugin-freeze-written",
+        "count" => &lock.plugins.len().to_string(),
+        "path" => &output.display().to_string()
+    ));
+    Ok(())
+}
+
+async fn handle_update(manager: &PluginManager, plugin_id: &str, to: Option<&str>) -> anyhow::Result<()> {
+    tracing::trace!(plugin_id = %plugin_id, to = ?to, "Updating plugin");
+    match to {
+        Some(version) => manager.update_plugin_to(plugin_id, version).await?,
+        None => manager.update_plugin(plugin_id).await?,
+    }
+    if manager.is_dry_run() {
+        print_plan(&manager.take_plan());
+        return Ok(());
+    }
+    regenerate_completions_quiet();
+    Ok(())
+}
+
+async fn handle_changelog(manager: &PluginManager, plugin_id: &str, version: Option<&str>) -> anyhow::Result<()> {
+    tracing::trace!(plugin_id = %plugin_id, version = ?version, "Showing plugin changelog");
+    let Some((owner, repo)) = plugin_changelog::installed_repository(&manager.plugin_path(plugin_id)) else {
+        out_warn!("{}", t!("plugin-changelog-no-source", "id" => plugin_id));
+        return Ok(());
+    };
+
+    let installed = manager.is_installed(plugin_id);
+    let notes = plugin_changelog::fetch_release_notes(plugin_id, &owner, &repo, installed.as_deref(), version).await?;
+    if notes.is_empty() {
+        out_info!("{}", t!("plugin-changelog-empty", "id" => plugin_id));
+        return Ok(());
+    }
+
+    for release in &notes {
+        Section::new(format!("{} v{}", plugin_id, release.version)).print();
+        if release.body.trim().is_empty() {
+            out_info!("{}", theme::muted(t!("plugin-changelog-no-notes", "tag" => &release.tag)));
+        } else {
+            println!("{}\n", release.body.trim_end());
+        }
+    }
+    Ok(())
+}
+
 async fn handle_update_all(manager: &PluginManager) -> anyhow::Result<()> {
     tracing::trace!("Updating all plugins");
     let installed = manager.list_installed().await?;
@@ -105,14 +375,40 @@ async fn handle_update_all(manager: &PluginManager) -> anyhow::Result<()> {
         return Ok(());
     }
 
-    out_info!("{}", t!("plugin-update-all-start", "count" => &installed.len().to_string()));
+    let (pinned, ids): (Vec<String>, Vec<String>) = installed
+        .into_iter()
+        .map(|(id, _)| id)
+        .partition(|id| plugin_pin::is_pinned(&manager.plugin_path(id)));
 
-    for (id, _) in installed {
-        if let Err(e) = manager.update_plugin(&id).await {
-            out_warn!("{}", t!("plugin-update-all-warning", "id" => &id, "error" => &e.localized()));
+    out_info!("{}", tn!("plugin-update-all-checking", ids.len()));
+    let mut checks = Vec::new();
+    for (id, result) in manager.check_updates(&ids).await {
+        match result {
+            Ok(check) => checks.push((id, check)),
+            Err(e) => out_warn!("{}", t!("plugin-update-all-warning", "id" => &id, "error" => &e.localized())),
         }
     }
+    let outdated = checks.iter().filter(|(_, check)| matches!(check, UpdateCheck::Available { .. })).count();
+    out_info!("{}", t!("plugin-update-all-summary",
+        "current" => &(checks.len() - outdated).to_string(),
+        "outdated" => &outdated.to_string()
+    ));
+
+    if outdated > 0 {
+        out_info!("{}", tn!("plugin-update-all-start", outdated));
+    }
+    for (id, e) in manager.apply_updates(checks).await? {
+        out_warn!("{}", t!("plugin-update-all-warning", "id" => &id, "error" => &e.localized()));
+    }
+    if !pinned.is_empty() {
+        tracing::trace!(pinned = ?pinned, "Skipped pinned plugins");
+        out_info!("{}", tn!("plugin-update-all-pinned", pinned.len()));
+    }
 
+    if manager.is_dry_run() {
+        print_plan(&manager.take_plan());
+        return Ok(());
+    }
     out_success!("{}", t!("plugin-update-all-done"));
     regenerate_completions_quiet();
     Ok(())
@@ -135,6 +431,33 @@ async fn handle_uninstall(manager: &PluginManager, plugin_id: &str) -> anyhow::R
     Ok(())
 }
 
+fn handle_pin(manager: &PluginManager, plugin_id: &str) -> anyhow::Result<()> {
+    tracing::trace!(plugin_id = %plugin_id, "Pinning plugin");
+    let Some(version) = manager.is_installed(plugin_id) else {
+        out_error!("{}", t!("plugin-uninstall-error-not-installed", "id" => plugin_id));
+        std::process::exit(1);
+    };
+
+    let plugin_dir = manager.plugin_path(plugin_id);
+    if let Some(pinned) = plugin_pin::pinned_version(&plugin_dir).filter(|pinned| *pinned == version) {
+        out_info!("{}", t!("plugin-pin-already", "id" => plugin_id, "version" => &pinned));
+        return Ok(());
+    }
+    plugin_pin::pin(&plugin_dir, &version)?;
+    out_success!("{}", t!("plugin-pin-success", "id" => plugin_id, "version" => &version));
+    Ok(())
+}
+
+fn handle_unpin(manager: &PluginManager, plugin_id: &str) -> anyhow::Result<()> {
+    tracing::trace!(plugin_id = %plugin_id, "Unpinning plugin");
+    if plugin_pin::unpin(&manager.plugin_path(plugin_id))? {
+        out_success!("{}", t!("plugin-unpin-success", "id" => plugin_id));
+    } else {
+        out_info!("{}", t!("plugin-unpin-not-pinned", "id" => plugin_id));
+    }
+    Ok(())
+}
+
 async fn handle_path(manager: &PluginManager, plugin_id: &str) -> anyhow::Result<()> {
     tracing::trace!(plugin_id = %plugin_id, "Resolving plugin path");
     let plugin_dir = manager.plugin_path(plugin_id);
@@ -151,6 +474,82 @@ async fn handle_path(manager: &PluginManager, plugin_id: &str) -> anyhow::Result
     Ok(())
 }
 
+async fn handle_doctor(manager: &PluginManager, plugin_id: &str) -> anyhow::Result<()> {
+    tracing::trace!(plugin_id = %plugin_id, "Running plugin doctor");
+    let plugin_dir = manager.plugin_path(plugin_id);
+    if !plugin_dir.exists() {
+        out_error!("{}", t!("plugin-uninstall-error-not-installed", "id" => plugin_id));
+        std::process::exit(1);
+    }
+
+    out_info!("{}", t!("plugin-doctor-checking", "id" => plugin_id));
+    if let Some(binary) = system_binary::recorded(&plugin_dir) {
+        if !binary.is_file() {
+            out_error!("{}", t!("plugin-doctor-system-missing", "id" => plugin_id, "path" => &binary.display().to_string()));
+            std::process::exit(1);
+        }
+        out_success!("{}", t!("plugin-doctor-system-ok", "path" => &binary.display().to_string()));
+    }
+    let was_disabled = plugin_health::is_disabled(&plugin_dir);
+    let runtime = PluginRuntime::new(RuntimeConfig::default()).await?;
+
+    if let Err(e) = runtime.revive_plugin(plugin_id).await {
+        out_error!("{}", t!("plugin-doctor-failed", "id" => plugin_id, "error" => &e.localized()));
+        std::process::exit(1);
+    }
+    out_success!("{}", t!("plugin-doctor-ok", "id" => plugin_id));
+    if was_disabled {
+        out_success!("{}", t!("plugin-doctor-reenabled", "id" => plugin_id));
+    }
+
+    if !run_self_test(&runtime, plugin_id).await {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn handle_test(plugin_id: &str) -> anyhow::Result<()> {
+    tracing::trace!(plugin_id = %plugin_id, "Testing plugin");
+    let runtime = PluginRuntime::new(RuntimeConfig::default()).await?;
+    if let Err(e) = runtime.scan_and_load_plugin(plugin_id).await {
+        out_error!("{}", t!("plugin-doctor-failed", "id" => plugin_id, "error" => &e.localized()));
+        std::process::exit(1);
+    }
+    if !run_self_test(&runtime, plugin_id).await {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Run and report a loaded plugin's self-test. Returns whether it passed.
+async fn run_self_test(runtime: &PluginRuntime, plugin_id: &str) -> bool {
+    match runtime.self_test(plugin_id).await {
+        Ok(SelfTestOutcome::Ran { exit_code, stdout, stderr }) => {
+            if !stdout.is_empty() {
+                print!("{}", stdout);
+            }
+            if !stderr.is_empty() {
+                eprint!("{}", stderr);
+            }
+            if exit_code == 0 {
+                out_success!("{}", t!("plugin-test-passed", "id" => plugin_id));
+                true
+            } else {
+                out_error!("{}", t!("plugin-test-failed", "id" => plugin_id, "code" => &exit_code.to_string()));
+                false
+            }
+        }
+        Ok(SelfTestOutcome::LoadOnly { commands }) => {
+            out_success!("{}", tn!("plugin-test-load-only", commands, "id" => plugin_id));
+            true
+        }
+        Err(e) => {
+            out_error!("{}", t!("plugin-test-error", "id" => plugin_id, "error" => &e.localized()));
+            false
+        }
+    }
+}
+
 fn regenerate_completions_quiet() {
     if let Err(e) = completions::regenerate_completions::<Cli>("adi") {
         #[cfg(debug_assertions)]
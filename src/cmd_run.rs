@@ -1,18 +1,38 @@
-use cli::plugin_runtime::{PluginRuntime, RuntimeConfig};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use cli::plugin_runtime::{PluginRuntime, RuntimeConfig, RESERVED_CONTEXT_KEYS};
+use cli::UserConfig;
+use lib_console_output::input::{Input, Select, SelectOption};
 use lib_console_output::{theme, blocks::{Columns, Section, Renderable}, out_info, out_error};
 use lib_i18n_core::{t, LocalizedError};
 
-pub(crate) async fn cmd_run(plugin_id: Option<String>, args: Vec<String>) -> anyhow::Result<()> {
-    tracing::trace!(plugin_id = ?plugin_id, args = ?args, "cmd_run invoked");
+pub(crate) async fn cmd_run(
+    plugin_id: Option<String>,
+    args: Vec<String>,
+    context_file: Option<PathBuf>,
+    args_file: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    tracing::trace!(plugin_id = ?plugin_id, args = ?args, context_file = ?context_file, args_file = ?args_file, "cmd_run invoked");
 
-    let runtime = PluginRuntime::new(RuntimeConfig::default()).await?;
-    runtime.load_all_plugins().await?;
+    // Read before loading anything so a bad file fails fast.
+    let extra_context = context_file.as_deref().map(read_context_file).transpose()?;
+    let mut args = expand_args(args, args_file.as_deref())?;
 
-    let runnable = runtime.list_runnable_plugins();
-    tracing::trace!(runnable_count = runnable.len(), "Loaded runnable plugins");
+    let runtime = PluginRuntime::new(RuntimeConfig::default()).await?;
+    let runnable = runtime.list_runnable_from_manifests();
+    tracing::trace!(runnable_count = runnable.len(), "Discovered runnable plugins from manifests");
 
     let plugin_id = match plugin_id {
         Some(id) => id,
+        None if UserConfig::is_interactive() && !runnable.is_empty() => {
+            let Some((id, prompted)) = pick_plugin(&runnable) else {
+                tracing::trace!("Plugin picker cancelled");
+                return Ok(());
+            };
+            args.extend(prompted);
+            id
+        }
         None => {
             Section::new(t!("run-title")).print();
 
@@ -20,12 +40,14 @@ pub(crate) async fn cmd_run(plugin_id: Option<String>, args: Vec<String>) -> any
                 out_info!("{}", t!("run-empty"));
                 out_info!("{}", t!("run-hint-install"));
             } else {
+                let header = ["Plugin", "Description"];
+                let rows = runnable.iter().map(|(id, desc)| [
+                    theme::brand_bold(id).to_string(),
+                    theme::muted(desc).to_string(),
+                ]).collect();
                 Columns::new()
-                    .header(["Plugin", "Description"])
-                    .rows(runnable.iter().map(|(id, desc)| [
-                        theme::brand_bold(id).to_string(),
-                        theme::muted(desc).to_string(),
-                    ]))
+                    .header(header)
+                    .rows(crate::layout::fit_column(header, rows, 1))
                     .print();
                 out_info!("{}", t!("run-hint-usage"));
             }
@@ -46,15 +68,24 @@ pub(crate) async fn cmd_run(plugin_id: Option<String>, args: Vec<String>) -> any
         std::process::exit(1);
     }
 
-    let context = serde_json::json!({
+    // Only the plugin being invoked gets its native code loaded.
+    if let Err(e) = runtime.scan_and_load_plugin(&plugin_id).await {
+        out_error!("{} {}", t!("common-error-prefix"), t!("run-error-failed", "error" => &e.localized()));
+        std::process::exit(1);
+    }
+
+    let mut context = serde_json::json!({
         "command": plugin_id,
         "args": args,
         "cwd": std::env::current_dir()?.to_string_lossy()
     });
+    if let (Some(extra), Some(object)) = (extra_context, context.as_object_mut()) {
+        object.extend(extra);
+    }
 
     match runtime.run_cli_command(&plugin_id, &context.to_string()).await {
         Ok(result) => {
-            handle_cli_result(&result);
+            handle_cli_result(result.as_bytes());
             Ok(())
         }
         Err(e) => {
@@ -64,15 +95,107 @@ pub(crate) async fn cmd_run(plugin_id: Option<String>, args: Vec<String>) -> any
     }
 }
 
-pub(crate) fn handle_cli_result(result_json: &str) {
-    #[derive(serde::Deserialize)]
-    struct CliResult {
-        exit_code: i32,
-        stdout: String,
-        stderr: String,
+/// Filterable picker over the runnable plugins, then a prompt for the
+/// plugin's arguments. `None` if the user backs out of either.
+fn pick_plugin(runnable: &[(String, String)]) -> Option<(String, Vec<String>)> {
+    let options = runnable
+        .iter()
+        .map(|(id, description)| SelectOption::new(id.clone(), id.clone()).with_description(description.clone()))
+        .collect();
+    let plugin_id = Select::new(t!("run-select-plugin"))
+        .options(options)
+        .filterable(true)
+        .max_display(Some(15))
+        .run()?;
+    let args = Input::new(t!("run-prompt-args", "id" => &plugin_id)).run()?;
+    tracing::trace!(plugin_id = %plugin_id, args = %args, "Picked plugin to run");
+    Some((plugin_id, args.split_whitespace().map(String::from).collect()))
+}
+
+/// Parse a `--context-file`: it must hold a JSON object without any of the
+/// keys the CLI sets itself.
+fn read_context_file(path: &Path) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read context file {}", path.display()))?;
+    parse_context(&content).with_context(|| format!("Invalid context file {}", path.display()))
+}
+
+fn parse_context(content: &str) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+    let serde_json::Value::Object(extra) = serde_json::from_str(content)? else {
+        anyhow::bail!("expected a JSON object");
+    };
+    let reserved: Vec<&str> = RESERVED_CONTEXT_KEYS
+        .iter()
+        .copied()
+        .filter(|key| extra.contains_key(*key))
+        .collect();
+    if !reserved.is_empty() {
+        anyhow::bail!("reserved keys cannot be overridden: {}", reserved.join(", "));
+    }
+    Ok(extra)
+}
+
+/// Replace each `@FILE` arg with the lines of FILE, then append the lines of
+/// `args_file`. This keeps huge arg lists (e.g. file batches) off the OS
+/// command line. `@@x` passes a literal `@x`.
+fn expand_args(args: Vec<String>, args_file: Option<&Path>) -> anyhow::Result<Vec<String>> {
+    let mut expanded = Vec::with_capacity(args.len());
+    for arg in args {
+        if let Some(literal) = arg.strip_prefix("@@") {
+            expanded.push(format!("@{}", literal));
+        } else if let Some(path) = arg.strip_prefix('@').filter(|p| !p.is_empty()) {
+            expanded.extend(read_args_file(Path::new(path))?);
+        } else {
+            expanded.push(arg);
+        }
     }
+    if let Some(path) = args_file {
+        expanded.extend(read_args_file(path)?);
+    }
+    tracing::trace!(count = expanded.len(), "Expanded plugin args");
+    Ok(expanded)
+}
+
+fn read_args_file(path: &Path) -> anyhow::Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read args file {}", path.display()))?;
+    Ok(parse_args_lines(&content))
+}
+
+/// One arg per line; blank lines are skipped and CRLF endings are accepted.
+fn parse_args_lines(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// A plugin command's result. Every field is optional: a plugin that only
+/// prints output succeeds, and a nonzero `exit_code` becomes `adi`'s own
+/// exit status so scripts and CI can act on it.
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct CliResult {
+    #[serde(default)]
+    exit_code: i32,
+    #[serde(default)]
+    stdout: String,
+    #[serde(default)]
+    stderr: String,
+}
+
+/// Plugin output that isn't a CLI result, with a short preview for the user.
+#[derive(Debug)]
+pub(crate) struct InvalidPluginOutput {
+    reason: String,
+    preview: String,
+}
 
-    match serde_json::from_str::<CliResult>(result_json) {
+/// Characters (or bytes, for binary output) shown in an invalid-output preview.
+const PREVIEW_LEN: usize = 64;
+
+pub(crate) fn handle_cli_result(output: &[u8]) {
+    match parse_cli_result(output) {
         Ok(result) => {
             tracing::trace!(exit_code = result.exit_code, stdout_len = result.stdout.len(), stderr_len = result.stderr.len(), "Handling CLI result");
             if !result.stdout.is_empty() {
@@ -82,12 +205,101 @@ pub(crate) fn handle_cli_result(result_json: &str) {
                 eprint!("{}", result.stderr);
             }
             if result.exit_code != 0 {
+                // process::exit skips destructors, so flush what was printed.
+                let _ = std::io::Write::flush(&mut std::io::stdout());
                 std::process::exit(result.exit_code);
             }
         }
         Err(e) => {
-            tracing::trace!(error = %e, "Failed to parse CLI result JSON, printing raw");
-            println!("{}", result_json);
+            tracing::trace!(reason = %e.reason, len = output.len(), "Plugin returned invalid CLI result");
+            out_error!(
+                "{} {}",
+                t!("common-error-prefix"),
+                t!("run-error-invalid-output", "reason" => &e.reason, "preview" => &e.preview)
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+pub(crate) fn parse_cli_result(output: &[u8]) -> Result<CliResult, InvalidPluginOutput> {
+    let invalid = |reason: String| InvalidPluginOutput {
+        reason,
+        preview: output_preview(output),
+    };
+    let text = std::str::from_utf8(output).map_err(|e| invalid(format!("not valid UTF-8: {}", e)))?;
+    serde_json::from_str(text).map_err(|e| invalid(format!("malformed JSON: {}", e)))
+}
+
+/// Quoted text for UTF-8 output, hex bytes otherwise; truncated to `PREVIEW_LEN`.
+fn output_preview(output: &[u8]) -> String {
+    match std::str::from_utf8(output) {
+        Ok(text) => {
+            let head: String = text.chars().take(PREVIEW_LEN).collect();
+            let more = if head.len() < text.len() { "…" } else { "" };
+            format!("{:?}{}", head, more)
         }
+        Err(_) => {
+            let hex: Vec<String> = output.iter().take(PREVIEW_LEN).map(|b| format!("{:02x}", b)).collect();
+            let more = if output.len() > PREVIEW_LEN { " …" } else { "" };
+            format!("{}{}", hex.join(" "), more)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_context() {
+        let extra = parse_context(r#"{"session_id": "abc", "project": {"path": "."}}"#).unwrap();
+        assert_eq!(extra["session_id"], "abc");
+
+        assert!(parse_context("[1, 2]").is_err());
+        let err = parse_context(r#"{"cwd": "/tmp", "args": []}"#).unwrap_err();
+        assert!(err.to_string().contains("cwd"));
+    }
+
+    #[test]
+    fn test_expand_args() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("files.txt");
+        std::fs::write(&list, "a.rs\r\n\nsrc/b rs.rs\n").unwrap();
+        let at_list = format!("@{}", list.display());
+
+        let args = vec!["index".to_string(), at_list, "@@tag".to_string(), "@".to_string()];
+        let expanded = expand_args(args, Some(&list)).unwrap();
+        assert_eq!(expanded, ["index", "a.rs", "src/b rs.rs", "@tag", "@", "a.rs", "src/b rs.rs"]);
+
+        assert!(expand_args(vec!["@/nonexistent/args".to_string()], None).is_err());
+    }
+
+    #[test]
+    fn test_parse_cli_result() {
+        let result = parse_cli_result(br#"{"exit_code":2,"stdout":"out","stderr":""}"#).unwrap();
+        assert_eq!(result.exit_code, 2);
+        assert_eq!(result.stdout, "out");
+
+        let result = parse_cli_result(br#"{"stdout":"ok\n"}"#).unwrap();
+        assert_eq!(result.exit_code, 0);
+        assert!(result.stderr.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_output_is_previewed() {
+        let err = parse_cli_result(b"panic: index out of range").unwrap_err();
+        assert!(err.reason.starts_with("malformed JSON"));
+        assert_eq!(err.preview, "\"panic: index out of range\"");
+
+        let long = "x".repeat(PREVIEW_LEN * 2);
+        assert!(parse_cli_result(long.as_bytes()).unwrap_err().preview.ends_with('…'));
+    }
+
+    #[test]
+    fn test_binary_output_is_previewed_as_hex() {
+        let err = parse_cli_result(&[0x7b, 0xff, 0xfe, 0x00]).unwrap_err();
+        assert!(err.reason.starts_with("not valid UTF-8"));
+        assert_eq!(err.preview, "7b ff fe 00");
     }
 }
@@ -1,30 +1,44 @@
-use cli::plugin_registry::PluginManager;
+use cli::plugin_registry::{PluginManager, SearchFilter, SearchKind};
+use registry_client::SearchResults;
 use lib_console_output::{theme, blocks::{Columns, Section, Renderable}, out_info};
 use lib_i18n_core::t;
+use std::io::Write;
 
-pub(crate) async fn cmd_search(query: &str) -> anyhow::Result<()> {
-    tracing::trace!(query = %query, "cmd_search invoked");
+use crate::layout;
+
+pub(crate) async fn cmd_search(query: &str, kind: SearchKind, filter: &SearchFilter) -> anyhow::Result<()> {
+    tracing::trace!(query = %query, kind = ?kind, filter = ?filter, "cmd_search invoked");
     let manager = PluginManager::new();
 
+    if cli::clienv::json_output() {
+        return search_json(&manager, query, kind, filter).await;
+    }
+
     out_info!("{}", t!("search-searching", "query" => query));
 
-    let results = manager.search(query).await?;
+    let results = manager.search_kind(query, kind, filter).await?;
     tracing::trace!(packages = results.packages.len(), plugins = results.plugins.len(), "Search results received");
 
     if results.packages.is_empty() && results.plugins.is_empty() {
-        out_info!("{}", t!("search-no-results"));
+        if filter.is_empty() {
+            out_info!("{}", t!("search-no-results"));
+        } else {
+            out_info!("{}", t!("search-no-results-filtered", "filters" => &filter.to_string()));
+        }
         return Ok(());
     }
 
     if !results.packages.is_empty() {
         Section::new(t!("search-packages-title")).print();
+        let header = ["Package", "Version", "Description"];
+        let rows = results.packages.iter().map(|pkg| [
+            theme::brand_bold(&pkg.id).to_string(),
+            theme::muted(format!("v{}", pkg.latest_version)).to_string(),
+            pkg.description.clone(),
+        ]).collect();
         let cols = Columns::new()
-            .header(["Package", "Version", "Description"])
-            .rows(results.packages.iter().map(|pkg| [
-                theme::brand_bold(&pkg.id).to_string(),
-                theme::muted(format!("v{}", pkg.latest_version)).to_string(),
-                pkg.description.clone(),
-            ]));
+            .header(header)
+            .rows(layout::fit_column(header, rows, 2));
         cols.print();
 
         for pkg in &results.packages {
@@ -36,14 +50,16 @@ pub(crate) async fn cmd_search(query: &str) -> anyhow::Result<()> {
 
     if !results.plugins.is_empty() {
         Section::new(t!("search-plugins-title")).print();
+        let header = ["Plugin", "Version", "Description", "Type"];
+        let rows = results.plugins.iter().map(|plugin| [
+            theme::brand_bold(&plugin.id).to_string(),
+            theme::muted(format!("v{}", plugin.latest_version)).to_string(),
+            plugin.description.clone(),
+            theme::warning(plugin.plugin_types.join(", ")).to_string(),
+        ]).collect();
         let cols = Columns::new()
-            .header(["Plugin", "Version", "Description", "Type"])
-            .rows(results.plugins.iter().map(|plugin| [
-                theme::brand_bold(&plugin.id).to_string(),
-                theme::muted(format!("v{}", plugin.latest_version)).to_string(),
-                plugin.description.clone(),
-                theme::warning(plugin.plugin_types.join(", ")).to_string(),
-            ]));
+            .header(header)
+            .rows(layout::fit_column(header, rows, 2));
         cols.print();
 
         for plugin in &results.plugins {
@@ -60,3 +76,84 @@ pub(crate) async fn cmd_search(query: &str) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// `--json` output of search: JSON Lines, one entry per line. The registry
+/// client only has batch endpoints, so entries are written once the response lands.
+async fn search_json(manager: &PluginManager, query: &str, kind: SearchKind, filter: &SearchFilter) -> anyhow::Result<()> {
+    let results = manager.search_kind(query, kind, filter).await?;
+    let mut out = JsonLines::stdout();
+    for entry in search_entries(&results) {
+        if !out.emit(&entry)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn search_entries(results: &SearchResults) -> impl Iterator<Item = serde_json::Value> + '_ {
+    let packages = results.packages.iter().map(|pkg| {
+        entry_json("package", &pkg.id, &pkg.latest_version, &pkg.description, &[], &pkg.tags)
+    });
+    let plugins = results.plugins.iter().map(|plugin| {
+        entry_json("plugin", &plugin.id, &plugin.latest_version, &plugin.description, &plugin.plugin_types, &plugin.tags)
+    });
+    packages.chain(plugins)
+}
+
+/// Print one JSON document (the `--json` output of a command). A reader
+/// that went away early (e.g. `| head`) is not an error.
+pub(crate) fn print_json(value: &serde_json::Value) -> anyhow::Result<()> {
+    let mut out = std::io::stdout().lock();
+    let result = serde_json::to_writer_pretty(&mut out, value)
+        .map_err(std::io::Error::from)
+        .and_then(|()| writeln!(out))
+        .and_then(|()| out.flush());
+    match result {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+pub(crate) fn entry_json(
+    kind: &str,
+    id: &str,
+    version: &str,
+    description: &str,
+    types: &[String],
+    tags: &[String],
+) -> serde_json::Value {
+    serde_json::json!({
+        "kind": kind,
+        "id": id,
+        "version": version,
+        "description": description,
+        "types": types,
+        "tags": tags,
+    })
+}
+
+/// Line-buffered JSON writer that flushes after every entry so downstream
+/// tools (`jq`, `fzf`) see results immediately.
+pub(crate) struct JsonLines {
+    out: std::io::Stdout,
+}
+
+impl JsonLines {
+    pub(crate) fn stdout() -> Self {
+        Self { out: std::io::stdout() }
+    }
+
+    /// Returns `Ok(false)` once the reader has gone away (e.g. `| head`).
+    pub(crate) fn emit(&mut self, value: &serde_json::Value) -> anyhow::Result<bool> {
+        let mut lock = self.out.lock();
+        let result = writeln!(lock, "{}", value).and_then(|_| lock.flush());
+        match result {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                tracing::trace!("Output pipe closed, stopping JSON stream");
+                Ok(false)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use cli::plugin_registry::PluginManager;
+use cli::plugin_registry::{cancel_on_interrupt, PluginManager};
 use cli::plugin_runtime::{PluginRuntime, RuntimeConfig};
 use lib_console_output::{theme, out_info, out_success};
 
@@ -10,7 +10,7 @@ pub(crate) async fn cmd_start(port: u16) -> anyhow::Result<()> {
     if manager.is_installed("adi.cocoon").is_none() {
         tracing::trace!("Cocoon plugin not installed, installing");
         out_info!("{}", theme::muted("Installing cocoon plugin..."));
-        manager.install_plugin("adi.cocoon", None).await?;
+        cancel_on_interrupt(manager.install_plugin("adi.cocoon", None)).await?;
         out_success!("Cocoon plugin installed!");
     }
 
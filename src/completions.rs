@@ -1,8 +1,9 @@
 use std::io::Write;
 use std::path::PathBuf;
 
-use clap::{Command, CommandFactory, ValueEnum};
+use clap::{Arg, ArgAction, Command, CommandFactory, ValueEnum};
 use clap_complete::{generate, Shell};
+use serde::Deserialize;
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum CompletionShell {
@@ -58,7 +59,7 @@ pub fn get_dynamic_completion_plugins() -> &'static Vec<String> {
 fn add_plugin_commands_from_manifests(mut cmd: Command) -> Command {
     use lib_plugin_manifest::PluginManifest;
 
-    let plugins_dir = lib_plugin_host::PluginConfig::default_plugins_dir();
+    let plugins_dir = crate::clienv::plugins_dir();
 
     if !plugins_dir.exists() {
         tracing::trace!(dir = %plugins_dir.display(), "Plugins dir does not exist, skipping manifest scan");
@@ -73,8 +74,11 @@ fn add_plugin_commands_from_manifests(mut cmd: Command) -> Command {
             continue;
         };
         let Some(cli) = &manifest.cli else { continue };
+        let static_cli = std::fs::read_to_string(&manifest_path)
+            .map(|content| parse_static_cli(&content))
+            .unwrap_or_default();
 
-        let (subcmd, is_dynamic) = build_cli_subcommand(cli);
+        let (subcmd, is_dynamic) = build_cli_subcommand(cli, &static_cli);
         if is_dynamic {
             dynamic_plugins.push(cli.command.clone());
         }
@@ -90,7 +94,7 @@ fn add_plugin_commands_from_manifests(mut cmd: Command) -> Command {
     cmd
 }
 
-fn build_cli_subcommand(cli: &lib_plugin_manifest::CliConfig) -> (Command, bool) {
+fn build_cli_subcommand(cli: &lib_plugin_manifest::CliConfig, static_cli: &StaticCli) -> (Command, bool) {
     let name: &'static str = Box::leak(cli.command.clone().into_boxed_str());
     let desc: &'static str = Box::leak(cli.description.clone().into_boxed_str());
     let mut subcmd = Command::new(name)
@@ -102,7 +106,102 @@ fn build_cli_subcommand(cli: &lib_plugin_manifest::CliConfig) -> (Command, bool)
         subcmd = subcmd.visible_alias(alias_static);
     }
 
-    (subcmd, cli.dynamic_completions)
+    (with_static_cli(subcmd, static_cli), cli.dynamic_completions)
+}
+
+/// Subcommands and flags a plugin lists in its manifest's `[cli]` section so
+/// they complete without the plugin implementing `--completions`:
+///
+/// ```toml
+/// [cli]
+/// command = "tasks"
+/// flags = [{ long = "json", description = "Print JSON" }]
+///
+/// [[cli.subcommands]]
+/// name = "add"
+/// description = "Add a task"
+/// flags = [{ long = "priority", short = "p", value = "LEVEL" }]
+/// ```
+///
+/// Only names are completed; values are left to dynamic completions.
+#[derive(Debug, Default, Deserialize)]
+struct StaticCli {
+    #[serde(default)]
+    subcommands: Vec<StaticSubcommand>,
+    #[serde(default)]
+    flags: Vec<StaticFlag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StaticSubcommand {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    flags: Vec<StaticFlag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StaticFlag {
+    long: String,
+    short: Option<char>,
+    #[serde(default)]
+    description: String,
+    /// Value name; the flag takes a value when set
+    value: Option<String>,
+}
+
+/// `subcmd` with the declared flags and subcommands attached as clap args.
+fn with_static_cli(mut subcmd: Command, static_cli: &StaticCli) -> Command {
+    subcmd = subcmd.args(static_args(&static_cli.flags));
+    let mut seen = std::collections::HashSet::new();
+    for nested in &static_cli.subcommands {
+        if !is_plain_word(&nested.name) || !seen.insert(nested.name.as_str()) {
+            continue;
+        }
+        let name: &'static str = Box::leak(nested.name.clone().into_boxed_str());
+        let desc: &'static str = Box::leak(one_line(&nested.description).into_boxed_str());
+        subcmd = subcmd.subcommand(Command::new(name).about(desc).args(static_args(&nested.flags)));
+    }
+    subcmd
+}
+
+/// The static `[cli]` completions of a manifest; none if it declares none
+/// or they don't parse.
+fn parse_static_cli(manifest: &str) -> StaticCli {
+    let Some(cli) = manifest.parse::<toml::Table>().ok().and_then(|mut table| table.remove("cli")) else {
+        return StaticCli::default();
+    };
+    cli.try_into::<StaticCli>().unwrap_or_else(|e| {
+        tracing::trace!(error = %e, "Ignoring malformed static completions in plugin manifest");
+        StaticCli::default()
+    })
+}
+
+/// clap args for declared flags. Flags clap would reject (`--help`, `-h`,
+/// repeated names) are dropped rather than failing completion generation.
+fn static_args(flags: &[StaticFlag]) -> Vec<Arg> {
+    let mut longs = std::collections::HashSet::new();
+    let mut shorts = std::collections::HashSet::from(['h']);
+    flags
+        .iter()
+        .filter(|flag| is_plain_word(&flag.long) && flag.long != "help" && longs.insert(flag.long.as_str()))
+        .map(|flag| {
+            let long: &'static str = Box::leak(flag.long.clone().into_boxed_str());
+            let help: &'static str = Box::leak(one_line(&flag.description).into_boxed_str());
+            let mut arg = Arg::new(long).long(long).help(help);
+            if let Some(short) = flag.short.filter(|c| c.is_ascii_alphanumeric() && shorts.insert(*c)) {
+                arg = arg.short(short);
+            }
+            match &flag.value {
+                Some(value) => {
+                    let value: &'static str = Box::leak(value.clone().into_boxed_str());
+                    arg.value_name(value).action(ArgAction::Set)
+                }
+                None => arg.action(ArgAction::SetTrue),
+            }
+        })
+        .collect()
 }
 
 fn collect_cli_manifest_paths(plugins_dir: &std::path::Path) -> Vec<std::path::PathBuf> {
@@ -137,15 +236,10 @@ fn collect_indexed_manifest_paths(plugins_dir: &std::path::Path) -> Option<Vec<s
 }
 
 fn collect_scanned_manifest_paths(plugins_dir: &std::path::Path) -> Vec<std::path::PathBuf> {
-    let Ok(entries) = std::fs::read_dir(plugins_dir) else {
-        return Vec::new();
-    };
-
-    entries
-        .flatten()
-        .filter(|e| e.path().is_dir())
-        .filter(|e| e.file_name() != lib_plugin_host::command_index::COMMANDS_DIR_NAME)
-        .filter_map(|e| find_plugin_manifest(&e.path()))
+    crate::plugin_runtime::scan_plugin_dirs(plugins_dir)
+        .dirs
+        .iter()
+        .filter_map(|dir| find_plugin_manifest(dir))
         .collect()
 }
 
@@ -260,10 +354,61 @@ fn write_completions_to_file(
     Ok(())
 }
 
+// Command names, aliases and descriptions come from plugin manifests and are
+// embedded in shell code that runs on Tab, so none of it goes in raw: names
+// that aren't plain words are left out, descriptions are quoted for the
+// target shell.
+
+/// Whether a plugin command name or alias can appear in a completion script
+/// as is.
+fn is_plain_word(word: &str) -> bool {
+    !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// A description on one line: control characters (newlines, tabs) become spaces.
+fn one_line(text: &str) -> String {
+    text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect()
+}
+
+/// `text` as a single-quoted zsh/bash word.
+fn sh_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// `text` as a single-quoted fish word.
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+fn about_of(cmd: &Command) -> String {
+    cmd.get_about().map(|s| one_line(&s.to_string())).unwrap_or_default()
+}
+
+/// Plugin commands (the ones taking external subcommands) that complete
+/// statically, with their declared subcommands and `--flags` and the
+/// description of each. Dynamic plugins are left to `--completions`.
+fn static_plugin_words<'a>(cmd: &'a Command, dynamic_plugins: &'a [String]) -> impl Iterator<Item = (&'a str, Vec<(String, String)>)> {
+    cmd.get_subcommands()
+        .filter(|subcmd| subcmd.is_allow_external_subcommands_set() && is_plain_word(subcmd.get_name()))
+        .filter(|subcmd| !dynamic_plugins.iter().any(|name| name == subcmd.get_name()))
+        .map(|subcmd| {
+            let subcommands = subcmd.get_subcommands().map(|nested| (nested.get_name().to_string(), about_of(nested)));
+            let flags = subcmd.get_arguments().filter_map(|arg| {
+                let help = arg.get_help().map(|h| one_line(&h.to_string())).unwrap_or_default();
+                arg.get_long().map(|long| (format!("--{}", long), help))
+            });
+            (subcmd.get_name(), subcommands.chain(flags).collect::<Vec<_>>())
+        })
+        .filter(|(_, words)| !words.is_empty())
+}
+
 fn generate_zsh_script_with_dynamic(bin_name: &str, cmd: &Command) -> String {
     let dynamic_plugins = get_dynamic_completion_plugins();
     let plugin_commands = build_zsh_plugin_command_entries(cmd);
-    let dynamic_cases = build_zsh_dynamic_cases(dynamic_plugins);
+    let dynamic_cases = build_zsh_dynamic_cases(dynamic_plugins) + &build_zsh_static_cases(cmd, dynamic_plugins);
 
     format!(
         "{}\n{}\n",
@@ -354,21 +499,19 @@ fn build_zsh_plugin_command_entries(cmd: &Command) -> String {
     let mut entries = String::new();
     for subcmd in cmd.get_subcommands() {
         let name = subcmd.get_name();
-        if ZSH_BUILTIN_COMMANDS.contains(&name) {
+        if ZSH_BUILTIN_COMMANDS.contains(&name) || !is_plain_word(name) {
             continue;
         }
-        let about = subcmd
-            .get_about()
-            .map(|s| s.to_string())
-            .unwrap_or_default();
-        entries.push_str(&format!("                '{name}:{about}'\n"));
+        // _describe splits on the first unescaped colon; a name has none.
+        let entry = sh_quote(&format!("{}:{}", name, about_of(subcmd)));
+        entries.push_str(&format!("                {entry}\n"));
     }
     entries
 }
 
 fn build_zsh_dynamic_cases(dynamic_plugins: &[String]) -> String {
     let mut cases = String::new();
-    for plugin_cmd in dynamic_plugins {
+    for plugin_cmd in dynamic_plugins.iter().filter(|c| is_plain_word(c)) {
         cases.push_str(&format!(
             r#"                {plugin_cmd})
                     _adi_dynamic_complete "{plugin_cmd}" $((CURRENT)) "${{words[@]:1}}"
@@ -379,16 +522,55 @@ fn build_zsh_dynamic_cases(dynamic_plugins: &[String]) -> String {
     cases
 }
 
+fn build_zsh_static_cases(cmd: &Command, dynamic_plugins: &[String]) -> String {
+    let mut cases = String::new();
+    for (plugin_cmd, words) in static_plugin_words(cmd, dynamic_plugins) {
+        let entries: Vec<String> = words
+            .iter()
+            .map(|(word, about)| sh_quote(&format!("{}:{}", word, about)))
+            .collect();
+        cases.push_str(&format!(
+            r#"                {plugin_cmd})
+                    local -a plugin_words
+                    plugin_words=({entries})
+                    _describe -t plugin-words '{plugin_cmd}' plugin_words
+                    ;;
+"#,
+            entries = entries.join(" ")
+        ));
+    }
+    cases
+}
+
 fn generate_bash_script_with_dynamic(bin_name: &str, cmd: &Command) -> String {
     let dynamic_plugins = get_dynamic_completion_plugins();
-    let subcommands: Vec<&str> = cmd.get_subcommands().map(|c| c.get_name()).collect();
+    let subcommands: Vec<&str> = cmd
+        .get_subcommands()
+        .map(|c| c.get_name())
+        .filter(|name| is_plain_word(name))
+        .collect();
     let subcommands_str = subcommands.join(" ");
-    let dynamic_str = dynamic_plugins.join("|");
+    let dynamic: Vec<&str> = dynamic_plugins
+        .iter()
+        .map(String::as_str)
+        .filter(|name| is_plain_word(name))
+        .collect();
+    // An empty case pattern is a syntax error; '' matches nothing real.
+    let dynamic_str = if dynamic.is_empty() { "''".to_string() } else { dynamic.join("|") };
+    let static_cases: String = static_plugin_words(cmd, dynamic_plugins)
+        .map(|(plugin_cmd, words)| {
+            let words: Vec<&str> = words.iter().map(|(word, _)| word.as_str()).collect();
+            format!(
+                "        {plugin_cmd})\n            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n            ;;\n",
+                words.join(" ")
+            )
+        })
+        .collect();
 
     format!(
         "{}\n{}\ncomplete -F _{bin_name} {bin_name}\n",
         bash_dynamic_complete_fn(bin_name),
-        bash_main_fn(bin_name, &subcommands_str, &dynamic_str),
+        bash_main_fn(bin_name, &subcommands_str, &dynamic_str, &static_cases),
     )
 }
 
@@ -418,7 +600,7 @@ _{bin_name}_dynamic_complete() {{
     )
 }
 
-fn bash_main_fn(bin_name: &str, subcommands_str: &str, dynamic_str: &str) -> String {
+fn bash_main_fn(bin_name: &str, subcommands_str: &str, dynamic_str: &str, static_cases: &str) -> String {
     format!(
         r#"_{bin_name}() {{
     local cur prev words cword
@@ -439,7 +621,7 @@ fn bash_main_fn(bin_name: &str, subcommands_str: &str, dynamic_str: &str) -> Str
             local cmd_words=("${{words[@]:2}}")
             _{bin_name}_dynamic_complete "$cmd" "$pos" "${{cmd_words[@]}}"
             ;;
-        *)
+{static_cases}        *)
             _filedir
             ;;
     esac
@@ -453,6 +635,7 @@ fn generate_fish_script_with_dynamic(bin_name: &str, cmd: &Command) -> String {
     append_fish_subcommand_completions(&mut script, bin_name, cmd);
     script.push('\n');
     append_fish_dynamic_completions(&mut script, bin_name, dynamic_plugins);
+    append_fish_static_completions(&mut script, bin_name, cmd, dynamic_plugins);
     script
 }
 
@@ -484,18 +667,12 @@ complete -c {bin_name} -f
 
 fn append_fish_subcommand_completions(script: &mut String, bin_name: &str, cmd: &Command) {
     for subcmd in cmd.get_subcommands() {
-        let name = subcmd.get_name();
-        let about = subcmd
-            .get_about()
-            .map(|s| s.to_string())
-            .unwrap_or_default();
-        script.push_str(&format!(
-            r#"complete -c {bin_name} -n "__fish_use_subcommand" -a "{name}" -d "{about}"
-"#
-        ));
-        for alias in subcmd.get_visible_aliases() {
+        let about = fish_quote(&about_of(subcmd));
+        let names = std::iter::once(subcmd.get_name()).chain(subcmd.get_visible_aliases());
+        // -a is expanded by fish (command substitution included), so only plain words.
+        for name in names.filter(|name| is_plain_word(name)) {
             script.push_str(&format!(
-                r#"complete -c {bin_name} -n "__fish_use_subcommand" -a "{alias}" -d "{about}"
+                r#"complete -c {bin_name} -n "__fish_use_subcommand" -a "{name}" -d {about}
 "#
             ));
         }
@@ -507,7 +684,7 @@ fn append_fish_dynamic_completions(
     bin_name: &str,
     dynamic_plugins: &[String],
 ) {
-    for plugin_cmd in dynamic_plugins {
+    for plugin_cmd in dynamic_plugins.iter().filter(|c| is_plain_word(c)) {
         script.push_str(&format!(
             r#"complete -c {bin_name} -n "__fish_seen_subcommand_from {plugin_cmd}" -a "(__adi_dynamic_complete {plugin_cmd} (count (commandline -opc)) (commandline -opc)[3..-1])"
 "#
@@ -515,13 +692,29 @@ fn append_fish_dynamic_completions(
     }
 }
 
+fn append_fish_static_completions(script: &mut String, bin_name: &str, cmd: &Command, dynamic_plugins: &[String]) {
+    for (plugin_cmd, words) in static_plugin_words(cmd, dynamic_plugins) {
+        for (word, about) in words {
+            let about = fish_quote(&about);
+            let completion = match word.strip_prefix("--") {
+                Some(long) => format!("-l {long}"),
+                None => format!("-a \"{word}\""),
+            };
+            script.push_str(&format!(
+                r#"complete -c {bin_name} -n "__fish_seen_subcommand_from {plugin_cmd}" {completion} -d {about}
+"#
+            ));
+        }
+    }
+}
+
 fn add_to_shell_config(shell: CompletionShell, snippet: &str) -> anyhow::Result<()> {
     let config_path = get_shell_config_path(shell)
         .ok_or_else(|| anyhow::anyhow!("Could not determine shell config path"))?;
 
     let existing = std::fs::read_to_string(&config_path).unwrap_or_default();
 
-    if existing.contains("# ADI CLI completions") {
+    if existing.contains(SHELL_CONFIG_MARKER) {
         return Ok(());
     }
 
@@ -535,28 +728,109 @@ fn add_to_shell_config(shell: CompletionShell, snippet: &str) -> anyhow::Result<
     Ok(())
 }
 
+/// Shells whose completion files are refreshed after plugin changes.
+const REGENERATED_SHELLS: [CompletionShell; 3] = [
+    CompletionShell::Bash,
+    CompletionShell::Zsh,
+    CompletionShell::Fish,
+];
+
 pub fn regenerate_completions<C: CommandFactory>(bin_name: &str) -> anyhow::Result<()> {
     tracing::trace!(bin_name = %bin_name, "Regenerating completions for installed shells");
-    for shell in [
-        CompletionShell::Bash,
-        CompletionShell::Zsh,
-        CompletionShell::Fish,
-    ] {
-        if let Some(dir) = get_completions_dir(shell) {
-            let file_path = dir.join(get_completion_filename(shell, bin_name));
-            if file_path.exists() {
-                tracing::trace!(shell = ?shell, path = %file_path.display(), "Regenerating completion file");
-                let file = std::fs::File::create(&file_path)?;
-                let mut cmd = C::command();
-                cmd = add_plugin_commands_from_manifests(cmd);
-                write_completions_to_file(shell, bin_name, &cmd, file)?;
-            }
-        }
+    for (shell, file_path) in installed_completion_files(bin_name) {
+        regenerate_completion_file::<C>(shell, bin_name, &file_path)?;
     }
 
     Ok(())
 }
 
+/// Result of checking a regenerated script with its shell's syntax checker.
+#[derive(Debug)]
+pub enum Validation {
+    Passed,
+    /// The shell binary isn't installed, so the script couldn't be checked
+    Skipped,
+}
+
+#[derive(Debug)]
+pub struct RegeneratedCompletion {
+    pub shell: CompletionShell,
+    pub path: PathBuf,
+    pub result: anyhow::Result<Validation>,
+}
+
+/// Regenerate every installed completion file and syntax-check each one
+/// (`bash -n`, `zsh -n`, `fish --no-execute`). Unlike
+/// [`regenerate_completions`], a failure for one shell doesn't stop the rest.
+pub fn regenerate_and_validate<C: CommandFactory>(bin_name: &str) -> Vec<RegeneratedCompletion> {
+    tracing::trace!(bin_name = %bin_name, "Regenerating and validating completions");
+    installed_completion_files(bin_name)
+        .into_iter()
+        .map(|(shell, path)| {
+            let result = regenerate_completion_file::<C>(shell, bin_name, &path)
+                .and_then(|()| validate_completion_script(shell, &path));
+            RegeneratedCompletion { shell, path, result }
+        })
+        .collect()
+}
+
+fn installed_completion_files(bin_name: &str) -> Vec<(CompletionShell, PathBuf)> {
+    REGENERATED_SHELLS
+        .into_iter()
+        .filter_map(|shell| {
+            let file_path = get_completions_dir(shell)?.join(get_completion_filename(shell, bin_name));
+            file_path.exists().then_some((shell, file_path))
+        })
+        .collect()
+}
+
+fn regenerate_completion_file<C: CommandFactory>(
+    shell: CompletionShell,
+    bin_name: &str,
+    file_path: &std::path::Path,
+) -> anyhow::Result<()> {
+    tracing::trace!(shell = ?shell, path = %file_path.display(), "Regenerating completion file");
+    let file = std::fs::File::create(file_path)?;
+    let mut cmd = C::command();
+    cmd = add_plugin_commands_from_manifests(cmd);
+    write_completions_to_file(shell, bin_name, &cmd, file)
+}
+
+fn syntax_check_command(shell: CompletionShell) -> Option<(&'static str, &'static str)> {
+    match shell {
+        CompletionShell::Bash => Some(("bash", "-n")),
+        CompletionShell::Zsh => Some(("zsh", "-n")),
+        CompletionShell::Fish => Some(("fish", "--no-execute")),
+        CompletionShell::PowerShell | CompletionShell::Elvish => None,
+    }
+}
+
+fn validate_completion_script(
+    shell: CompletionShell,
+    path: &std::path::Path,
+) -> anyhow::Result<Validation> {
+    let Some((program, flag)) = syntax_check_command(shell) else {
+        return Ok(Validation::Skipped);
+    };
+
+    let output = match std::process::Command::new(program).arg(flag).arg(path).output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::trace!(shell = ?shell, "Shell not installed, skipping validation");
+            return Ok(Validation::Skipped);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    tracing::trace!(shell = ?shell, status = ?output.status, "Validated completion script");
+
+    if output.status.success() {
+        Ok(Validation::Passed)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("`{} {}` failed: {}", program, flag, stderr.trim())
+    }
+}
+
 pub fn detect_shell() -> Option<CompletionShell> {
     std::env::var("SHELL").ok().and_then(|s| {
         tracing::trace!(shell_env = %s, "Detecting shell from $SHELL");
@@ -623,7 +897,7 @@ fn write_completion_file_if_possible<C: CommandFactory>(
 }
 
 fn completions_outdated(completion_file: &std::path::Path) -> bool {
-    let plugins_dir = lib_plugin_host::PluginConfig::default_plugins_dir();
+    let plugins_dir = crate::clienv::plugins_dir();
 
     if !plugins_dir.exists() {
         return false;
@@ -655,40 +929,143 @@ fn completions_outdated(completion_file: &std::path::Path) -> bool {
     false
 }
 
+/// Heading line of the block [`setup_shell_config`] appends to rc files.
+const SHELL_CONFIG_MARKER: &str = "# ADI CLI completions";
+
 fn setup_shell_config(
     shell: CompletionShell,
     completion_file: &std::path::Path,
 ) -> anyhow::Result<()> {
-    match shell {
-        CompletionShell::Zsh => {
-            add_to_shell_config(
-                shell,
-                r#"
-# ADI CLI completions
-fpath=(~/.zfunc $fpath)
-autoload -Uz compinit && compinit
-"#,
-            )?;
+    // Fish auto-loads from ~/.config/fish/completions
+    if let Some(block) = shell_config_block(shell, completion_file) {
+        add_to_shell_config(shell, &block)?;
+    }
+    Ok(())
+}
+
+/// The rc file snippet for `shell`, if it needs one.
+fn shell_config_block(shell: CompletionShell, completion_file: &std::path::Path) -> Option<String> {
+    let body = match shell {
+        CompletionShell::Zsh => "fpath=(~/.zfunc $fpath)\nautoload -Uz compinit && compinit".to_string(),
+        CompletionShell::Bash => format!("source \"{}\"", completion_file.display()),
+        _ => return None,
+    };
+    Some(format!("\n{}\n{}\n", SHELL_CONFIG_MARKER, body))
+}
+
+/// What [`uninstall_completions`] removed for one shell.
+#[derive(Debug)]
+pub struct UninstalledCompletion {
+    pub shell: CompletionShell,
+    /// Completion script and install marker that were deleted
+    pub removed_files: Vec<PathBuf>,
+    /// Rc files the completions block was stripped from
+    pub cleaned_configs: Vec<PathBuf>,
+}
+
+/// Remove everything completion setup wrote, for every shell: the completion
+/// script, the `.<bin>-installed` marker and the `# ADI CLI completions`
+/// block in the rc file. Safe to run again; shells with nothing left to
+/// remove are omitted from the result.
+pub fn uninstall_completions(bin_name: &str) -> anyhow::Result<Vec<UninstalledCompletion>> {
+    tracing::trace!(bin_name = %bin_name, "Uninstalling completions");
+    let shells = [
+        CompletionShell::Bash,
+        CompletionShell::Zsh,
+        CompletionShell::Fish,
+        CompletionShell::PowerShell,
+        CompletionShell::Elvish,
+    ];
+
+    let mut uninstalled = Vec::new();
+    for shell in shells {
+        let mut removed_files = Vec::new();
+        if let Some(dir) = get_completions_dir(shell) {
+            let files = [
+                dir.join(get_completion_filename(shell, bin_name)),
+                dir.join(format!(".{}-installed", bin_name)),
+            ];
+            for file in files {
+                match std::fs::remove_file(&file) {
+                    Ok(()) => removed_files.push(file),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(anyhow::anyhow!("Failed to remove {}: {}", file.display(), e)),
+                }
+            }
         }
-        CompletionShell::Bash => {
-            let source_line = format!("source \"{}\"", completion_file.display());
-            add_to_shell_config(
+
+        let mut cleaned_configs = Vec::new();
+        for config in shell_config_candidates(shell) {
+            let Ok(content) = std::fs::read_to_string(&config) else {
+                continue;
+            };
+            if let Some(stripped) = strip_shell_config_block(&content, shell, bin_name) {
+                std::fs::write(&config, stripped)
+                    .map_err(|e| anyhow::anyhow!("Failed to update {}: {}", config.display(), e))?;
+                cleaned_configs.push(config);
+            }
+        }
+
+        if !removed_files.is_empty() || !cleaned_configs.is_empty() {
+            uninstalled.push(UninstalledCompletion {
                 shell,
-                &format!(
-                    r#"
-# ADI CLI completions
-{}
-"#,
-                    source_line
-                ),
-            )?;
+                removed_files,
+                cleaned_configs,
+            });
         }
-        CompletionShell::Fish => {
-            // Fish auto-loads from ~/.config/fish/completions
+    }
+    Ok(uninstalled)
+}
+
+/// Every rc file [`get_shell_config_path`] may have picked. For bash that
+/// depends on whether `.bash_profile` existed at install time, so check both.
+fn shell_config_candidates(shell: CompletionShell) -> Vec<PathBuf> {
+    match (shell, dirs::home_dir()) {
+        (CompletionShell::Bash, Some(home)) => vec![home.join(".bash_profile"), home.join(".bashrc")],
+        _ => get_shell_config_path(shell).into_iter().collect(),
+    }
+}
+
+/// `content` without the block [`setup_shell_config`] wrote for `shell`, or
+/// `None` if it has no such block. Only the exact lines of that block (and
+/// the blank lines written around it) are removed; a marker followed by
+/// anything else is left alone.
+fn strip_shell_config_block(content: &str, shell: CompletionShell, bin_name: &str) -> Option<String> {
+    // Bash sources the script from whichever completions dir was in use at
+    // install time, so match it by file name.
+    let completion_file = PathBuf::from(get_completion_filename(shell, bin_name));
+    let block = shell_config_block(shell, &completion_file)?;
+    let expected: Vec<&str> = block.lines().skip(1).collect();
+    let matches = |line: &str, want: &str| match shell {
+        CompletionShell::Bash if want.starts_with("source ") => {
+            let suffix = format!("{}\"", completion_file.display());
+            line.starts_with("source \"")
+                && line.ends_with(&suffix)
+                && line[..line.len() - suffix.len()].ends_with(['/', '\\', '"'])
         }
-        _ => {}
+        _ => line == want,
+    };
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let start = (0..lines.len()).find(|&i| {
+        i + expected.len() <= lines.len()
+            && expected
+                .iter()
+                .enumerate()
+                .all(|(offset, want)| matches(lines[i + offset].trim_end_matches(['\n', '\r']), want))
+    })?;
+
+    let mut from = start;
+    let mut to = start + expected.len();
+    // The block is written with a leading blank line and `writeln!` adds a
+    // trailing one.
+    if from > 0 && lines[from - 1].trim().is_empty() {
+        from -= 1;
     }
-    Ok(())
+    if to < lines.len() && lines[to].trim().is_empty() {
+        to += 1;
+    }
+    Some([&lines[..from], &lines[to..]].concat().concat())
 }
 
 #[cfg(test)]
@@ -715,4 +1092,124 @@ mod tests {
             "adi.fish"
         );
     }
+
+    #[test]
+    fn test_validate_completion_script_reports_syntax_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("adi.bash");
+
+        std::fs::write(&script, "complete -F _adi adi\n").unwrap();
+        let valid = validate_completion_script(CompletionShell::Bash, &script).unwrap();
+
+        std::fs::write(&script, "_adi() {\n  if then\n").unwrap();
+        let invalid = validate_completion_script(CompletionShell::Bash, &script);
+
+        match valid {
+            Validation::Passed => assert!(invalid.is_err()),
+            // bash isn't installed here; nothing else to check
+            Validation::Skipped => assert!(matches!(invalid, Ok(Validation::Skipped))),
+        }
+    }
+
+    const HOSTILE_ABOUT: &str = "it's \"quoted\" `tick` $(touch pwned)\nnext";
+
+    fn hostile_cli() -> Command {
+        Command::new("adi")
+            .subcommand(Command::new("tasks").about(HOSTILE_ABOUT).visible_alias("t"))
+            .subcommand(Command::new("bad;name").about("x"))
+    }
+
+    #[test]
+    fn test_plugin_strings_are_quoted_in_scripts() {
+        let cli = hostile_cli();
+        let zsh = generate_zsh_script_with_dynamic("adi", &cli);
+        let bash = generate_bash_script_with_dynamic("adi", &cli);
+        let fish = generate_fish_script_with_dynamic("adi", &cli);
+
+        assert!(zsh.contains(r#"'tasks:it'\''s "quoted" `tick` $(touch pwned) next'"#), "{}", zsh);
+        assert!(fish.contains(r#"-a "t" -d 'it\'s "quoted" `tick` $(touch pwned) next'"#), "{}", fish);
+        for script in [&zsh, &bash, &fish] {
+            assert!(!script.contains("bad;name"));
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        for (shell, script) in [
+            (CompletionShell::Zsh, &zsh),
+            (CompletionShell::Bash, &bash),
+            (CompletionShell::Fish, &fish),
+        ] {
+            let path = dir.path().join(get_completion_filename(shell, "adi"));
+            std::fs::write(&path, script).unwrap();
+            // Skipped where the shell isn't installed
+            validate_completion_script(shell, &path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_static_plugin_completions() {
+        let manifest = r#"
+[cli]
+command = "tasks"
+description = "Task tracker"
+flags = [{ long = "json", description = "Print JSON" }, { long = "help" }]
+
+[[cli.subcommands]]
+name = "add"
+description = "Add a task"
+flags = [{ long = "priority", short = "p", value = "LEVEL" }, { long = "quiet", short = "h" }]
+"#;
+        let tasks = Command::new("tasks").about("Task tracker").allow_external_subcommands(true);
+        let cli = Command::new("adi").subcommand(with_static_cli(tasks, &parse_static_cli(manifest)));
+        assert!(cli
+            .clone()
+            .try_get_matches_from(["adi", "tasks", "add", "-p", "high", "--quiet"])
+            .is_ok());
+
+        let zsh = generate_zsh_script_with_dynamic("adi", &cli);
+        let bash = generate_bash_script_with_dynamic("adi", &cli);
+        let fish = generate_fish_script_with_dynamic("adi", &cli);
+        assert!(zsh.contains("plugin_words=('add:Add a task' '--json:Print JSON')"), "{}", zsh);
+        assert!(bash.contains(r#"compgen -W "add --json""#), "{}", bash);
+        assert!(fish.contains(r#"-n "__fish_seen_subcommand_from tasks" -l json -d 'Print JSON'"#), "{}", fish);
+
+        assert_eq!(static_plugin_words(&cli, &["tasks".to_string()]).count(), 0);
+        assert!(parse_static_cli("[cli]\nflags = 3\n").flags.is_empty());
+    }
+
+    #[test]
+    fn test_sh_quote_round_trips() {
+        let quoted = sh_quote(&one_line(HOSTILE_ABOUT));
+        let Ok(output) = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("printf %s {}", quoted))
+            .output()
+        else {
+            return;
+        };
+        assert_eq!(String::from_utf8_lossy(&output.stdout), one_line(HOSTILE_ABOUT));
+    }
+
+    #[test]
+    fn test_strip_shell_config_block() {
+        let user = "export PATH=\"$HOME/bin:$PATH\"\nalias ll='ls -l'\n";
+
+        for (shell, script) in [
+            (CompletionShell::Zsh, PathBuf::from("/home/me/.zfunc/_adi")),
+            (CompletionShell::Bash, PathBuf::from("/home/me/.bash_completion.d/adi.bash")),
+        ] {
+            // As `add_to_shell_config` appends it, with more config after.
+            let block = shell_config_block(shell, &script).unwrap();
+            let installed = format!("{}{}\n# later\nexport EDITOR=vim\n", user, block);
+            let stripped = strip_shell_config_block(&installed, shell, "adi").unwrap();
+            assert_eq!(stripped, format!("{}# later\nexport EDITOR=vim\n", user));
+            assert_eq!(strip_shell_config_block(&stripped, shell, "adi"), None);
+        }
+
+        // A marker we didn't write, or a script for another binary, stays.
+        let edited = format!("{}\n# ADI CLI completions\nsource ~/my-completions\n", user);
+        assert_eq!(strip_shell_config_block(&edited, CompletionShell::Bash, "adi"), None);
+        let other = format!("{}\n# ADI CLI completions\nsource \"/x/notadi.bash\"\n", user);
+        assert_eq!(strip_shell_config_block(&other, CompletionShell::Bash, "adi"), None);
+        assert_eq!(strip_shell_config_block(user, CompletionShell::Fish, "adi"), None);
+    }
 }
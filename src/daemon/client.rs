@@ -1,3 +1,75 @@
 //! Re-export daemon client from lib-daemon-client
 pub use lib_daemon_client::client::*;
 pub use lib_daemon_client::DaemonClient;
+
+use std::future::Future;
+
+/// Await a daemon request, failing with "daemon not responding" once
+/// [`crate::clienv::daemon_timeout`] passes. Guards against a daemon whose
+/// socket exists but which no longer accepts or answers.
+pub async fn with_timeout<T, E: Into<anyhow::Error>>(request: impl Future<Output = Result<T, E>>) -> anyhow::Result<T> {
+    let timeout = crate::clienv::daemon_timeout();
+    match tokio::time::timeout(timeout, request).await {
+        Ok(result) => result.map_err(Into::into),
+        Err(_) => {
+            tracing::trace!(timeout = ?timeout, "Daemon request timed out");
+            anyhow::bail!("Daemon not responding (no answer within {}s)", timeout.as_secs_f32())
+        }
+    }
+}
+
+/// [`DaemonClient::is_running`], treating a daemon that doesn't answer in
+/// time as not running.
+pub async fn is_responding(client: &DaemonClient) -> bool {
+    tokio::time::timeout(crate::clienv::daemon_timeout(), client.is_running())
+        .await
+        .unwrap_or(false)
+}
+
+/// Print-as-you-go `ServiceLogs { follow: true }`: calls `on_line` for the
+/// last `lines` lines and then for each new one until the daemon ends the
+/// stream. Dropping the future closes the connection, which is how a
+/// follower stops (e.g. on Ctrl+C). A daemon without follow support answers
+/// with a single `Logs` response, which is printed before returning.
+#[cfg(unix)]
+pub async fn follow_service_logs(name: &str, lines: usize, mut on_line: impl FnMut(&str)) -> anyhow::Result<()> {
+    use super::protocol::{ArchivedResponse, MessageFrame, Request};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let socket_path = crate::clienv::daemon_socket_path();
+    tracing::trace!(socket = %socket_path.display(), service = %name, "Following service logs");
+    let mut stream = tokio::net::UnixStream::connect(&socket_path).await?;
+
+    let request = Request::ServiceLogs {
+        name: name.to_string(),
+        lines,
+        follow: true,
+    };
+    let frame = MessageFrame::encode_request(&request)
+        .map_err(|e| anyhow::anyhow!("Failed to encode request: {}", e))?;
+    stream.write_all(&frame).await?;
+    stream.flush().await?;
+
+    loop {
+        let mut len_buf = [0u8; 4];
+        match stream.read_exact(&mut len_buf).await {
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(()),
+            result => result.map(|_| ())?,
+        }
+        let mut buf = vec![0u8; MessageFrame::read_length(&len_buf)];
+        stream.read_exact(&mut buf).await?;
+        let response = rkyv::access::<ArchivedResponse, rkyv::rancor::Error>(&buf)
+            .map_err(|e| anyhow::anyhow!("Failed to deserialize response: {}", e))?;
+
+        match response {
+            ArchivedResponse::LogLine { line } => on_line(line.as_str()),
+            ArchivedResponse::StreamEnd => return Ok(()),
+            ArchivedResponse::Logs { lines } => {
+                lines.iter().for_each(|line| on_line(line.as_str()));
+                return Ok(());
+            }
+            ArchivedResponse::Error { message } => anyhow::bail!("{}", message),
+            _ => anyhow::bail!("Unexpected response while following logs"),
+        }
+    }
+}
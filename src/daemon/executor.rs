@@ -1,13 +1,16 @@
 use crate::clienv;
 use anyhow::Result;
+use std::path::Path;
 use std::process::Output;
 use tokio::process::Command;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Runs commands as either `adi` (unprivileged) or `adi-root` (sudo) users
 pub struct CommandExecutor {
     regular_user: String,
     privileged_user: String,
+    /// Commands `sudo_run` accepts (see [`SUDO_ALLOWLIST_PATH`])
+    sudo_allowlist: Vec<String>,
 }
 
 impl CommandExecutor {
@@ -15,9 +18,15 @@ impl CommandExecutor {
         Self {
             regular_user: clienv::daemon_user(),
             privileged_user: clienv::daemon_root_user(),
+            sudo_allowlist: Vec::new(),
         }
     }
 
+    pub fn with_sudo_allowlist(mut self, commands: Vec<String>) -> Self {
+        self.sudo_allowlist = commands;
+        self
+    }
+
     /// Runs with `adi` user privileges (no sudo access).
     pub async fn run(&self, cmd: &str, args: &[String]) -> Result<Output> {
         debug!("Running command as {}: {} {:?}", self.regular_user, cmd, args);
@@ -46,8 +55,17 @@ impl CommandExecutor {
     }
 
     /// Runs with root privileges via `adi-root` user (NOPASSWD sudo).
-    /// Only call after validating the plugin has permission for this command.
+    /// Refuses commands not on the allowlist; only call after validating the
+    /// plugin has permission for this command.
     pub async fn sudo_run(&self, cmd: &str, args: &[String]) -> Result<Output> {
+        if !sudo_command_allowed(&self.sudo_allowlist, cmd) {
+            warn!("Refusing privileged command not in {}: {}", SUDO_ALLOWLIST_PATH, cmd);
+            anyhow::bail!(
+                "Privileged command '{}' is not allowed; allow it with `adi daemon setup --sudo-allow {}`",
+                cmd,
+                cmd
+            );
+        }
         info!(
             "Running privileged command as {}: {} {:?}",
             self.privileged_user, cmd, args
@@ -80,6 +98,53 @@ impl CommandExecutor {
     }
 }
 
+/// Commands `sudo_run` accepts, one per line, written by `adi daemon setup
+/// --sudo-allow`. It lives outside the user's config because anything
+/// running as the user could otherwise grant itself root.
+pub const SUDO_ALLOWLIST_PATH: &str = "/etc/adi/sudo-allowed-commands";
+
+/// The allowlist at `path`, empty if it is missing or if it or its
+/// directory could be written by anyone but root.
+pub fn load_sudo_allowlist(path: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let trusted = path.parent().is_some_and(root_only_writable) && root_only_writable(path);
+    if !trusted {
+        warn!("Ignoring {}: it must be owned and only writable by root", path.display());
+        return Vec::new();
+    }
+    parse_sudo_allowlist(&content)
+}
+
+fn parse_sudo_allowlist(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(unix)]
+fn root_only_writable(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).is_ok_and(|m| m.uid() == 0 && m.mode() & 0o022 == 0)
+}
+
+#[cfg(not(unix))]
+fn root_only_writable(_path: &Path) -> bool {
+    false
+}
+
+/// Whether `cmd` may run as root. A bare name must be listed by name and is
+/// resolved through sudo's `secure_path`; anything with a path must be
+/// listed with that exact path, so `/tmp/x/systemctl` doesn't pass as
+/// `systemctl`.
+pub fn sudo_command_allowed(allowlist: &[String], cmd: &str) -> bool {
+    !cmd.is_empty() && allowlist.iter().any(|allowed| allowed == cmd)
+}
+
 impl Default for CommandExecutor {
     fn default() -> Self {
         Self::new()
@@ -96,4 +161,17 @@ mod tests {
         assert_eq!(executor.regular_user, clienv::daemon_user());
         assert_eq!(executor.privileged_user, clienv::daemon_root_user());
     }
+
+    #[test]
+    fn test_sudo_command_allowed() {
+        let allowlist = vec!["systemctl".to_string(), "/usr/sbin/nginx".to_string()];
+        assert!(sudo_command_allowed(&allowlist, "systemctl"));
+        assert!(sudo_command_allowed(&allowlist, "/usr/sbin/nginx"));
+        assert!(!sudo_command_allowed(&allowlist, "/tmp/evil/systemctl"));
+        assert!(!sudo_command_allowed(&allowlist, "./systemctl"));
+        assert!(!sudo_command_allowed(&allowlist, "nginx"));
+        assert!(!sudo_command_allowed(&allowlist, "rm"));
+        assert!(!sudo_command_allowed(&[], "systemctl"));
+        assert_eq!(parse_sudo_allowlist("# adi\nsystemctl\n\n  /usr/sbin/nginx  \n"), allowlist);
+    }
 }
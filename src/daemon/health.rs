@@ -1,6 +1,8 @@
 use super::log_buffer::LogBuffer;
+use super::probe::HealthCheck;
 use super::protocol::ServiceState;
 use super::services::{ManagedService, ServiceManager};
+use crate::logging;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -10,6 +12,7 @@ use tracing::{debug, error, info, warn};
 
 const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 
+#[derive(Clone)]
 pub struct HealthManager {
     services: Arc<RwLock<HashMap<String, ManagedService>>>,
     log_buffer: Arc<LogBuffer>,
@@ -58,7 +61,7 @@ impl HealthManager {
         };
 
         for name in running_names {
-            let (alive, pid, restart_on_failure, max_restarts) = {
+            let (alive, pid, restart_on_failure, max_restarts, health_check) = {
                 let mut services = self.services.write().await;
                 let Some(service) = services.get_mut(&name) else {
                     continue;
@@ -81,8 +84,11 @@ impl HealthManager {
                 } else {
                     false
                 };
+                if alive && service.reset_restarts_if_stable() {
+                    info!("Service '{}' has been stable, resetting its restart count", name);
+                }
 
-                (alive, pid, restart_on_failure, max_restarts)
+                (alive, pid, restart_on_failure, max_restarts, service.health_check.clone())
             };
 
             if !alive {
@@ -93,20 +99,72 @@ impl HealthManager {
                 }
                 self.handle_service_death(&name, restart_on_failure, max_restarts)
                     .await;
+            } else if let Some(check) = health_check {
+                self.probe(&name, &check, restart_on_failure, max_restarts).await;
             } else {
                 debug!("Service '{}' (PID {:?}) is healthy", name, pid);
             }
         }
     }
 
+    /// Run a live service's health check. After `failure_threshold` failures
+    /// in a row the process is considered hung: it is killed and handled like
+    /// a crash, so it restarts per its policy.
+    async fn probe(&self, name: &str, check: &HealthCheck, restart_on_failure: bool, max_restarts: u32) {
+        let result = check.run().await;
+
+        let mut services = self.services.write().await;
+        let Some(service) = services.get_mut(name) else {
+            return;
+        };
+        if service.state != ServiceState::Running {
+            // Stopped while the probe ran
+            return;
+        }
+        match result {
+            Ok(()) => {
+                if service.probe_failures > 0 {
+                    info!("Health check for '{}' passed again", name);
+                }
+                service.probe_failures = 0;
+                debug!("Service '{}' (PID {:?}) is healthy", name, service.pid());
+            }
+            Err(e) => {
+                service.probe_failures += 1;
+                warn!(
+                    "Health check for '{}' failed ({}/{}): {}",
+                    name, service.probe_failures, check.failure_threshold, e
+                );
+                if service.probe_failures < check.failure_threshold {
+                    return;
+                }
+
+                error!("Service '{}' (PID {:?}) is unresponsive, killing it", name, service.pid());
+                if let Some(ref mut child) = service.process {
+                    let _ = child.start_kill();
+                }
+                service.last_error = Some(format!(
+                    "Health check failed {} times in a row: {}",
+                    service.probe_failures, e
+                ));
+                service.probe_failures = 0;
+                drop(services);
+                self.handle_service_death(name, restart_on_failure, max_restarts)
+                    .await;
+            }
+        }
+    }
+
     async fn handle_service_death(&self, name: &str, restart_on_failure: bool, max_restarts: u32) {
         let mut services = self.services.write().await;
 
         if let Some(service) = services.get_mut(name) {
             if restart_on_failure && service.restarts < max_restarts {
+                let delay = service.restart_policy.backoff(service.restarts);
                 info!(
-                    "Restarting service '{}' (attempt {}/{})",
+                    "Restarting service '{}' in {:?} (attempt {}/{})",
                     name,
+                    delay,
                     service.restarts + 1,
                     max_restarts
                 );
@@ -119,10 +177,20 @@ impl HealthManager {
                 let config = service.config.clone();
                 drop(services);
 
-                if let Err(e) = self.restart_service(name, &config).await {
-                    error!("Failed to restart service '{}': {}", name, e);
-                    self.mark_failed(name, &e.to_string()).await;
-                }
+                // Wait in the background so other services keep being checked.
+                let health = self.clone();
+                let name = name.to_string();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    if !health.awaiting_restart(&name).await {
+                        debug!("Service '{}' changed state during restart backoff, not restarting", name);
+                        return;
+                    }
+                    if let Err(e) = health.restart_service(&name, &config).await {
+                        error!("Failed to restart service '{}': {}", name, e);
+                        health.mark_failed(&name, &e.to_string()).await;
+                    }
+                });
             } else {
                 service.state = ServiceState::Failed;
                 service.last_error = Some("Process died and max restarts exceeded".to_string());
@@ -136,6 +204,15 @@ impl HealthManager {
         }
     }
 
+    /// Still waiting for its automatic restart, i.e. not stopped or started
+    /// by hand during the backoff.
+    async fn awaiting_restart(&self, name: &str) -> bool {
+        let services = self.services.read().await;
+        services
+            .get(name)
+            .is_some_and(|s| s.state == ServiceState::Starting && s.process.is_none())
+    }
+
     async fn restart_service(
         &self,
         name: &str,
@@ -144,6 +221,7 @@ impl HealthManager {
         use std::process::Stdio;
         use tokio::process::Command;
 
+        let config = super::interpolate::resolve_config(name, config)?;
         let mut cmd = Command::new(&config.command);
         cmd.args(&config.args);
 
@@ -167,7 +245,7 @@ impl HealthManager {
             tokio::spawn(async move {
                 let mut lines = BufReader::new(stdout).lines();
                 while let Ok(Some(line)) = lines.next_line().await {
-                    buf.push(&svc, line);
+                    buf.push(&svc, logging::service_line(logging::format(), &svc, "stdout", line));
                 }
             });
         }
@@ -177,7 +255,7 @@ impl HealthManager {
             tokio::spawn(async move {
                 let mut lines = BufReader::new(stderr).lines();
                 while let Ok(Some(line)) = lines.next_line().await {
-                    buf.push(&svc, line);
+                    buf.push(&svc, logging::service_line(logging::format(), &svc, "stderr", line));
                 }
             });
         }
@@ -191,6 +269,7 @@ impl HealthManager {
             service.state = ServiceState::Running;
             service.started_at = Some(std::time::Instant::now());
             service.last_error = None;
+            service.probe_failures = 0;
         }
 
         Ok(())
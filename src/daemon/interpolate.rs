@@ -0,0 +1,96 @@
+//! `${VAR}` expansion in service configs.
+//!
+//! The command, args, env values and working dir of a [`ServiceConfig`] are
+//! expanded each time the service is spawned. Names resolve against the
+//! built-in ADI variables first (see [`builtin_vars`]), then the daemon's
+//! own environment. `$$` is a literal `$`, and a `$` not followed by `{` is
+//! left as is. An unknown name or an unclosed `${` fails the start instead
+//! of handing the process a literal `${...}`.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use super::protocol::ServiceConfig;
+use crate::clienv;
+
+/// Variables every service config can use, for service `name`.
+pub fn builtin_vars(name: &str) -> HashMap<String, String> {
+    let plugins_dir = clienv::plugins_dir();
+    HashMap::from([
+        ("ADI_SERVICE".to_string(), name.to_string()),
+        ("ADI_CONFIG_DIR".to_string(), clienv::config_dir().display().to_string()),
+        ("ADI_DATA_DIR".to_string(), clienv::data_dir().display().to_string()),
+        ("ADI_CACHE_DIR".to_string(), clienv::cache_dir().display().to_string()),
+        ("ADI_PLUGIN_DIR".to_string(), plugins_dir.join(name).display().to_string()),
+        ("ADI_PLUGINS_DIR".to_string(), plugins_dir.display().to_string()),
+    ])
+}
+
+/// `config` with every interpolated field expanded, ready to spawn.
+pub fn resolve_config(name: &str, config: &ServiceConfig) -> Result<ServiceConfig> {
+    let builtins = builtin_vars(name);
+    let lookup = |var: &str| builtins.get(var).cloned().or_else(|| std::env::var(var).ok());
+    let expand = |field: &str, value: &str| {
+        interpolate(value, &lookup).map_err(|e| anyhow::anyhow!("Service '{}' {}: {}", name, field, e))
+    };
+
+    let mut resolved = config.clone();
+    resolved.command = expand("command", &config.command)?;
+    for arg in &mut resolved.args {
+        *arg = expand("args", arg)?;
+    }
+    for (key, value) in &mut resolved.env {
+        *value = expand(&format!("env {}", key), value)?;
+    }
+    if let Some(dir) = &mut resolved.working_dir {
+        *dir = expand("working_dir", dir)?;
+    }
+    tracing::trace!(service = %name, command = %resolved.command, args = ?resolved.args, "Resolved service config");
+    Ok(resolved)
+}
+
+/// Expand `${NAME}` in `value` using `lookup`.
+pub fn interpolate(value: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String> {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(at) = rest.find('$') {
+        out.push_str(&rest[..at]);
+        let after = &rest[at + 1..];
+        if let Some(tail) = after.strip_prefix('$') {
+            out.push('$');
+            rest = tail;
+        } else if let Some(tail) = after.strip_prefix('{') {
+            let end = tail
+                .find('}')
+                .ok_or_else(|| anyhow::anyhow!("unclosed '${{' in \"{}\"", value))?;
+            let var = &tail[..end];
+            let resolved = lookup(var).ok_or_else(|| anyhow::anyhow!("unresolved variable ${{{}}}", var))?;
+            out.push_str(&resolved);
+            rest = &tail[end + 1..];
+        } else {
+            out.push('$');
+            rest = after;
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        let lookup = |var: &str| (var == "HOME").then(|| "/home/me".to_string());
+
+        assert_eq!(interpolate("--root=${HOME}/db", &lookup).unwrap(), "--root=/home/me/db");
+        assert_eq!(interpolate("cost $$5 or $x", &lookup).unwrap(), "cost $5 or $x");
+        assert_eq!(interpolate("$${HOME}", &lookup).unwrap(), "${HOME}");
+
+        let err = interpolate("${MISSING}/x", &lookup).unwrap_err();
+        assert!(err.to_string().contains("${MISSING}"));
+        assert!(interpolate("${HOME", &lookup).is_err());
+    }
+}
@@ -1,12 +1,22 @@
 use std::collections::HashMap;
 use std::sync::RwLock;
 
+use tokio::sync::broadcast;
+
+use super::log_files::ServiceLogFiles;
+
 const DEFAULT_MAX_LINES: usize = 10_000;
+/// New lines a slow follower may fall behind by before it skips ahead.
+const FOLLOW_CAPACITY: usize = 1024;
 
-/// Per-service ring buffer for captured stdout/stderr lines.
+/// Per-service ring buffer for captured stdout/stderr lines, optionally
+/// also written to rotating per-service log files.
 pub struct LogBuffer {
     max_lines: usize,
     logs: RwLock<HashMap<String, Vec<String>>>,
+    /// `(service, line)` for every pushed line, for `adi daemon logs -f`
+    new_lines: broadcast::Sender<(String, String)>,
+    files: Option<ServiceLogFiles>,
 }
 
 impl LogBuffer {
@@ -14,28 +24,63 @@ impl LogBuffer {
         Self {
             max_lines,
             logs: RwLock::new(HashMap::new()),
+            new_lines: broadcast::channel(FOLLOW_CAPACITY).0,
+            files: None,
         }
     }
 
+    /// Also persist every line to `files`, and read history from them.
+    pub fn with_files(mut self, files: ServiceLogFiles) -> Self {
+        self.files = Some(files);
+        self
+    }
+
+    /// Receive every line pushed from now on, for all services.
+    pub fn subscribe(&self) -> broadcast::Receiver<(String, String)> {
+        self.new_lines.subscribe()
+    }
+
     /// Append a line for the given service, trimming oldest if over capacity.
     pub fn push(&self, service: &str, line: String) {
+        if let Some(files) = &self.files {
+            files.append(service, &line);
+        }
         let mut logs = self.logs.write().expect("LogBuffer lock poisoned");
         let entries = logs.entry(service.to_string()).or_default();
-        entries.push(line);
+        entries.push(line.clone());
         if entries.len() > self.max_lines {
             let excess = entries.len() - self.max_lines;
             entries.drain(..excess);
         }
+        drop(logs);
+        // No followers is the common case, not an error.
+        let _ = self.new_lines.send((service.to_string(), line));
     }
 
     /// Return the last `n` lines for a service (or all if `n` exceeds stored count).
+    /// When the buffer holds fewer than `n`, older lines (including ones from
+    /// before a daemon restart) come from the service's log files.
     pub fn tail(&self, service: &str, n: usize) -> Vec<String> {
-        let logs = self.logs.read().expect("LogBuffer lock poisoned");
-        let Some(entries) = logs.get(service) else {
-            return Vec::new();
+        let buffered = {
+            let logs = self.logs.read().expect("LogBuffer lock poisoned");
+            let entries = logs.get(service).map(Vec::as_slice).unwrap_or_default();
+            entries[entries.len().saturating_sub(n)..].to_vec()
         };
-        let start = entries.len().saturating_sub(n);
-        entries[start..].to_vec()
+        if buffered.len() >= n {
+            return buffered;
+        }
+        match &self.files {
+            // Every buffered line was also written to the files, so they hold a superset.
+            Some(files) => {
+                let persisted = files.tail(service, n);
+                if persisted.len() > buffered.len() {
+                    persisted
+                } else {
+                    buffered
+                }
+            }
+            None => buffered,
+        }
     }
 
     /// Remove all logs for a service.
@@ -81,6 +126,29 @@ mod tests {
         assert_eq!(lines, vec!["line 5", "line 6", "line 7", "line 8", "line 9"]);
     }
 
+    #[test]
+    fn subscribers_receive_new_lines() {
+        let buf = LogBuffer::default();
+        buf.push("svc", "before".into());
+        let mut rx = buf.subscribe();
+        buf.push("svc", "after".into());
+        assert_eq!(rx.try_recv().unwrap(), ("svc".to_string(), "after".to_string()));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn tail_reads_history_from_log_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = || ServiceLogFiles::new(dir.path().to_path_buf(), Default::default());
+        let before_restart = LogBuffer::new(100).with_files(files());
+        before_restart.push("svc", "old".into());
+
+        let buf = LogBuffer::new(100).with_files(files());
+        buf.push("svc", "new".into());
+        assert_eq!(buf.tail("svc", 10), vec!["old", "new"]);
+        assert_eq!(buf.tail("svc", 1), vec!["new"]);
+    }
+
     #[test]
     fn clear_removes_service_logs() {
         let buf = LogBuffer::default();
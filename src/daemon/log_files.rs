@@ -0,0 +1,197 @@
+//! On-disk service logs with size-based rotation.
+//!
+//! Each service's captured output is appended to `<logs_dir>/<service>.log`.
+//! When a write would push the file past `max_bytes` it is renamed to
+//! `<service>.log.1` (older files shift to `.2`, `.3`, ...) and a fresh file
+//! is started; only the newest `keep` rotated files are kept.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+pub const DEFAULT_KEEP: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RotationPolicy {
+    pub max_bytes: u64,
+    pub keep: usize,
+}
+
+impl Default for RotationPolicy {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_BYTES,
+            keep: DEFAULT_KEEP,
+        }
+    }
+}
+
+impl RotationPolicy {
+    /// Defaults, overridden by $ADI_SERVICE_LOG_MAX_BYTES and $ADI_SERVICE_LOG_KEEP.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_bytes: crate::clienv::service_log_max_bytes().unwrap_or(defaults.max_bytes),
+            keep: crate::clienv::service_log_keep().unwrap_or(defaults.keep),
+        }
+    }
+}
+
+/// One service's log file, opened lazily and rotated by size.
+struct RotatingFile {
+    path: PathBuf,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn new(path: PathBuf) -> Self {
+        Self { path, file: None, size: 0 }
+    }
+
+    fn write_line(&mut self, line: &str, policy: &RotationPolicy) -> std::io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.file.is_none() {
+            self.size = std::fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        }
+        if self.size > 0 && self.size + len > policy.max_bytes {
+            self.rotate(policy.keep)?;
+        }
+
+        let file = match self.file.take() {
+            Some(file) => file,
+            None => open_append(&self.path)?,
+        };
+        writeln!(self.file.insert(file), "{}", line)?;
+        self.size += len;
+        Ok(())
+    }
+
+    fn rotate(&mut self, keep: usize) -> std::io::Result<()> {
+        tracing::trace!(path = %self.path.display(), keep = keep, "Rotating service log");
+        self.file = None;
+        self.size = 0;
+        if keep == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+        let _ = std::fs::remove_file(rotated_path(&self.path, keep));
+        for n in (1..keep).rev() {
+            let from = rotated_path(&self.path, n);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))
+    }
+}
+
+/// Log files for every service, shared by the stdout and stderr capture
+/// tasks of each service (writes for one service are serialized).
+pub struct ServiceLogFiles {
+    dir: PathBuf,
+    policy: RotationPolicy,
+    files: Mutex<HashMap<String, RotatingFile>>,
+}
+
+impl ServiceLogFiles {
+    pub fn new(dir: PathBuf, policy: RotationPolicy) -> Self {
+        Self {
+            dir,
+            policy,
+            files: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn path(&self, service: &str) -> PathBuf {
+        service_log_path(&self.dir, service)
+    }
+
+    pub fn append(&self, service: &str, line: &str) {
+        let mut files = self.files.lock().expect("ServiceLogFiles lock poisoned");
+        let file = files
+            .entry(service.to_string())
+            .or_insert_with(|| RotatingFile::new(self.path(service)));
+        if let Err(e) = file.write_line(line, &self.policy) {
+            tracing::warn!("Failed to write log file {}: {}", file.path.display(), e);
+            // Reopen on the next line rather than writing to a stale handle.
+            file.file = None;
+        }
+    }
+
+    /// Last `n` lines across the current file and rotated ones, oldest first.
+    pub fn tail(&self, service: &str, n: usize) -> Vec<String> {
+        // Hold the lock so a rotation can't happen between reading two files.
+        let _files = self.files.lock().expect("ServiceLogFiles lock poisoned");
+        let path = self.path(service);
+        let mut lines = read_lines(&path);
+        for rotated in 1..=self.policy.keep {
+            if lines.len() >= n {
+                break;
+            }
+            let mut older = read_lines(&rotated_path(&path, rotated));
+            if older.is_empty() {
+                break;
+            }
+            older.append(&mut lines);
+            lines = older;
+        }
+        let start = lines.len().saturating_sub(n);
+        lines.split_off(start)
+    }
+}
+
+/// `<dir>/<service>.log`, with path separators in the name replaced.
+pub fn service_log_path(dir: &Path, service: &str) -> PathBuf {
+    let name: String = service
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\') { '_' } else { c })
+        .collect();
+    dir.join(format!("{}.log", name))
+}
+
+fn open_append(path: &Path) -> std::io::Result<File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+fn read_lines(path: &Path) -> Vec<String> {
+    match File::open(path) {
+        Ok(file) => BufReader::new(file).lines().map_while(Result::ok).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_by_size_and_keeps_newest_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = ServiceLogFiles::new(dir.path().to_path_buf(), RotationPolicy { max_bytes: 16, keep: 2 });
+        for i in 0..10 {
+            files.append("hive", &format!("line {i}"));
+        }
+
+        let path = files.path("hive");
+        // "line N\n" is 7 bytes, so each file holds two lines.
+        assert_eq!(read_lines(&path), ["line 8", "line 9"]);
+        assert_eq!(read_lines(&rotated_path(&path, 1)), ["line 6", "line 7"]);
+        assert_eq!(read_lines(&rotated_path(&path, 2)), ["line 4", "line 5"]);
+        assert!(!rotated_path(&path, 3).exists());
+
+        assert_eq!(files.tail("hive", 5), ["line 5", "line 6", "line 7", "line 8", "line 9"]);
+        assert!(files.tail("unknown", 4).is_empty());
+    }
+}
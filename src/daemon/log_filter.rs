@@ -0,0 +1,215 @@
+//! Level and time filters for captured service output (`adi daemon logs
+//! --level/--since`).
+//!
+//! Services log in whatever format they like, so the level of a line is a
+//! guess: the `level` field of a JSON line, or else the first of its leading
+//! words that names one (`INFO`, `[warn]`, `level=error`, ...). Lines with no
+//! recognizable level are [`LogLevel::Unknown`], the lowest severity, and are
+//! never dropped by a level filter: they are usually continuations such as
+//! stack traces, and hiding them would hide the interesting part.
+//!
+//! The CLI applies these filters to the lines the daemon returns: the
+//! `ServiceLogs` request (lib-daemon-client) has no fields to carry them.
+
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+
+use crate::logging;
+
+/// Leading words of a line searched for a level, enough to skip a timestamp
+/// and a target.
+const LEVEL_WORDS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Unknown,
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    /// Level named by `name` (`WARN`, `warning`, `err`, ...).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "trace" => Some(Self::Trace),
+            "debug" => Some(Self::Debug),
+            "info" | "notice" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" | "err" => Some(Self::Error),
+            "fatal" | "critical" | "crit" | "panic" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+
+    /// Level of one line of service output.
+    pub fn detect(line: &str) -> Self {
+        if let Some(object) = json_object(line) {
+            return match (object.get("level"), object.get("message")) {
+                (Some(serde_json::Value::String(level)), _) => Self::parse(level).unwrap_or(Self::Unknown),
+                (_, Some(serde_json::Value::String(message))) => Self::detect(message),
+                _ => Self::Unknown,
+            };
+        }
+        line.split_whitespace()
+            .take(LEVEL_WORDS)
+            .find_map(|word| {
+                let word = word.split_once('=').map_or(word, |(key, value)| match key {
+                    "level" | "lvl" => value,
+                    _ => word,
+                });
+                Self::parse(word.trim_matches(|c: char| !c.is_ascii_alphabetic()))
+            })
+            .unwrap_or(Self::Unknown)
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("unknown level '{}' (expected trace, debug, info, warn, error or fatal)", s))
+    }
+}
+
+/// When a line was written, if it says: the `timestamp` field of a JSON
+/// line, or a leading RFC 3339 timestamp.
+pub fn detect_timestamp(line: &str) -> Option<SystemTime> {
+    if let Some(object) = json_object(line) {
+        return object.get("timestamp")?.as_str().and_then(logging::parse_rfc3339);
+    }
+    let mut words = line.split_whitespace();
+    let first = words.next()?;
+    logging::parse_rfc3339(first).or_else(|| logging::parse_rfc3339(&format!("{} {}", first, words.next()?)))
+}
+
+fn json_object(line: &str) -> Option<serde_json::Map<String, serde_json::Value>> {
+    if !line.trim_start().starts_with('{') {
+        return None;
+    }
+    match serde_json::from_str(line) {
+        Ok(serde_json::Value::Object(object)) => Some(object),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    /// Drop lines below this level (lines of unknown level are kept)
+    pub min_level: Option<LogLevel>,
+    /// Drop lines written before this time (lines of unknown time are kept)
+    pub since: Option<SystemTime>,
+}
+
+impl LogFilter {
+    pub fn is_empty(&self) -> bool {
+        self.min_level.is_none() && self.since.is_none()
+    }
+
+    pub fn matches(&self, level: LogLevel, timestamp: Option<SystemTime>) -> bool {
+        let level_ok = match self.min_level {
+            Some(min_level) => level == LogLevel::Unknown || level >= min_level,
+            None => true,
+        };
+        let time_ok = match (self.since, timestamp) {
+            (Some(since), Some(timestamp)) => timestamp >= since,
+            _ => true,
+        };
+        level_ok && time_ok
+    }
+
+    /// Judges lines in order by their own text. A line without a timestamp
+    /// is taken to be as old as the one before it.
+    pub fn matcher(&self) -> impl FnMut(&str) -> bool + '_ {
+        let mut last_timestamp = None;
+        move |line| {
+            let timestamp = detect_timestamp(line).or(last_timestamp);
+            last_timestamp = timestamp;
+            self.matches(LogLevel::detect(line), timestamp)
+        }
+    }
+}
+
+/// `--since`: a span back from `now` (`90s`, `10m`, `1h30m`, `2d`) or an
+/// absolute time (`2024-05-01`, `2024-05-01T12:00:00Z`, ...).
+pub fn parse_since(value: &str, now: SystemTime) -> Result<SystemTime> {
+    if let Some(time) = logging::parse_rfc3339(value) {
+        return Ok(time);
+    }
+    let span = parse_span(value)
+        .ok_or_else(|| anyhow::anyhow!("Invalid --since '{}': expected a duration like 10m or a time like 2024-05-01T12:00:00Z", value))?;
+    Ok(now.checked_sub(span).unwrap_or(SystemTime::UNIX_EPOCH))
+}
+
+/// `1h30m` -> 5400s. Units are s, m, h and d.
+fn parse_span(value: &str) -> Option<Duration> {
+    let mut secs = 0u64;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let amount: u64 = rest[..digits].parse().ok()?;
+        let unit = match rest[digits..].chars().next()? {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            _ => return None,
+        };
+        secs = secs.checked_add(amount.checked_mul(unit)?)?;
+        rest = &rest[digits + 1..];
+    }
+    Some(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn test_detect_level() {
+        assert_eq!(LogLevel::detect("2024-05-01T12:00:00Z  WARN hive: disk almost full"), LogLevel::Warn);
+        assert_eq!(LogLevel::detect("[error] connection refused"), LogLevel::Error);
+        assert_eq!(LogLevel::detect("ts=1 level=debug msg=tick"), LogLevel::Debug);
+        assert_eq!(LogLevel::detect(r#"{"level":"INFO","message":"ready"}"#), LogLevel::Info);
+        assert_eq!(LogLevel::detect(r#"{"timestamp":"x","message":"FATAL: out of memory"}"#), LogLevel::Fatal);
+        assert_eq!(LogLevel::detect("    at main.rs:10"), LogLevel::Unknown);
+    }
+
+    #[test]
+    fn test_filter_keeps_unknown_level_and_inherits_timestamps() {
+        let since = UNIX_EPOCH + Duration::from_secs(1_714_564_800); // 2024-05-01T12:00:00Z
+        let filter = LogFilter {
+            min_level: Some(LogLevel::Warn),
+            since: Some(since),
+        };
+        let lines = [
+            "2024-05-01T11:59:00Z ERROR too old",
+            "  continuation of too old",
+            "2024-05-01T12:00:01Z INFO too quiet",
+            "2024-05-01T12:00:02Z ERROR kept",
+            "  continuation of kept",
+        ];
+        let mut matches = filter.matcher();
+        let kept: Vec<_> = lines.into_iter().filter(|line| matches(line)).collect();
+        assert_eq!(kept, ["2024-05-01T12:00:02Z ERROR kept", "  continuation of kept"]);
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = UNIX_EPOCH + Duration::from_secs(100_000);
+        assert_eq!(parse_since("10m", now).unwrap(), now - Duration::from_secs(600));
+        assert_eq!(parse_since("1h30m", now).unwrap(), now - Duration::from_secs(5400));
+        assert_eq!(parse_since("1970-01-01T00:01:00Z", now).unwrap(), UNIX_EPOCH + Duration::from_secs(60));
+        assert!(parse_since("10", now).is_err());
+        assert!(parse_since("soon", now).is_err());
+    }
+}
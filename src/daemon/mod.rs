@@ -1,11 +1,17 @@
 pub mod client;
 pub mod executor;
 pub mod health;
+pub mod interpolate;
 pub mod log_buffer;
+pub mod log_files;
+pub mod log_filter;
+pub mod probe;
+pub mod proc_stats;
 pub mod protocol;
 pub mod server;
 pub mod services;
 pub mod setup;
+pub mod snapshot;
 
 pub use client::DaemonClient;
 pub use executor::CommandExecutor;
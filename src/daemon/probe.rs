@@ -0,0 +1,126 @@
+//! Health probes for daemon services.
+//!
+//! Without a probe a service is healthy as long as its process is alive. A
+//! plugin can declare one in plugin.toml so a hung service is caught too:
+//!
+//! ```toml
+//! [daemon.health_check]
+//! http_url = "http://127.0.0.1:8080/health"  # or: tcp_port = 8080
+//! failure_threshold = 3                      # consecutive failures before restart
+//! timeout_secs = 2
+//! ```
+//!
+//! The health manager restarts the service (per its restart policy) after
+//! `failure_threshold` failed probes in a row, and dependents wait for the
+//! probe to pass before starting.
+
+use std::time::Duration;
+
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Probe {
+    /// A TCP connection to this port on localhost succeeds
+    Tcp { port: u16 },
+    /// A GET to this URL answers with a 2xx status
+    Http { url: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthCheck {
+    pub probe: Probe,
+    pub failure_threshold: u32,
+    pub timeout: Duration,
+}
+
+impl HealthCheck {
+    /// `[daemon.health_check]` from a plugin.toml; `None` if absent or if it
+    /// names neither `http_url` nor `tcp_port`.
+    pub fn from_manifest(manifest: &str) -> Option<Self> {
+        let table = manifest.parse::<toml::Table>().ok()?;
+        let section = table.get("daemon")?.get("health_check")?;
+        let probe = if let Some(url) = section.get("http_url").and_then(|v| v.as_str()) {
+            Probe::Http { url: url.to_string() }
+        } else {
+            let port = section.get("tcp_port")?.as_integer()?;
+            Probe::Tcp { port: u16::try_from(port).ok()? }
+        };
+        let integer = |key: &str| section.get(key).and_then(|v| v.as_integer()).and_then(|v| u64::try_from(v).ok());
+        Some(Self {
+            probe,
+            failure_threshold: integer("failure_threshold")
+                .map(|n| u32::try_from(n.max(1)).unwrap_or(u32::MAX))
+                .unwrap_or(DEFAULT_FAILURE_THRESHOLD),
+            timeout: integer("timeout_secs").map(Duration::from_secs).unwrap_or(DEFAULT_PROBE_TIMEOUT),
+        })
+    }
+
+    /// Run the probe once. The error describes why it failed.
+    pub async fn run(&self) -> Result<(), String> {
+        match &self.probe {
+            Probe::Tcp { port } => {
+                match tokio::time::timeout(self.timeout, tokio::net::TcpStream::connect(("127.0.0.1", *port))).await {
+                    Ok(Ok(_)) => Ok(()),
+                    Ok(Err(e)) => Err(format!("TCP port {}: {}", port, e)),
+                    Err(_) => Err(format!("TCP port {}: timed out after {:?}", port, self.timeout)),
+                }
+            }
+            Probe::Http { url } => {
+                let client = reqwest::Client::builder()
+                    .user_agent(crate::http::user_agent())
+                    .timeout(self.timeout)
+                    .no_proxy()
+                    .build()
+                    .map_err(|e| e.to_string())?;
+                let response = client.get(url).send().await.map_err(|e| format!("{}: {}", url, e))?;
+                if response.status().is_success() {
+                    Ok(())
+                } else {
+                    Err(format!("{}: HTTP {}", url, response.status()))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_check_from_manifest() {
+        let http = "[daemon.health_check]\nhttp_url = \"http://127.0.0.1:8080/health\"\nfailure_threshold = 5\n";
+        assert_eq!(
+            HealthCheck::from_manifest(http),
+            Some(HealthCheck {
+                probe: Probe::Http { url: "http://127.0.0.1:8080/health".into() },
+                failure_threshold: 5,
+                timeout: DEFAULT_PROBE_TIMEOUT,
+            })
+        );
+
+        let tcp = HealthCheck::from_manifest("[daemon.health_check]\ntcp_port = 9000\ntimeout_secs = 1\n").unwrap();
+        assert_eq!(tcp.probe, Probe::Tcp { port: 9000 });
+        assert_eq!(tcp.failure_threshold, DEFAULT_FAILURE_THRESHOLD);
+        assert_eq!(tcp.timeout, Duration::from_secs(1));
+
+        assert!(HealthCheck::from_manifest("[daemon]\nauto_start = true\n").is_none());
+        assert!(HealthCheck::from_manifest("[daemon.health_check]\ntcp_port = 70000\n").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tcp_probe() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let check = HealthCheck {
+            probe: Probe::Tcp { port },
+            failure_threshold: 1,
+            timeout: DEFAULT_PROBE_TIMEOUT,
+        };
+        assert!(check.run().await.is_ok());
+
+        drop(listener);
+        assert!(check.run().await.is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Resource usage of service processes, sampled from the OS.
+//!
+//! Linux reads `/proc/<pid>/statm` and `/proc/<pid>/stat`; other Unixes fall
+//! back to `ps`. A process that has exited yields `None`.
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessStats {
+    pub rss_bytes: u64,
+    /// Total user + system CPU time consumed so far
+    pub cpu_time: Duration,
+}
+
+pub fn sample(pid: u32) -> Option<ProcessStats> {
+    let stats = sample_impl(pid);
+    tracing::trace!(pid = pid, stats = ?stats, "Sampled process stats");
+    stats
+}
+
+/// CPU usage between two samples taken `elapsed` apart, as a percentage of
+/// one core (so a busy multi-threaded process can exceed 100).
+pub fn cpu_percent(prev: &ProcessStats, next: &ProcessStats, elapsed: Duration) -> f32 {
+    if elapsed.is_zero() {
+        return 0.0;
+    }
+    let used = next.cpu_time.saturating_sub(prev.cpu_time);
+    (used.as_secs_f64() / elapsed.as_secs_f64() * 100.0) as f32
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn sample_impl(pid: u32) -> Option<ProcessStats> {
+    let statm = std::fs::read_to_string(format!("/proc/{}/statm", pid)).ok()?;
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as u64;
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+
+    let resident_pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    let (utime, stime) = parse_stat_cpu_ticks(&stat)?;
+
+    Some(ProcessStats {
+        rss_bytes: resident_pages * page_size,
+        cpu_time: Duration::from_millis((utime + stime) * 1000 / ticks),
+    })
+}
+
+/// utime and stime (fields 14 and 15) from `/proc/<pid>/stat`. The command
+/// name in field 2 may contain spaces, so fields are counted after its `)`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_stat_cpu_ticks(stat: &str) -> Option<(u64, u64)> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let mut fields = rest.split_whitespace().skip(11);
+    let utime = fields.next()?.parse().ok()?;
+    let stime = fields.next()?.parse().ok()?;
+    Some((utime, stime))
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn sample_impl(pid: u32) -> Option<ProcessStats> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "rss=", "-o", "time=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut fields = text.split_whitespace();
+    let rss_kib: u64 = fields.next()?.parse().ok()?;
+    let cpu_time = parse_ps_time(fields.next()?)?;
+    Some(ProcessStats {
+        rss_bytes: rss_kib * 1024,
+        cpu_time,
+    })
+}
+
+/// `ps` TIME column: `[[dd-]hh:]mm:ss[.ff]`
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+fn parse_ps_time(value: &str) -> Option<Duration> {
+    let (days, clock) = match value.split_once('-') {
+        Some((d, rest)) => (d.parse::<u64>().ok()?, rest),
+        None => (0, value),
+    };
+    let mut secs = 0.0_f64;
+    for part in clock.split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(Duration::from_secs_f64(secs + (days * 86400) as f64))
+}
+
+#[cfg(not(unix))]
+fn sample_impl(_pid: u32) -> Option<ProcessStats> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_handles_spaces_in_comm() {
+        let stat = "1234 (my (odd) svc) S 1 1234 1234 0 -1 4194560 100 0 0 0 250 50 0 0 20 0 1 0";
+        assert_eq!(parse_stat_cpu_ticks(stat), Some((250, 50)));
+    }
+
+    #[test]
+    fn test_parse_ps_time() {
+        assert_eq!(parse_ps_time("0:01.50"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_ps_time("01:02:03"), Some(Duration::from_secs(3723)));
+        assert_eq!(parse_ps_time("1-00:00:01"), Some(Duration::from_secs(86401)));
+    }
+
+    #[test]
+    fn test_cpu_percent() {
+        let prev = ProcessStats { rss_bytes: 0, cpu_time: Duration::from_millis(100) };
+        let next = ProcessStats { rss_bytes: 0, cpu_time: Duration::from_millis(600) };
+        assert_eq!(cpu_percent(&prev, &next, Duration::from_secs(1)), 50.0);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sample_current_process() {
+        let stats = sample(std::process::id()).unwrap();
+        assert!(stats.rss_bytes > 0);
+    }
+}
@@ -1,22 +1,36 @@
 use super::executor::CommandExecutor;
 use super::health::HealthManager;
 use super::log_buffer::LogBuffer;
+use super::log_files::{RotationPolicy, ServiceLogFiles};
 use super::protocol::{ArchivedRequest, MessageFrame, Response};
-use super::services::ServiceManager;
+use super::services::{ReloadReport, ServiceManager};
 use crate::clienv;
 use anyhow::Result;
 use lib_daemon_core::{PidFile, ShutdownCoordinator, ShutdownHandle};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-use tracing::{debug, error, info, trace, warn};
+use tracing::{debug, error, info, trace, warn, Instrument};
 
 pub struct DaemonConfig {
     pub socket_path: std::path::PathBuf,
     pub pid_path: std::path::PathBuf,
     pub log_path: std::path::PathBuf,
     pub auto_start: Vec<String>,
+    /// Rescan plugin manifests on this interval (opt-in, None disables)
+    pub refresh_interval: Option<std::time::Duration>,
+    /// Where each service's output is persisted as `<service>.log`
+    pub service_logs_dir: std::path::PathBuf,
+    pub service_log_rotation: RotationPolicy,
+    /// Commands `SudoRun` may execute (from the root-owned [`super::executor::SUDO_ALLOWLIST_PATH`])
+    pub sudo_allowed_commands: Vec<String>,
+    /// Where the outcome of each SIGHUP reload is written for `adi daemon reload`
+    pub reload_report_path: std::path::PathBuf,
+    /// Services running at the last shutdown are recorded here and started
+    /// again on the next run (None: only `auto_start` is started)
+    pub services_snapshot_path: Option<std::path::PathBuf>,
 }
 
 impl Default for DaemonConfig {
@@ -26,6 +40,14 @@ impl Default for DaemonConfig {
             pid_path: clienv::daemon_pid_path(),
             log_path: clienv::daemon_log_path(),
             auto_start: Vec::new(),
+            refresh_interval: clienv::daemon_refresh_interval(),
+            service_logs_dir: clienv::service_logs_dir(),
+            service_log_rotation: RotationPolicy::from_env(),
+            sudo_allowed_commands: super::executor::load_sudo_allowlist(std::path::Path::new(
+                super::executor::SUDO_ALLOWLIST_PATH,
+            )),
+            reload_report_path: clienv::daemon_reload_report_path(),
+            services_snapshot_path: clienv::daemon_restore_services().then(clienv::daemon_services_snapshot_path),
         }
     }
 }
@@ -37,12 +59,22 @@ pub struct DaemonServer {
     started_at: Instant,
     version: String,
     shutdown_handle: Option<ShutdownHandle>,
+    /// Id of the next accepted connection. Each connection carries one
+    /// request, and its logs are recorded under a `request{id=..}` span.
+    next_request_id: AtomicU64,
+    /// Only processes running as this user may talk to the daemon
+    #[cfg(unix)]
+    owner_uid: u32,
 }
 
 impl DaemonServer {
     pub async fn new(mut config: DaemonConfig) -> Self {
-        let log_buffer = Arc::new(LogBuffer::default());
+        let log_files = ServiceLogFiles::new(config.service_logs_dir.clone(), config.service_log_rotation);
+        let log_buffer = Arc::new(LogBuffer::default().with_files(log_files));
         let mut manager = ServiceManager::new(Arc::clone(&log_buffer));
+        if let Some(path) = &config.services_snapshot_path {
+            manager = manager.with_snapshot(path.clone());
+        }
         if let Err(e) = manager.discover_plugins().await {
             warn!("Failed to discover plugin daemon services: {}", e);
         }
@@ -56,13 +88,17 @@ impl DaemonServer {
             }
         }
 
+        let executor = Arc::new(CommandExecutor::new().with_sudo_allowlist(config.sudo_allowed_commands.clone()));
         Self {
             config,
             services: Arc::new(manager),
-            executor: Arc::new(CommandExecutor::new()),
+            executor,
             started_at: Instant::now(),
             version: env!("CARGO_PKG_VERSION").to_string(),
             shutdown_handle: None,
+            next_request_id: AtomicU64::new(1),
+            #[cfg(unix)]
+            owner_uid: unsafe { libc::geteuid() },
         }
     }
 
@@ -102,18 +138,45 @@ impl DaemonServer {
             std::fs::set_permissions(&self.config.socket_path, perms)?;
         }
 
-        for name in &self.config.auto_start {
-            info!("Auto-starting service: {}", name);
-            if let Err(e) = self.services.start(name, None).await {
-                warn!("Failed to auto-start '{}': {}", name, e);
+        let mut start = self.config.auto_start.clone();
+        for name in self.services.restorable().await {
+            if !start.contains(&name) {
+                info!("Restoring service that was running before: {}", name);
+                start.push(name);
             }
         }
+        if let Err(e) = self.services.start_all(&start).await {
+            warn!("Failed to auto-start services: {}", e);
+        }
 
         let health_manager = HealthManager::new(&self.services);
         tokio::spawn(async move {
             health_manager.run().await;
         });
 
+        if let Some(interval) = self.config.refresh_interval {
+            info!("Refreshing plugin services every {:?}", interval);
+            tokio::spawn(refresh_loop(Arc::clone(&self.services), interval));
+        }
+
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut sighup = signal(SignalKind::hangup())?;
+            let services = Arc::clone(&self.services);
+            let report_path = self.config.reload_report_path.clone();
+            tokio::spawn(async move {
+                while sighup.recv().await.is_some() {
+                    info!("Received SIGHUP, reloading plugin services");
+                    let result = reload_services(&services).await;
+                    if let Err(e) = &result {
+                        warn!("Failed to reload plugin services: {}", e);
+                    }
+                    write_reload_report(&report_path, &result.map_err(|e| e.to_string()));
+                }
+            });
+        }
+
         let mut shutdown = ShutdownCoordinator::new();
         self.shutdown_handle = Some(shutdown.handle());
 
@@ -157,11 +220,13 @@ impl DaemonServer {
                     match conn {
                         Ok((stream, _)) => {
                             let server = Arc::clone(&server);
+                            let id = server.next_request_id.fetch_add(1, Ordering::Relaxed);
+                            let span = tracing::info_span!("request", id, client_pid = tracing::field::Empty);
                             tokio::spawn(async move {
                                 if let Err(e) = server.handle_connection(stream).await {
                                     error!("Connection handler error: {}", e);
                                 }
-                            });
+                            }.instrument(span));
                         }
                         Err(e) => {
                             error!("Accept error: {}", e);
@@ -190,6 +255,25 @@ impl DaemonServer {
     async fn handle_connection(&self, mut stream: tokio::net::UnixStream) -> Result<()> {
         trace!("New connection accepted");
 
+        // The socket is 0600, but check who is on the other end too: SudoRun
+        // must never be reachable by another user.
+        let peer = stream.peer_cred()?;
+        if let Some(pid) = peer.pid() {
+            tracing::Span::current().record("client_pid", pid);
+        }
+        if peer.uid() != self.owner_uid {
+            warn!(
+                "Rejecting connection from uid {} (pid {:?}); the daemon belongs to uid {}",
+                peer.uid(),
+                peer.pid(),
+                self.owner_uid
+            );
+            let response = Response::Error {
+                message: "Permission denied: the daemon only accepts connections from the user that started it".to_string(),
+            };
+            return write_response(&mut stream, &response).await;
+        }
+
         let mut len_buf = [0u8; 4];
         stream.read_exact(&mut len_buf).await?;
         let len = MessageFrame::read_length(&len_buf);
@@ -201,17 +285,56 @@ impl DaemonServer {
         let archived = rkyv::access::<ArchivedRequest, rkyv::rancor::Error>(&request_buf)
             .map_err(|e| anyhow::anyhow!("Failed to deserialize request: {}", e))?;
 
-        let response = self.handle_request(archived).await;
+        if let ArchivedRequest::ServiceLogs { name, lines, follow: true } = archived {
+            let n: usize = (*lines).try_into().unwrap_or(100);
+            return self.follow_logs(&mut stream, name.as_str(), n).await;
+        }
 
-        let response_bytes = MessageFrame::encode_response(&response)
-            .map_err(|e| anyhow::anyhow!("Failed to encode response: {}", e))?;
-        stream.write_all(&response_bytes).await?;
-        stream.flush().await?;
+        let response = self.handle_request(archived).await;
+        write_response(&mut stream, &response).await?;
 
         trace!("Response sent");
         Ok(())
     }
 
+    /// Stream a service's last `lines` lines, then every new one, as
+    /// `LogLine` frames. Ends when the client hangs up (dropping the
+    /// subscription) or with `StreamEnd` if the log buffer goes away.
+    #[cfg(unix)]
+    async fn follow_logs(&self, stream: &mut tokio::net::UnixStream, name: &str, lines: usize) -> Result<()> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        debug!("Handling: ServiceLogs({}, lines: {}, follow)", name, lines);
+        // Subscribe before reading the backlog so no line falls in between.
+        let mut new_lines = self.services.log_buffer().subscribe();
+        for line in self.services.log_buffer().tail(name, lines) {
+            write_response(stream, &Response::LogLine { line }).await?;
+        }
+
+        // The client sends nothing after its request, so a readable socket means it hung up.
+        let mut probe = [0u8; 1];
+        loop {
+            let received = tokio::select! {
+                received = new_lines.recv() => received,
+                _ = stream.read(&mut probe) => {
+                    trace!("Log follower for {} disconnected", name);
+                    return Ok(());
+                }
+            };
+            match received {
+                Ok((service, line)) if service == name => {
+                    write_response(stream, &Response::LogLine { line }).await?;
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => warn!("Log follower for {} skipped {} lines", name, skipped),
+                Err(RecvError::Closed) => {
+                    write_response(stream, &Response::StreamEnd).await?;
+                    return Ok(());
+                }
+            }
+        }
+    }
+
     #[cfg(not(unix))]
     async fn handle_connection(&self, mut stream: tokio::net::TcpStream) -> Result<()> {
         trace!("New connection accepted");
@@ -332,6 +455,67 @@ impl DaemonServer {
     }
 }
 
+async fn write_response<W: tokio::io::AsyncWrite + Unpin>(stream: &mut W, response: &Response) -> Result<()> {
+    let response_bytes = MessageFrame::encode_response(response)
+        .map_err(|e| anyhow::anyhow!("Failed to encode response: {}", e))?;
+    stream.write_all(&response_bytes).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// Periodically rescan installed plugins so services from plugins installed
+/// or updated after the daemon started become available without a restart.
+async fn refresh_loop(services: Arc<ServiceManager>, interval: std::time::Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        match reload_services(&services).await {
+            Ok(report) if report.is_empty() => trace!("Plugin service refresh: no changes"),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to refresh plugin services: {}", e),
+        }
+    }
+}
+
+/// Rescan installed plugins, then auto-start the new services that ask for it.
+async fn reload_services(services: &ServiceManager) -> Result<ReloadReport> {
+    let report = services.reload().await?;
+    let auto_start = services.auto_start_names();
+    for name in &report.added {
+        info!("Discovered new plugin service: {}", name);
+        if auto_start.contains(name) {
+            if let Err(e) = services.start(name, None).await {
+                warn!("Failed to auto-start '{}': {}", name, e);
+            }
+        }
+    }
+    for name in &report.changed {
+        info!("Config of running service '{}' changed; restart it to apply", name);
+    }
+    for name in &report.orphaned {
+        warn!("Service '{}' is still running but its plugin was uninstalled", name);
+    }
+    for name in &report.removed {
+        info!("Removed service of uninstalled plugin: {}", name);
+    }
+    Ok(report)
+}
+
+/// Best effort; `adi daemon reload` gives up waiting if this never lands.
+#[cfg(unix)]
+fn write_reload_report(path: &std::path::Path, result: &std::result::Result<ReloadReport, String>) {
+    let written = serde_json::to_vec(result).map_err(std::io::Error::from).and_then(|json| {
+        // Renamed into place so the CLI never reads half a report.
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    });
+    if let Err(e) = written {
+        warn!("Failed to write reload report {}: {}", path.display(), e);
+    }
+}
+
 fn deserialize_service_config(
     archived: &super::protocol::ArchivedServiceConfig,
 ) -> super::protocol::ServiceConfig {
@@ -1,12 +1,16 @@
+use super::interpolate;
 use super::log_buffer::LogBuffer;
+use super::probe::HealthCheck;
 use super::protocol::{ServiceConfig, ServiceInfo, ServiceState};
+use super::snapshot::Snapshot;
 use crate::clienv;
+use crate::logging;
 use anyhow::Result;
 use lib_daemon_core::is_process_running;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::process::Stdio;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::RwLock;
@@ -14,30 +18,127 @@ use tracing::{debug, error, info, warn};
 
 pub struct ServiceManager {
     services: Arc<RwLock<HashMap<String, ManagedService>>>,
-    registry: ServiceRegistry,
+    registry: std::sync::RwLock<ServiceRegistry>,
     log_buffer: Arc<LogBuffer>,
+    /// Services to bring back after a daemon restart; `None` leaves that to `auto_start`
+    snapshot: Option<Snapshot>,
+}
+
+/// Longest wait between automatic restarts, however many crashes preceded it.
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+/// How long a dependency may take to become ready before its dependents give up.
+const DEPENDENCY_READY_TIMEOUT: Duration = Duration::from_secs(30);
+/// A dependency counts as up once its process has survived this long.
+const DEPENDENCY_SETTLE: Duration = Duration::from_millis(500);
+
+/// How crashed services are restarted. Set per plugin under `[daemon]` in
+/// plugin.toml (`restart_backoff_base`, `stable_reset_secs`, both seconds).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// Delay before the first restart; doubles with each further restart
+    pub backoff_base: Duration,
+    /// Uptime after which earlier crashes no longer count against `max_restarts`
+    pub stable_reset: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            backoff_base: Duration::from_secs(1),
+            stable_reset: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Wait before restart number `restarts + 1`: base, 2×base, 4×base, …
+    /// capped at [`MAX_RESTART_BACKOFF`].
+    pub fn backoff(&self, restarts: u32) -> Duration {
+        let factor = 1u32.checked_shl(restarts).unwrap_or(u32::MAX);
+        self.backoff_base.saturating_mul(factor).min(MAX_RESTART_BACKOFF)
+    }
+
+    fn from_manifest(manifest: &str) -> Self {
+        let defaults = Self::default();
+        let Ok(table) = manifest.parse::<toml::Table>() else {
+            return defaults;
+        };
+        let secs = |key: &str| {
+            table
+                .get("daemon")
+                .and_then(|d| d.get(key))
+                .and_then(|v| v.as_integer())
+                .and_then(|v| u64::try_from(v).ok())
+                .map(Duration::from_secs)
+        };
+        Self {
+            backoff_base: secs("restart_backoff_base").unwrap_or(defaults.backoff_base),
+            stable_reset: secs("stable_reset_secs").unwrap_or(defaults.stable_reset),
+        }
+    }
 }
 
 pub struct ManagedService {
     pub config: ServiceConfig,
+    pub restart_policy: RestartPolicy,
     pub state: ServiceState,
     pub process: Option<Child>,
+    /// When the current process was started (or last restarted)
     pub started_at: Option<Instant>,
-    /// Number of restarts since daemon started
+    /// Automatic restarts since the daemon started or the service last
+    /// stayed up for `restart_policy.stable_reset`
     pub restarts: u32,
     pub last_error: Option<String>,
+    /// Probe run on top of the process-alive check (`[daemon.health_check]`)
+    pub health_check: Option<HealthCheck>,
+    /// Consecutive failed probes of the current process
+    pub probe_failures: u32,
+    /// A reload found a different declared config while the process was
+    /// running; the next start or restart picks it up
+    pub config_changed: bool,
+    /// The plugin that declared it was uninstalled while it was running
+    pub orphaned: bool,
 }
 
 impl ManagedService {
     pub fn new(config: ServiceConfig) -> Self {
         Self {
             config,
+            restart_policy: RestartPolicy::default(),
             state: ServiceState::Stopped,
             process: None,
             started_at: None,
             restarts: 0,
             last_error: None,
+            health_check: None,
+            probe_failures: 0,
+            config_changed: false,
+            orphaned: false,
+        }
+    }
+
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.restart_policy = policy;
+        self
+    }
+
+    pub fn with_health_check(mut self, health_check: Option<HealthCheck>) -> Self {
+        self.health_check = health_check;
+        self
+    }
+
+    /// Forget earlier crashes once the service has been up for the stable
+    /// window, so crashes hours apart don't use up `max_restarts`. Returns
+    /// whether the counter was reset.
+    pub fn reset_restarts_if_stable(&mut self) -> bool {
+        let stable = self
+            .started_at
+            .is_some_and(|started| started.elapsed() >= self.restart_policy.stable_reset);
+        if self.restarts == 0 || !stable {
+            return false;
         }
+        self.restarts = 0;
+        true
     }
 
     pub fn pid(&self) -> Option<u32> {
@@ -64,9 +165,35 @@ impl ServiceManager {
     pub fn new(log_buffer: Arc<LogBuffer>) -> Self {
         Self {
             services: Arc::new(RwLock::new(HashMap::new())),
-            registry: ServiceRegistry::new(),
+            registry: std::sync::RwLock::new(ServiceRegistry::new()),
             log_buffer,
+            snapshot: None,
+        }
+    }
+
+    /// Record running services in the snapshot file at `path`, see
+    /// [`super::snapshot`].
+    pub fn with_snapshot(mut self, path: std::path::PathBuf) -> Self {
+        self.snapshot = Some(Snapshot::load(path));
+        self
+    }
+
+    /// Services that were running when the daemon last stopped, ready for
+    /// [`Self::start_all`]. Those no plugin declares anymore are registered
+    /// with the config they last ran with.
+    pub async fn restorable(&self) -> Vec<String> {
+        let Some(snapshot) = &self.snapshot else {
+            return Vec::new();
+        };
+        let mut services = self.services.write().await;
+        let mut names = Vec::new();
+        for (name, config) in snapshot.services() {
+            if self.registry().get_config(&name).is_none() {
+                services.entry(name.clone()).or_insert_with(|| ManagedService::new(config));
+            }
+            names.push(name);
         }
+        names
     }
 
     pub fn log_buffer(&self) -> &Arc<LogBuffer> {
@@ -74,16 +201,116 @@ impl ServiceManager {
     }
 
     /// Discover daemon services from installed plugin manifests
-    pub async fn discover_plugins(&mut self) -> Result<()> {
-        self.registry.discover_plugins().await
+    pub async fn discover_plugins(&self) -> Result<()> {
+        self.reload().await.map(|_| ())
+    }
+
+    /// Rescan plugin manifests and swap in the new registry without touching
+    /// running processes. See [`merge_registry`] for what happens to each
+    /// known service.
+    pub async fn reload(&self) -> Result<ReloadReport> {
+        let mut fresh = ServiceRegistry::new();
+        fresh.discover_plugins().await?;
+
+        let mut services = self.services.write().await;
+        let mut registry = self.registry.write().unwrap_or_else(|e| e.into_inner());
+        let report = merge_registry(&mut services, &registry, &fresh);
+        *registry = fresh;
+        Ok(report)
     }
 
     /// Return service names that should be started automatically at daemon startup
     pub fn auto_start_names(&self) -> Vec<String> {
-        self.registry.auto_start_names().to_vec()
+        self.registry().auto_start_names().to_vec()
+    }
+
+    fn registry(&self) -> std::sync::RwLockReadGuard<'_, ServiceRegistry> {
+        self.registry.read().unwrap_or_else(|e| e.into_inner())
     }
 
+    /// Start a service, first starting (and waiting for) any services it
+    /// declares in `depends_on` that aren't running yet.
     pub async fn start(&self, name: &str, config: Option<ServiceConfig>) -> Result<()> {
+        let order = start_order(&[name.to_string()], &|service| self.registry().dependencies(service))?;
+        for dependency in order.iter().filter(|service| *service != name) {
+            if self.get(dependency).await.is_some_and(|info| info.state.is_running()) {
+                continue;
+            }
+            info!("Starting '{}' before '{}'", dependency, name);
+            self.start_one(dependency, None)
+                .await
+                .map_err(|e| anyhow::anyhow!("Dependency '{}' of '{}' failed to start: {}", dependency, name, e))?;
+            self.wait_ready(dependency)
+                .await
+                .map_err(|e| anyhow::anyhow!("Dependency '{}' of '{}' is not ready: {}", dependency, name, e))?;
+        }
+        self.start_one(name, config).await
+    }
+
+    /// Start `names` (the daemon's auto-start list) dependencies first. A
+    /// service whose dependency failed is skipped, and so are its dependents.
+    /// A dependency cycle starts nothing.
+    pub async fn start_all(&self, names: &[String]) -> Result<()> {
+        let order = start_order(names, &|service| self.registry().dependencies(service))?;
+        let mut failed: HashSet<String> = HashSet::new();
+        for name in &order {
+            let dependencies = self.registry().dependencies(name);
+            if let Some(dependency) = dependencies.iter().find(|d| failed.contains(*d)) {
+                warn!("Skipping '{}': its dependency '{}' failed to start", name, dependency);
+                failed.insert(name.clone());
+                continue;
+            }
+            if self.get(name).await.is_some_and(|info| info.state.is_running()) {
+                continue;
+            }
+
+            info!("Auto-starting service: {}", name);
+            let started = match self.start_one(name, None).await {
+                // Only wait when something depends on it.
+                Ok(()) if order.iter().any(|other| self.registry().dependencies(other).contains(name)) => {
+                    self.wait_ready(name).await
+                }
+                result => result,
+            };
+            if let Err(e) = started {
+                warn!("Failed to auto-start '{}': {}", name, e);
+                failed.insert(name.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait until a just-started service passes its health check or, without
+    /// one, has stayed up for [`DEPENDENCY_SETTLE`]. Fails if it exits or
+    /// takes longer than [`DEPENDENCY_READY_TIMEOUT`].
+    async fn wait_ready(&self, name: &str) -> Result<()> {
+        let deadline = Instant::now() + DEPENDENCY_READY_TIMEOUT;
+        loop {
+            let (state, started_at, error, health_check) = {
+                let services = self.services.read().await;
+                let service = services
+                    .get(name)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown service: {}", name))?;
+                (service.state, service.started_at, service.last_error.clone(), service.health_check.clone())
+            };
+            if !state.is_running() || !self.is_process_alive(name).await {
+                anyhow::bail!("exited ({})", error.unwrap_or_else(|| format!("{:?}", state)));
+            }
+            let ready = match health_check {
+                Some(check) => check.run().await.is_ok(),
+                None => started_at.is_some_and(|started| started.elapsed() >= DEPENDENCY_SETTLE),
+            };
+            if ready {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                anyhow::bail!("not ready after {:?}", DEPENDENCY_READY_TIMEOUT);
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    async fn start_one(&self, name: &str, config: Option<ServiceConfig>) -> Result<()> {
         let mut services = self.services.write().await;
 
         let service = if let Some(s) = services.get_mut(name) {
@@ -92,29 +319,54 @@ impl ServiceManager {
             }
             if let Some(cfg) = config {
                 s.config = cfg;
+            } else if s.config_changed {
+                if let Some(cfg) = self.registry().get_config(name) {
+                    info!("Applying reloaded config for service '{}'", name);
+                    s.config = cfg;
+                    s.restart_policy = self.registry().restart_policy(name);
+                    s.health_check = self.registry().health_check(name);
+                }
             }
+            s.config_changed = false;
             s
         } else {
             // Look up service config from registry
             let config = config
-                .or_else(|| self.registry.get_config(name))
+                .or_else(|| self.registry().get_config(name))
                 .ok_or_else(|| anyhow::anyhow!("Unknown service: {}", name))?;
-
-            services.insert(name.to_string(), ManagedService::new(config));
+            let policy = self.registry().restart_policy(name);
+            let health_check = self.registry().health_check(name);
+
+            services.insert(
+                name.to_string(),
+                ManagedService::new(config)
+                    .with_restart_policy(policy)
+                    .with_health_check(health_check),
+            );
             services.get_mut(name).unwrap()
         };
 
         service.state = ServiceState::Starting;
         service.last_error = None;
 
-        let mut cmd = Command::new(&service.config.command);
-        cmd.args(&service.config.args);
+        let config = match interpolate::resolve_config(name, &service.config) {
+            Ok(config) => config,
+            Err(e) => {
+                error!("Failed to start service '{}': {}", name, e);
+                service.state = ServiceState::Failed;
+                service.last_error = Some(e.to_string());
+                return Err(e);
+            }
+        };
 
-        for (key, value) in &service.config.env {
+        let mut cmd = Command::new(&config.command);
+        cmd.args(&config.args);
+
+        for (key, value) in &config.env {
             cmd.env(key, value);
         }
 
-        if let Some(ref dir) = service.config.working_dir {
+        if let Some(ref dir) = config.working_dir {
             cmd.current_dir(std::path::Path::new(dir));
         }
 
@@ -131,6 +383,10 @@ impl ServiceManager {
                 service.process = Some(child);
                 service.state = ServiceState::Running;
                 service.started_at = Some(Instant::now());
+                service.probe_failures = 0;
+                if let Some(snapshot) = &self.snapshot {
+                    snapshot.set_running(name, &service.config);
+                }
 
                 Ok(())
             }
@@ -143,7 +399,16 @@ impl ServiceManager {
         }
     }
 
+    /// Stop a service on request, so it stays stopped across daemon restarts.
     pub async fn stop(&self, name: &str, force: bool) -> Result<()> {
+        self.stop_process(name, force).await?;
+        if let Some(snapshot) = &self.snapshot {
+            snapshot.set_stopped(name);
+        }
+        Ok(())
+    }
+
+    async fn stop_process(&self, name: &str, force: bool) -> Result<()> {
         let mut services = self.services.write().await;
 
         let service = services
@@ -199,12 +464,13 @@ impl ServiceManager {
     }
 
     pub async fn restart(&self, name: &str) -> Result<()> {
+        // Left to `start` when a reload changed it, so the new one applies.
         let config = {
             let services = self.services.read().await;
-            services.get(name).map(|s| s.config.clone())
+            services.get(name).filter(|s| !s.config_changed).map(|s| s.config.clone())
         };
 
-        self.stop(name, false).await?;
+        self.stop_process(name, false).await?;
 
         {
             let mut services = self.services.write().await;
@@ -216,12 +482,15 @@ impl ServiceManager {
         self.start(name, config).await
     }
 
+    /// Sorted by service name
     pub async fn list(&self) -> Vec<ServiceInfo> {
         let services = self.services.read().await;
-        services
+        let mut infos: Vec<ServiceInfo> = services
             .iter()
             .map(|(name, service)| service.to_info(name))
-            .collect()
+            .collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
     }
 
     pub async fn get(&self, name: &str) -> Option<ServiceInfo> {
@@ -236,7 +505,7 @@ impl ServiceManager {
         };
 
         for name in names {
-            if let Err(e) = self.stop(&name, false).await {
+            if let Err(e) = self.stop_process(&name, false).await {
                 warn!("Failed to stop service '{}': {}", name, e);
             }
         }
@@ -276,6 +545,58 @@ impl ServiceManager {
     }
 }
 
+/// `roots` and everything they depend on (transitively), each service after
+/// its dependencies. Dependencies are visited in name order so the result is
+/// deterministic. A cycle is an error naming it.
+pub fn start_order(roots: &[String], dependencies: &dyn Fn(&str) -> Vec<String>) -> Result<Vec<String>> {
+    fn visit(
+        name: &str,
+        dependencies: &dyn Fn(&str) -> Vec<String>,
+        stack: &mut Vec<String>,
+        done: &mut HashSet<String>,
+        order: &mut Vec<String>,
+    ) -> Result<()> {
+        if done.contains(name) {
+            return Ok(());
+        }
+        if let Some(pos) = stack.iter().position(|n| n == name) {
+            let mut cycle = stack[pos..].to_vec();
+            cycle.push(name.to_string());
+            anyhow::bail!("Service dependency cycle: {}", cycle.join(" -> "));
+        }
+        stack.push(name.to_string());
+        let mut deps = dependencies(name);
+        deps.sort();
+        for dep in &deps {
+            visit(dep, dependencies, stack, done, order)?;
+        }
+        stack.pop();
+        done.insert(name.to_string());
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    let mut order = Vec::new();
+    let mut done = HashSet::new();
+    for root in roots {
+        visit(root, dependencies, &mut Vec::new(), &mut done, &mut order)?;
+    }
+    Ok(order)
+}
+
+/// `[daemon] depends_on = ["adi.hive"]` from a plugin.toml.
+fn depends_on_from_manifest(manifest: &str) -> Vec<String> {
+    let Ok(table) = manifest.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    table
+        .get("daemon")
+        .and_then(|d| d.get("depends_on"))
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
 /// Spawn background tasks that read stdout/stderr from a child process into the LogBuffer.
 fn spawn_log_readers(service_name: &str, child: &mut Child, log_buffer: &Arc<LogBuffer>) {
     if let Some(stdout) = child.stdout.take() {
@@ -284,7 +605,7 @@ fn spawn_log_readers(service_name: &str, child: &mut Child, log_buffer: &Arc<Log
         tokio::spawn(async move {
             let mut lines = BufReader::new(stdout).lines();
             while let Ok(Some(line)) = lines.next_line().await {
-                buf.push(&name, line);
+                buf.push(&name, logging::service_line(logging::format(), &name, "stdout", line));
             }
         });
     }
@@ -294,7 +615,7 @@ fn spawn_log_readers(service_name: &str, child: &mut Child, log_buffer: &Arc<Log
         tokio::spawn(async move {
             let mut lines = BufReader::new(stderr).lines();
             while let Ok(Some(line)) = lines.next_line().await {
-                buf.push(&name, line);
+                buf.push(&name, logging::service_line(logging::format(), &name, "stderr", line));
             }
         });
     }
@@ -302,6 +623,10 @@ fn spawn_log_readers(service_name: &str, child: &mut Child, log_buffer: &Arc<Log
 
 pub struct ServiceRegistry {
     builtin: HashMap<String, ServiceConfig>,
+    restart_policies: HashMap<String, RestartPolicy>,
+    /// Services each service needs running first (`[daemon] depends_on`)
+    dependencies: HashMap<String, Vec<String>>,
+    health_checks: HashMap<String, HealthCheck>,
     auto_start: Vec<String>,
 }
 
@@ -309,6 +634,9 @@ impl ServiceRegistry {
     pub fn new() -> Self {
         Self {
             builtin: HashMap::new(),
+            restart_policies: HashMap::new(),
+            dependencies: HashMap::new(),
+            health_checks: HashMap::new(),
             auto_start: Vec::new(),
         }
     }
@@ -317,12 +645,31 @@ impl ServiceRegistry {
         self.builtin.get(name).cloned()
     }
 
+    pub fn restart_policy(&self, name: &str) -> RestartPolicy {
+        self.restart_policies.get(name).copied().unwrap_or_default()
+    }
+
+    pub fn health_check(&self, name: &str) -> Option<HealthCheck> {
+        self.health_checks.get(name).cloned()
+    }
+
+    pub fn dependencies(&self, name: &str) -> Vec<String> {
+        self.dependencies.get(name).cloned().unwrap_or_default()
+    }
+
+    pub fn set_dependencies(&mut self, name: &str, dependencies: Vec<String>) {
+        self.dependencies.insert(name.to_string(), dependencies);
+    }
+
     pub fn register(&mut self, name: String, config: ServiceConfig) {
         self.builtin.insert(name, config);
     }
 
+    /// Sorted service names
     pub fn list(&self) -> Vec<String> {
-        self.builtin.keys().cloned().collect()
+        let mut names: Vec<String> = self.builtin.keys().cloned().collect();
+        names.sort();
+        names
     }
 
     pub fn auto_start_names(&self) -> &[String] {
@@ -385,12 +732,105 @@ impl ServiceRegistry {
         } else {
             info!("Discovered daemon service: {}", plugin_id);
         }
+        self.restart_policies
+            .insert(plugin_id.clone(), RestartPolicy::from_manifest(&content));
+        self.set_dependencies(plugin_id, depends_on_from_manifest(&content));
+        if let Some(health_check) = HealthCheck::from_manifest(&content) {
+            self.health_checks.insert(plugin_id.clone(), health_check);
+        }
         self.register(plugin_id.clone(), config);
 
         Ok(())
     }
 }
 
+/// What a reload changed, by service name.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ReloadReport {
+    /// Newly declared
+    pub added: Vec<String>,
+    /// Running with a config that no longer matches its declaration
+    pub changed: Vec<String>,
+    /// Still running, but its plugin was uninstalled
+    pub orphaned: Vec<String>,
+    /// Declared by an uninstalled plugin and not running, so forgotten
+    pub removed: Vec<String>,
+}
+
+impl ReloadReport {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.orphaned.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Reconcile `services` with a rescan: stopped services take their new
+/// declaration right away, running ones whose declaration changed are
+/// flagged `config_changed`, and services declared in `old` (or already
+/// orphaned) but missing from `fresh` are dropped if stopped and flagged
+/// `orphaned` if running. Services started with an ad-hoc config are left
+/// alone.
+fn merge_registry(
+    services: &mut HashMap<String, ManagedService>,
+    old: &ServiceRegistry,
+    fresh: &ServiceRegistry,
+) -> ReloadReport {
+    let mut report = ReloadReport::default();
+    for name in fresh.list() {
+        if old.get_config(&name).is_none() {
+            report.added.push(name.clone());
+        }
+        let Some(service) = services.get_mut(&name) else {
+            continue;
+        };
+        let config = fresh.get_config(&name).expect("listed by the registry");
+        let policy = fresh.restart_policy(&name);
+        let health_check = fresh.health_check(&name);
+        service.orphaned = false;
+        if service.state.is_running() {
+            let changed = !same_config(&service.config, &config)
+                || service.restart_policy != policy
+                || service.health_check != health_check;
+            if changed {
+                service.config_changed = true;
+                report.changed.push(name);
+            }
+            continue;
+        }
+        service.config = config;
+        service.restart_policy = policy;
+        service.health_check = health_check;
+        service.config_changed = false;
+    }
+
+    let mut gone: Vec<String> = services
+        .iter()
+        .filter(|(name, service)| (service.orphaned || old.get_config(name).is_some()) && fresh.get_config(name).is_none())
+        .map(|(name, _)| name.clone())
+        .collect();
+    gone.sort();
+    for name in gone {
+        if services[&name].state.is_running() {
+            services.get_mut(&name).expect("just listed").orphaned = true;
+            report.orphaned.push(name);
+        } else {
+            services.remove(&name);
+            report.removed.push(name);
+        }
+    }
+    report
+}
+
+fn same_config(a: &ServiceConfig, b: &ServiceConfig) -> bool {
+    let ServiceConfig { command, args, env, working_dir, restart_on_failure, max_restarts, privileged } = a;
+    *command == b.command
+        && *args == b.args
+        && *env == b.env
+        && *working_dir == b.working_dir
+        && *restart_on_failure == b.restart_on_failure
+        && *max_restarts == b.max_restarts
+        && *privileged == b.privileged
+}
+
 impl Default for ServiceRegistry {
     fn default() -> Self {
         Self::new()
@@ -418,6 +858,114 @@ mod tests {
         assert!(registry.get_config("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_service_registry_list_is_sorted() {
+        let mut registry = ServiceRegistry::new();
+        for name in ["indexer", "hive", "tasks"] {
+            registry.register(name.to_string(), ServiceConfig::new("test"));
+        }
+        assert_eq!(registry.list(), ["hive", "indexer", "tasks"]);
+    }
+
+    #[test]
+    fn test_restart_backoff_doubles_and_caps() {
+        let policy = RestartPolicy::default();
+        let delays: Vec<u64> = (0..4).map(|n| policy.backoff(n).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8]);
+        assert_eq!(policy.backoff(6), MAX_RESTART_BACKOFF);
+        assert_eq!(policy.backoff(100), MAX_RESTART_BACKOFF);
+    }
+
+    #[test]
+    fn test_restart_policy_from_manifest() {
+        let manifest = "[plugin]\nid = \"adi.hive\"\n\n[daemon]\nrestart_backoff_base = 5\nstable_reset_secs = 300\n";
+        let policy = RestartPolicy::from_manifest(manifest);
+        assert_eq!(policy.backoff_base, Duration::from_secs(5));
+        assert_eq!(policy.stable_reset, Duration::from_secs(300));
+        assert_eq!(RestartPolicy::from_manifest("[daemon]\n"), RestartPolicy::default());
+    }
+
+    #[test]
+    fn test_restarts_reset_after_stable_window() {
+        let mut service = ManagedService::new(ServiceConfig::new("test"));
+        service.restarts = 3;
+        service.started_at = Some(Instant::now());
+        assert!(!service.reset_restarts_if_stable());
+
+        service.started_at = Instant::now().checked_sub(Duration::from_secs(61));
+        assert!(service.reset_restarts_if_stable());
+        assert_eq!(service.restarts, 0);
+    }
+
+    #[test]
+    fn test_start_order_puts_dependencies_first() {
+        let mut registry = ServiceRegistry::new();
+        registry.set_dependencies("adi.indexer", vec!["adi.hive".into()]);
+        registry.set_dependencies("adi.hive", vec!["adi.db".into()]);
+        let deps = |name: &str| registry.dependencies(name);
+
+        let roots = ["adi.indexer".to_string(), "adi.tasks".to_string(), "adi.hive".to_string()];
+        assert_eq!(start_order(&roots, &deps).unwrap(), ["adi.db", "adi.hive", "adi.indexer", "adi.tasks"]);
+
+        registry.set_dependencies("adi.db", vec!["adi.indexer".into()]);
+        let deps = |name: &str| registry.dependencies(name);
+        let err = start_order(&roots, &deps).unwrap_err().to_string();
+        assert!(err.contains("adi.indexer -> adi.hive -> adi.db -> adi.indexer"), "{}", err);
+    }
+
+    #[test]
+    fn test_depends_on_from_manifest() {
+        let manifest = "[plugin]\nid = \"adi.indexer\"\n\n[daemon]\ndepends_on = [\"adi.hive\"]\n";
+        assert_eq!(depends_on_from_manifest(manifest), ["adi.hive"]);
+        assert!(depends_on_from_manifest("[daemon]\n").is_empty());
+    }
+
+    #[test]
+    fn test_merge_registry() {
+        let declared = |names: &[&str], command: &str| {
+            let mut registry = ServiceRegistry::new();
+            for name in names {
+                registry.register(name.to_string(), ServiceConfig::new(command));
+            }
+            registry
+        };
+        let old = declared(&["adi.hive", "adi.indexer", "adi.tasks", "adi.llm"], "old");
+        let mut fresh = declared(&["adi.hive", "adi.indexer", "adi.db"], "new");
+        fresh.register("adi.indexer".into(), ServiceConfig::new("old"));
+
+        let mut services = HashMap::new();
+        for (name, state) in [
+            ("adi.hive", ServiceState::Running),
+            ("adi.indexer", ServiceState::Running),
+            ("adi.tasks", ServiceState::Running),
+            ("adi.llm", ServiceState::Stopped),
+            ("scratch", ServiceState::Stopped),
+        ] {
+            let mut service = ManagedService::new(ServiceConfig::new("old"));
+            service.state = state;
+            services.insert(name.to_string(), service);
+        }
+
+        let report = merge_registry(&mut services, &old, &fresh);
+        assert_eq!(report.added, ["adi.db"]);
+        assert_eq!(report.changed, ["adi.hive"]);
+        assert_eq!(report.orphaned, ["adi.tasks"]);
+        assert_eq!(report.removed, ["adi.llm"]);
+
+        // Running processes keep the config they were started with.
+        assert_eq!(services["adi.hive"].config.command, "old");
+        assert!(services["adi.hive"].config_changed && !services["adi.indexer"].config_changed);
+        assert!(services["adi.tasks"].orphaned);
+        assert!(services.contains_key("scratch") && !services.contains_key("adi.llm"));
+
+        // Once stopped, an orphan is forgotten on the next reload.
+        services.get_mut("adi.tasks").unwrap().state = ServiceState::Stopped;
+        let report = merge_registry(&mut services, &fresh, &fresh);
+        assert_eq!(report.removed, ["adi.tasks"]);
+        assert_eq!(report.changed, ["adi.hive"]);
+        assert!(report.added.is_empty() && report.orphaned.is_empty());
+    }
+
     #[tokio::test]
     async fn test_service_manager_list() {
         let manager = ServiceManager::new(Arc::new(LogBuffer::default()));
@@ -1,13 +1,18 @@
 use anyhow::{bail, Context, Result};
 use lib_console_output::theme;
-use std::process::Command;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use super::executor::SUDO_ALLOWLIST_PATH;
 
 const SUDOERS_PATH: &str = "/etc/sudoers.d/adi-daemon";
 
-/// Run the daemon setup: create adi-root user, configure sudoers, prepare directories.
-pub async fn run_setup() -> Result<()> {
+/// Run the daemon setup: create adi-root user, configure sudoers, prepare
+/// directories. A non-empty `sudo_allow` replaces the privileged command allowlist.
+pub async fn run_setup(sudo_allow: &[String]) -> Result<()> {
     verify_platform()?;
     verify_interactive()?;
+    verify_sudo_allow(sudo_allow)?;
 
     println!(
         "{} ADI daemon setup — creates system user and privilege escalation rules",
@@ -26,6 +31,9 @@ pub async fn run_setup() -> Result<()> {
 
     setup_user(&root_user)?;
     setup_sudoers(&root_user)?;
+    if !sudo_allow.is_empty() {
+        setup_sudo_allowlist(sudo_allow)?;
+    }
 
     #[cfg(target_os = "macos")]
     setup_resolver_dir()?;
@@ -237,6 +245,58 @@ fn write_sudoers_safe(content: &str) -> Result<()> {
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Privileged command allowlist
+// ---------------------------------------------------------------------------
+
+/// Relative paths would resolve against whatever directory the daemon runs in.
+fn verify_sudo_allow(commands: &[String]) -> Result<()> {
+    for cmd in commands {
+        if cmd.is_empty() || (cmd.contains('/') && !cmd.starts_with('/')) {
+            bail!("--sudo-allow takes a command name or an absolute path, not '{}'", cmd);
+        }
+    }
+    Ok(())
+}
+
+/// Write the allowlist as root (`sudo tee`), so nothing running as the user
+/// can change it between writing and installing.
+fn setup_sudo_allowlist(commands: &[String]) -> Result<()> {
+    let dir = std::path::Path::new(SUDO_ALLOWLIST_PATH)
+        .parent()
+        .and_then(|p| p.to_str())
+        .context("Allowlist path has no parent directory")?;
+    run_sudo(&["mkdir", "-p", dir])?;
+    run_sudo(&["chown", "root", dir])?;
+    run_sudo(&["chmod", "0755", dir])?;
+
+    let mut tee = Command::new("sudo")
+        .args(["tee", SUDO_ALLOWLIST_PATH])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .context("Failed to execute: sudo tee")?;
+    let content = format!("# Commands the ADI daemon may run as root\n{}\n", commands.join("\n"));
+    tee.stdin
+        .take()
+        .context("sudo tee has no stdin")?
+        .write_all(content.as_bytes())
+        .context("Failed to write the allowlist")?;
+    let status = tee.wait().context("Failed to wait for sudo tee")?;
+    if !status.success() {
+        bail!("Command failed (exit {}): sudo tee {}", status, SUDO_ALLOWLIST_PATH);
+    }
+    run_sudo(&["chmod", "0644", SUDO_ALLOWLIST_PATH])?;
+
+    println!(
+        "  {} Privileged commands allowed: {} ({})",
+        theme::icons::SUCCESS,
+        commands.join(", "),
+        theme::muted(SUDO_ALLOWLIST_PATH),
+    );
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Resolver directory (macOS)
 // ---------------------------------------------------------------------------
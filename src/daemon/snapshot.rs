@@ -0,0 +1,156 @@
+//! Services that should be running, kept across daemon restarts.
+//!
+//! A service is recorded when it starts and forgotten when it is stopped on
+//! request, so the file holds the services the user wants up. Stopping
+//! everything at daemon shutdown and crashes leave it as is, which is the
+//! point: on the next start the daemon brings those services back alongside
+//! `auto_start`. Set `ADI_DAEMON_RESTORE_SERVICES=0` to rely on `auto_start`
+//! alone.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tracing::{trace, warn};
+
+use super::protocol::ServiceConfig;
+
+/// [`ServiceConfig`] as stored on disk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SavedConfig {
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: Vec<(String, String)>,
+    #[serde(default)]
+    working_dir: Option<String>,
+    restart_on_failure: bool,
+    max_restarts: u32,
+    #[serde(default)]
+    privileged: bool,
+}
+
+impl From<&ServiceConfig> for SavedConfig {
+    fn from(config: &ServiceConfig) -> Self {
+        Self {
+            command: config.command.clone(),
+            args: config.args.clone(),
+            env: config.env.clone(),
+            working_dir: config.working_dir.clone(),
+            restart_on_failure: config.restart_on_failure,
+            max_restarts: config.max_restarts,
+            privileged: config.privileged,
+        }
+    }
+}
+
+impl From<SavedConfig> for ServiceConfig {
+    fn from(saved: SavedConfig) -> Self {
+        ServiceConfig {
+            command: saved.command,
+            args: saved.args,
+            env: saved.env,
+            working_dir: saved.working_dir,
+            restart_on_failure: saved.restart_on_failure,
+            max_restarts: saved.max_restarts,
+            privileged: saved.privileged,
+        }
+    }
+}
+
+pub struct Snapshot {
+    path: PathBuf,
+    services: Mutex<BTreeMap<String, SavedConfig>>,
+}
+
+impl Snapshot {
+    /// The snapshot at `path`; empty if it doesn't exist or can't be read.
+    pub fn load(path: PathBuf) -> Self {
+        let services = match std::fs::read(&path) {
+            Ok(content) => serde_json::from_slice(&content).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable service snapshot {}: {}", path.display(), e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        Self {
+            path,
+            services: Mutex::new(services),
+        }
+    }
+
+    /// Recorded services with the config they were last started with, by name.
+    pub fn services(&self) -> Vec<(String, ServiceConfig)> {
+        self.lock()
+            .iter()
+            .map(|(name, saved)| (name.clone(), saved.clone().into()))
+            .collect()
+    }
+
+    pub fn set_running(&self, name: &str, config: &ServiceConfig) {
+        let mut services = self.lock();
+        let saved = SavedConfig::from(config);
+        if services.get(name) != Some(&saved) {
+            services.insert(name.to_string(), saved);
+            self.save(&services);
+        }
+    }
+
+    pub fn set_stopped(&self, name: &str) {
+        let mut services = self.lock();
+        if services.remove(name).is_some() {
+            self.save(&services);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, SavedConfig>> {
+        self.services.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Best effort: a snapshot that can't be written only costs the restore.
+    fn save(&self, services: &BTreeMap<String, SavedConfig>) {
+        trace!(path = %self.path.display(), services = services.len(), "Saving service snapshot");
+        if let Err(e) = write_atomic(&self.path, services) {
+            warn!("Failed to save service snapshot {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Write then rename, so a crash mid-write leaves the previous snapshot.
+fn write_atomic(path: &Path, services: &BTreeMap<String, SavedConfig>) -> std::io::Result<()> {
+    let json = serde_json::to_vec_pretty(services).map_err(std::io::Error::from)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_survives_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon-services.json");
+
+        let snapshot = Snapshot::load(path.clone());
+        assert!(snapshot.services().is_empty());
+        snapshot.set_running("adi.hive", &ServiceConfig::new("adi").args(["daemon", "run-service", "adi.hive"]));
+        snapshot.set_running("scratch", &ServiceConfig::new("sleep").args(["60"]));
+        snapshot.set_stopped("scratch");
+        assert!(!path.with_extension("json.tmp").exists());
+
+        let restored = Snapshot::load(path.clone()).services();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].0, "adi.hive");
+        assert_eq!(restored[0].1.args, ["daemon", "run-service", "adi.hive"]);
+
+        std::fs::write(&path, "{not json").unwrap();
+        assert!(Snapshot::load(path).services().is_empty());
+    }
+}
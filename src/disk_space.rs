@@ -0,0 +1,130 @@
+//! Free-space preflight before plugins are downloaded and unpacked.
+//!
+//! The unpacked size is estimated up front: the sum of the tar entry sizes
+//! when the archive is already on disk, otherwise the registry's download
+//! size times [`EXPANSION_FACTOR`]. If the filesystem holding the target
+//! directory has less than that free, the install fails with
+//! [`InstallerError::InsufficientSpace`] before anything is written.
+
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::{InstallerError, Result};
+
+/// Assumed ratio of unpacked size to download size when only the latter is known.
+pub const EXPANSION_FACTOR: u64 = 4;
+
+/// Free-space query, behind a trait so the preflight can be tested.
+pub trait FreeSpace {
+    /// Bytes available to an unprivileged user on the filesystem holding `path`.
+    fn available_bytes(&self, path: &Path) -> std::io::Result<u64>;
+}
+
+/// Asks the OS. On platforms without a query the space is reported as
+/// unlimited, which skips the check.
+pub struct SystemFreeSpace;
+
+impl FreeSpace for SystemFreeSpace {
+    #[cfg(unix)]
+    fn available_bytes(&self, path: &Path) -> std::io::Result<u64> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        // Field widths differ between platforms (u32 on macOS, u64 on Linux).
+        #[allow(clippy::unnecessary_cast)]
+        let available = stat.f_bavail as u64 * stat.f_frsize as u64;
+        Ok(available)
+    }
+
+    #[cfg(not(unix))]
+    fn available_bytes(&self, _path: &Path) -> std::io::Result<u64> {
+        Ok(u64::MAX)
+    }
+}
+
+/// Space needed to download an archive of `size_bytes` and unpack it.
+pub fn estimate_from_download(size_bytes: u64) -> u64 {
+    size_bytes.saturating_mul(EXPANSION_FACTOR + 1)
+}
+
+/// Total size of the entries in a gzipped tarball, read from the headers.
+pub fn tar_gz_unpacked_size(reader: impl Read) -> std::io::Result<u64> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(reader));
+    let mut total: u64 = 0;
+    for entry in archive.entries()? {
+        total = total.saturating_add(entry?.header().size()?);
+    }
+    Ok(total)
+}
+
+/// Fail with [`InstallerError::InsufficientSpace`] if `dir` (or, if it doesn't
+/// exist yet, its closest existing ancestor) has less than `needed` bytes free.
+pub fn ensure_available(dir: &Path, needed: u64) -> Result<()> {
+    ensure_available_with(&SystemFreeSpace, dir, needed)
+}
+
+pub fn ensure_available_with(space: &impl FreeSpace, dir: &Path, needed: u64) -> Result<()> {
+    if needed == 0 {
+        return Ok(());
+    }
+    let Some(existing) = dir.ancestors().find(|p| p.exists()) else {
+        return Ok(());
+    };
+    let available = match space.available_bytes(existing) {
+        Ok(available) => available,
+        Err(e) => {
+            // An unanswerable query shouldn't block installs; extraction still fails cleanly.
+            tracing::trace!(dir = %existing.display(), error = %e, "Could not query free space");
+            return Ok(());
+        }
+    };
+    tracing::trace!(dir = %existing.display(), needed = needed, available = available, "Checked free space");
+    if available < needed {
+        return Err(InstallerError::InsufficientSpace { needed, available });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(u64);
+
+    impl FreeSpace for Fixed {
+        fn available_bytes(&self, _path: &Path) -> std::io::Result<u64> {
+            Ok(self.0)
+        }
+    }
+
+    #[test]
+    fn test_ensure_available() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("plugins").join("adi.hive");
+
+        assert!(ensure_available_with(&Fixed(2048), &target, 1024).is_ok());
+        let err = ensure_available_with(&Fixed(512), &target, 1024).unwrap_err();
+        assert!(matches!(err, InstallerError::InsufficientSpace { needed: 1024, available: 512 }));
+        assert!(ensure_available_with(&Fixed(0), &target, 0).is_ok());
+    }
+
+    #[test]
+    fn test_tar_gz_unpacked_size() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+        for (name, len) in [("plugin.toml", 100usize), ("bin/adi-hive", 5000)] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(len as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, vec![0u8; len].as_slice()).unwrap();
+        }
+        let tarball = builder.into_inner().unwrap().finish().unwrap();
+
+        assert_eq!(tar_gz_unpacked_size(tarball.as_slice()).unwrap(), 5100);
+    }
+}
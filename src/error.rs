@@ -17,6 +17,24 @@ pub enum InstallerError {
         dependency: String,
     },
 
+    #[error("error-dependency-conflict")]
+    DependencyConflict {
+        plugin: String,
+        dependency: String,
+        requirement: String,
+        installed: String,
+        /// Other installed plugins' requirements on the same dependency
+        held_by: Vec<String>,
+    },
+
+    #[error("error-dependency-pinned")]
+    DependencyPinned {
+        plugin: String,
+        dependency: String,
+        requirement: String,
+        version: String,
+    },
+
     #[error("error-config")]
     ConfigError(String),
 
@@ -38,12 +56,53 @@ pub enum InstallerError {
     #[error("error-plugin-not-found")]
     PluginNotFound { id: String },
 
+    #[error("error-plugin-disabled")]
+    PluginDisabled { id: String },
+
+    #[error("error-plugin-pinned")]
+    PluginPinned { id: String, version: String },
+
     #[error("error-plugin-host")]
     PluginHost(#[from] lib_plugin_host::HostError),
 
     #[error("error-service")]
     Service(String),
 
+    #[error("error-checksum-mismatch")]
+    ChecksumMismatch {
+        id: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("error-unsafe-archive-entry")]
+    UnsafeArchiveEntry { entry: String },
+
+    #[error("error-archive-too-large")]
+    ArchiveTooLarge {
+        /// "bytes" or "entries"
+        kind: String,
+        limit: u64,
+    },
+
+    #[error("error-insufficient-space")]
+    InsufficientSpace { needed: u64, available: u64 },
+
+    #[error("error-retries-exhausted")]
+    RetriesExhausted { attempts: u32, detail: String },
+
+    #[error("error-offline-not-cached")]
+    OfflineNotCached { what: String },
+
+    #[error("error-operation-in-progress")]
+    OperationInProgress { lock_path: String },
+
+    #[error("error-plugin-timeout")]
+    PluginTimeout { plugin: String, ms: u64 },
+
+    #[error("error-registry-unreachable")]
+    RegistryUnreachable { url: String },
+
     #[error("error-other")]
     Other(String),
 }
@@ -54,6 +113,8 @@ impl LocalizedError for InstallerError {
             Self::ComponentNotFound(_) => "error-component-not-found",
             Self::InstallationFailed { .. } => "error-installation-failed",
             Self::DependencyMissing { .. } => "error-dependency-missing",
+            Self::DependencyConflict { .. } => "error-dependency-conflict",
+            Self::DependencyPinned { .. } => "error-dependency-pinned",
             Self::ConfigError(_) => "error-config",
             Self::Io(_) => "error-io",
             Self::Serialization(_) => "error-serialization",
@@ -61,8 +122,19 @@ impl LocalizedError for InstallerError {
             Self::UninstallationFailed { .. } => "error-uninstallation-failed",
             Self::Registry(_) => "error-registry",
             Self::PluginNotFound { .. } => "error-plugin-not-found",
+            Self::PluginDisabled { .. } => "error-plugin-disabled",
+            Self::PluginPinned { .. } => "error-plugin-pinned",
             Self::PluginHost(_) => "error-plugin-host",
             Self::Service(_) => "error-service",
+            Self::ChecksumMismatch { .. } => "error-checksum-mismatch",
+            Self::UnsafeArchiveEntry { .. } => "error-unsafe-archive-entry",
+            Self::ArchiveTooLarge { .. } => "error-archive-too-large",
+            Self::InsufficientSpace { .. } => "error-insufficient-space",
+            Self::RetriesExhausted { .. } => "error-retries-exhausted",
+            Self::OfflineNotCached { .. } => "error-offline-not-cached",
+            Self::OperationInProgress { .. } => "error-operation-in-progress",
+            Self::PluginTimeout { .. } => "error-plugin-timeout",
+            Self::RegistryUnreachable { .. } => "error-registry-unreachable",
             Self::Other(_) => "error-other",
         }
     }
@@ -84,6 +156,31 @@ impl LocalizedError for InstallerError {
                 args.insert("component".into(), FluentValue::from(component.clone()));
                 args.insert("dependency".into(), FluentValue::from(dependency.clone()));
             }
+            Self::DependencyConflict {
+                plugin,
+                dependency,
+                requirement,
+                installed,
+                held_by,
+            } => {
+                args.insert("plugin".into(), FluentValue::from(plugin.clone()));
+                args.insert("dependency".into(), FluentValue::from(dependency.clone()));
+                args.insert("requirement".into(), FluentValue::from(requirement.clone()));
+                args.insert("installed".into(), FluentValue::from(installed.clone()));
+                let held_by = if held_by.is_empty() { "none".to_string() } else { held_by.join(", ") };
+                args.insert("held_by".into(), FluentValue::from(held_by));
+            }
+            Self::DependencyPinned {
+                plugin,
+                dependency,
+                requirement,
+                version,
+            } => {
+                args.insert("plugin".into(), FluentValue::from(plugin.clone()));
+                args.insert("dependency".into(), FluentValue::from(dependency.clone()));
+                args.insert("requirement".into(), FluentValue::from(requirement.clone()));
+                args.insert("version".into(), FluentValue::from(version.clone()));
+            }
             Self::ConfigError(detail) => {
                 args.insert("detail".into(), FluentValue::from(detail.clone()));
             }
@@ -106,12 +203,53 @@ impl LocalizedError for InstallerError {
             Self::PluginNotFound { id } => {
                 args.insert("id".into(), FluentValue::from(id.clone()));
             }
+            Self::PluginDisabled { id } => {
+                args.insert("id".into(), FluentValue::from(id.clone()));
+            }
+            Self::PluginPinned { id, version } => {
+                args.insert("id".into(), FluentValue::from(id.clone()));
+                args.insert("version".into(), FluentValue::from(version.clone()));
+            }
             Self::PluginHost(e) => {
                 args.insert("detail".into(), FluentValue::from(e.to_string()));
             }
             Self::Service(detail) => {
                 args.insert("detail".into(), FluentValue::from(detail.clone()));
             }
+            Self::ChecksumMismatch { id, expected, actual } => {
+                args.insert("id".into(), FluentValue::from(id.clone()));
+                args.insert("expected".into(), FluentValue::from(expected.clone()));
+                args.insert("actual".into(), FluentValue::from(actual.clone()));
+            }
+            Self::UnsafeArchiveEntry { entry } => {
+                args.insert("entry".into(), FluentValue::from(entry.clone()));
+            }
+            Self::ArchiveTooLarge { kind, limit } => {
+                args.insert("kind".into(), FluentValue::from(kind.clone()));
+                args.insert("limit".into(), FluentValue::from(limit.to_string()));
+            }
+            Self::InsufficientSpace { needed, available } => {
+                use crate::daemon::proc_stats::format_bytes;
+                args.insert("needed".into(), FluentValue::from(format_bytes(*needed)));
+                args.insert("available".into(), FluentValue::from(format_bytes(*available)));
+            }
+            Self::RetriesExhausted { attempts, detail } => {
+                args.insert("attempts".into(), FluentValue::from(attempts.to_string()));
+                args.insert("detail".into(), FluentValue::from(detail.clone()));
+            }
+            Self::OfflineNotCached { what } => {
+                args.insert("what".into(), FluentValue::from(what.clone()));
+            }
+            Self::OperationInProgress { lock_path } => {
+                args.insert("path".into(), FluentValue::from(lock_path.clone()));
+            }
+            Self::PluginTimeout { plugin, ms } => {
+                args.insert("plugin".into(), FluentValue::from(plugin.clone()));
+                args.insert("ms".into(), FluentValue::from(ms.to_string()));
+            }
+            Self::RegistryUnreachable { url } => {
+                args.insert("url".into(), FluentValue::from(url.clone()));
+            }
             Self::Other(detail) => {
                 args.insert("detail".into(), FluentValue::from(detail.clone()));
             }
@@ -0,0 +1,40 @@
+//! Shared settings for outgoing HTTP requests.
+//!
+//! Every client identifies itself as `adi/<version> (<os>; <arch>)` so
+//! registry and mirror operators can attribute traffic and users can find
+//! their requests in server logs. `$ADI_USER_AGENT_SUFFIX` is appended, e.g.
+//! to name the organization.
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// User-agent for every request adi makes.
+pub fn user_agent() -> String {
+    format_user_agent(crate::clienv::user_agent_suffix().as_deref())
+}
+
+/// A reqwest client from [`crate::clienv::http_client_builder`] (user-agent,
+/// timeouts, proxy), for plain downloads.
+pub fn client() -> reqwest::Result<reqwest::Client> {
+    crate::clienv::http_client_builder().build()
+}
+
+fn format_user_agent(suffix: Option<&str>) -> String {
+    let base = format!("adi/{} ({}; {})", VERSION, std::env::consts::OS, std::env::consts::ARCH);
+    match suffix.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(suffix) => format!("{} {}", base, suffix),
+        None => base,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_user_agent() {
+        let base = format!("adi/{} ({}; {})", VERSION, std::env::consts::OS, std::env::consts::ARCH);
+        assert_eq!(format_user_agent(None), base);
+        assert_eq!(format_user_agent(Some("  ")), base);
+        assert_eq!(format_user_agent(Some("acme-ci")), format!("{} acme-ci", base));
+    }
+}
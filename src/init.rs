@@ -1,4 +1,4 @@
-use cli::plugin_registry::PluginManager;
+use cli::plugin_registry::{cancel_on_interrupt, PluginManager};
 use cli::user_config::UserConfig;
 use lib_console_output::{theme, out_info, out_success, out_warn};
 use lib_console_output::input::Select;
@@ -53,6 +53,20 @@ async fn resolve_language(
     Ok("en-US".to_string())
 }
 
+fn embedded_i18n() -> I18n {
+    let mut i18n = I18n::new_standalone();
+    let _ = i18n.load_embedded("en-US", include_str!("../plugins/en-US/messages.ftl"));
+    tracing::trace!("Loaded embedded en-US translations");
+    i18n
+}
+
+/// English-only messages, for errors raised before user config may be read.
+pub(crate) fn initialize_embedded_i18n() {
+    let mut i18n = embedded_i18n();
+    let _ = i18n.set_language("en-US");
+    init_global(i18n);
+}
+
 pub(crate) async fn initialize_i18n(lang_override: Option<&str>) -> anyhow::Result<()> {
     tracing::trace!(lang_override = ?lang_override, "Initializing i18n");
     let mut config = UserConfig::load()?;
@@ -60,13 +74,17 @@ pub(crate) async fn initialize_i18n(lang_override: Option<&str>) -> anyhow::Resu
     let user_lang = resolve_language(lang_override, &mut config).await?;
     tracing::trace!(lang = %user_lang, "Selected language");
 
-    let mut i18n = I18n::new_standalone();
-    let _ = i18n.load_embedded("en-US", include_str!("../plugins/en-US/messages.ftl"));
-    tracing::trace!("Loaded embedded en-US translations");
-
-    if user_lang != "en-US" {
-        load_translation(&mut i18n, &user_lang).await;
-    }
+    let mut i18n = embedded_i18n();
+    let user_lang = if user_lang != "en-US" {
+        let installed = installed_translations(&cli::clienv::plugins_dir());
+        let lang = best_translation(&user_lang, &installed).unwrap_or(user_lang);
+        if lang != "en-US" {
+            load_translation(&mut i18n, &lang).await;
+        }
+        lang
+    } else {
+        user_lang
+    };
 
     if i18n.set_language(&user_lang).is_err() {
         tracing::trace!(lang = %user_lang, "Language not available, falling back to en-US");
@@ -78,11 +96,47 @@ pub(crate) async fn initialize_i18n(lang_override: Option<&str>) -> anyhow::Resu
     Ok(())
 }
 
+/// Language codes of the installed translation plugins (`adi.cli.<lang>`).
+fn installed_translations(plugins_dir: &std::path::Path) -> Vec<String> {
+    cli::plugin_runtime::scan_plugin_dirs(plugins_dir)
+        .dirs
+        .iter()
+        .filter_map(|dir| {
+            let name = dir.file_name()?.to_str()?;
+            Some(name.strip_prefix(cli::clienv::CLI_PLUGIN_PREFIX)?.to_string())
+        })
+        .collect()
+}
+
+/// Closest installed translation for `requested`: the exact locale, then the
+/// bare base language (`de-AT` → `de`), then any region of it (`de-DE`).
+/// `None` if nothing matches, so the caller can try installing `requested`.
+fn best_translation(requested: &str, installed: &[String]) -> Option<String> {
+    let base = requested.split(['-', '_']).next().unwrap_or(requested);
+    let region_of = |code: &str| {
+        code.split_once('-')
+            .is_some_and(|(code_base, _)| code_base.eq_ignore_ascii_case(base))
+    };
+
+    let mut regional: Vec<&String> = installed.iter().filter(|code| region_of(code)).collect();
+    regional.sort();
+    let best = installed
+        .iter()
+        .find(|code| code.eq_ignore_ascii_case(requested))
+        .or_else(|| installed.iter().find(|code| code.eq_ignore_ascii_case(base)))
+        .or_else(|| regional.first().copied())
+        .cloned();
+    if best.as_deref().is_some_and(|best| best != requested) {
+        tracing::trace!(requested = %requested, fallback = ?best, "Using closest installed translation");
+    }
+    best
+}
+
 async fn load_translation(i18n: &mut I18n, lang: &str) {
     let translation_id = format!("{}{}", cli::clienv::CLI_PLUGIN_PREFIX, lang);
     tracing::trace!(translation_id = %translation_id, "Looking for translation plugin");
 
-    let plugins_dir = lib_plugin_host::PluginConfig::default_plugins_dir();
+    let plugins_dir = cli::clienv::plugins_dir();
     let plugin_dir = plugins_dir.join(&translation_id);
 
     if try_load_ftl(i18n, lang, &plugin_dir) {
@@ -98,7 +152,7 @@ async fn load_translation(i18n: &mut I18n, lang: &str) {
     mark_translation_checked(&plugins_dir, &translation_id);
 
     let manager = PluginManager::new();
-    if manager.install_plugin(&translation_id, None).await.is_ok() {
+    if cancel_on_interrupt(manager.install_plugin(&translation_id, None)).await.is_ok() {
         tracing::trace!("Translation plugin installed, loading FTL");
         try_load_ftl(i18n, lang, &plugin_dir);
     } else {
@@ -163,7 +217,7 @@ fn registry_languages(plugins: &[registry_client::PluginEntry]) -> Vec<(String,
 }
 
 async fn installed_languages() -> Vec<(String, String)> {
-    let plugins_dir = lib_plugin_host::PluginConfig::default_plugins_dir();
+    let plugins_dir = cli::clienv::plugins_dir();
     let Ok(mut entries) = tokio::fs::read_dir(&plugins_dir).await else { return Vec::new() };
 
     let mut languages = Vec::new();
@@ -203,10 +257,7 @@ async fn prompt_language_selection() -> anyhow::Result<String> {
 
     out_info!("{}", theme::brand_bold("Welcome to ADI! 🎉"));
 
-    let items: Vec<(String, String)> = languages
-        .iter()
-        .map(|(code, name)| (format!("{} ({})", name, code), code.clone()))
-        .collect();
+    let items = language_choices(&languages);
 
     let selected = Select::new("Please select your preferred language:")
         .items(items)
@@ -217,6 +268,22 @@ async fn prompt_language_selection() -> anyhow::Result<String> {
     Ok(selected)
 }
 
+/// Select items `(label, code)`. A language offered in a single region is
+/// labelled with just its base (`Deutsch (de)`); several regions keep their
+/// full codes so they can be told apart.
+fn language_choices(languages: &[(String, String)]) -> Vec<(String, String)> {
+    let base = |code: &str| code.split('-').next().unwrap_or(code).to_string();
+    languages
+        .iter()
+        .map(|(code, name)| {
+            let code_base = base(code);
+            let regions = languages.iter().filter(|(other, _)| base(other) == code_base).count();
+            let shown = if regions == 1 { code_base } else { code.clone() };
+            (format!("{} ({})", name, shown), code.clone())
+        })
+        .collect()
+}
+
 fn should_check_translation(plugins_dir: &std::path::Path, translation_id: &str) -> bool {
     let stamp = plugins_dir.join(format!(".{}.last-check", translation_id));
     let should = match std::fs::metadata(&stamp) {
@@ -271,11 +338,7 @@ fn find_direct_ftl(plugin_dir: &std::path::Path) -> Option<std::path::PathBuf> {
 }
 
 fn find_subdirectory_ftl(plugin_dir: &std::path::Path) -> Option<std::path::PathBuf> {
-    std::fs::read_dir(plugin_dir).ok()?.flatten().find_map(|entry| {
-        let subdir = entry.path();
-        if !subdir.is_dir() {
-            return None;
-        }
+    cli::plugin_runtime::plugin_subdirs(plugin_dir).into_iter().find_map(|subdir| {
         let ftl_path = subdir.join("messages.ftl");
         if ftl_path.exists() {
             tracing::trace!(path = %ftl_path.display(), "Found messages.ftl in subdirectory");
@@ -285,3 +348,33 @@ fn find_subdirectory_ftl(plugin_dir: &std::path::Path) -> Option<std::path::Path
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_best_translation_falls_back_to_base_language() {
+        let installed = codes(&["de-DE", "fr", "pt-PT", "pt-BR"]);
+        assert_eq!(best_translation("de-DE", &installed).as_deref(), Some("de-DE"));
+        assert_eq!(best_translation("de-AT", &installed).as_deref(), Some("de-DE"));
+        assert_eq!(best_translation("fr-CA", &installed).as_deref(), Some("fr"));
+        assert_eq!(best_translation("pt", &installed).as_deref(), Some("pt-BR"));
+        assert_eq!(best_translation("ja-JP", &installed), None);
+    }
+
+    #[test]
+    fn test_language_choices_collapse_single_regions() {
+        let languages = vec![
+            ("en-US".to_string(), "English".to_string()),
+            ("pt-PT".to_string(), "Português".to_string()),
+            ("pt-BR".to_string(), "Português (Brasil)".to_string()),
+        ];
+        let labels: Vec<String> = language_choices(&languages).into_iter().map(|(label, _)| label).collect();
+        assert_eq!(labels, ["English (en)", "Português (pt-PT)", "Português (Brasil) (pt-BR)"]);
+    }
+}
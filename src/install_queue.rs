@@ -0,0 +1,125 @@
+//! Resumable pattern installs (`adi plugin install <pattern> --resume`).
+//!
+//! While a pattern install runs, the ids still to install and those already
+//! done are kept in `<cache_dir>/install-queues/<pattern>.json`. If the run
+//! dies partway, `--resume` picks up the pending ids without searching the
+//! registry again. The file is removed once everything installed.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{InstallerError, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallQueue {
+    pub pattern: String,
+    pub version: Option<String>,
+    pub pending: Vec<String>,
+    pub completed: Vec<String>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl InstallQueue {
+    pub fn new(pattern: &str, version: Option<&str>, ids: Vec<String>) -> Self {
+        Self::new_in(&queues_dir(), pattern, version, ids)
+    }
+
+    /// The saved queue for `pattern`, if a previous run left one behind.
+    pub fn load(pattern: &str) -> Result<Option<Self>> {
+        Self::load_from(&queues_dir(), pattern)
+    }
+
+    fn new_in(dir: &Path, pattern: &str, version: Option<&str>, ids: Vec<String>) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            version: version.map(String::from),
+            pending: ids,
+            completed: Vec::new(),
+            path: queue_path(dir, pattern),
+        }
+    }
+
+    fn load_from(dir: &Path, pattern: &str) -> Result<Option<Self>> {
+        let path = queue_path(dir, pattern);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut queue: Self = serde_json::from_str(&content)
+            .map_err(|e| InstallerError::ConfigError(format!("invalid install queue {}: {}", path.display(), e)))?;
+        tracing::trace!(path = %path.display(), pending = queue.pending.len(), "Loaded install queue");
+        queue.path = path;
+        Ok(Some(queue))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| InstallerError::Other(e.to_string()))?;
+        std::fs::write(&self.path, content)?;
+        Ok(())
+    }
+
+    /// Move `id` from pending to completed and persist right away, so a
+    /// crash right after still counts it.
+    pub fn mark_completed(&mut self, id: &str) -> Result<()> {
+        self.pending.retain(|pending| pending != id);
+        if !self.completed.iter().any(|done| done == id) {
+            self.completed.push(id.to_string());
+        }
+        self.save()
+    }
+
+    /// Delete the saved queue (after a fully successful run).
+    pub fn clear(self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn queues_dir() -> PathBuf {
+    crate::clienv::cache_dir().join("install-queues")
+}
+
+/// One file per pattern; characters that aren't safe in file names
+/// (`*`, `/`, ...) become `_`.
+fn queue_path(dir: &Path, pattern: &str) -> PathBuf {
+    let name: String = pattern
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-') { c } else { '_' })
+        .collect();
+    dir.join(format!("{}.json", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_survives_reload_and_clears() {
+        let dir = tempfile::tempdir().unwrap();
+        let ids = vec!["adi.lang.go".to_string(), "adi.lang.rust".to_string()];
+        let mut queue = InstallQueue::new_in(dir.path(), "adi.lang.*", None, ids);
+        queue.save().unwrap();
+        queue.mark_completed("adi.lang.go").unwrap();
+
+        let reloaded = InstallQueue::load_from(dir.path(), "adi.lang.*").unwrap().unwrap();
+        assert_eq!(reloaded.pending, ["adi.lang.rust"]);
+        assert_eq!(reloaded.completed, ["adi.lang.go"]);
+
+        reloaded.clear().unwrap();
+        assert!(InstallQueue::load_from(dir.path(), "adi.lang.*").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_queue_path_is_file_name_safe() {
+        let path = queue_path(Path::new("/q"), "adi.lang/*");
+        assert_eq!(path, Path::new("/q/adi.lang__.json"));
+    }
+}
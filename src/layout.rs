@@ -0,0 +1,68 @@
+use dialoguer::console::{measure_text_width, truncate_str, Term};
+
+/// Spaces `Columns` puts between cells, plus the leading indent.
+const COLUMN_GAP: usize = 2;
+/// Never squeeze a free-text column below this, even on tiny terminals.
+const MIN_FLEX_WIDTH: usize = 16;
+const ELLIPSIS: &str = "…";
+
+/// Width tables should fit in: `--width`/$ADI_WIDTH, else the terminal's
+/// width. `None` for piped output without an override (left untruncated).
+pub(crate) fn output_width() -> Option<usize> {
+    cli::clienv::output_width().or_else(|| {
+        let term = Term::stdout();
+        term.is_term().then(|| term.size().1 as usize)
+    })
+}
+
+/// Truncate column `flex` of every row with an ellipsis so the table fits the
+/// output width. Other columns keep their natural width. Full values remain
+/// available through `--output json`.
+pub(crate) fn fit_column<const N: usize>(header: [&str; N], rows: Vec<[String; N]>, flex: usize) -> Vec<[String; N]> {
+    let Some(width) = output_width() else {
+        return rows;
+    };
+    let budget = flex_budget(width, &header, &rows, flex);
+    tracing::trace!(width = width, budget = budget, "Fitting table column to output width");
+
+    rows.into_iter()
+        .map(|mut row| {
+            row[flex] = truncate_str(&row[flex], budget, ELLIPSIS).into_owned();
+            row
+        })
+        .collect()
+}
+
+fn flex_budget<const N: usize>(width: usize, header: &[&str; N], rows: &[[String; N]], flex: usize) -> usize {
+    let fixed: usize = (0..N)
+        .filter(|&i| i != flex)
+        .map(|i| {
+            rows.iter()
+                .map(|row| measure_text_width(&row[i]))
+                .chain(std::iter::once(measure_text_width(header[i])))
+                .max()
+                .unwrap_or(0)
+        })
+        .sum();
+    width
+        .saturating_sub(fixed + COLUMN_GAP * N)
+        .max(MIN_FLEX_WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flex_budget_leaves_room_for_fixed_columns() {
+        let rows = vec![["adi.hive".to_string(), "a long description".to_string(), "v1.0.0".to_string()]];
+        let budget = flex_budget(80, &["Plugin", "Description", "Version"], &rows, 1);
+        assert_eq!(budget, 80 - 8 - 7 - COLUMN_GAP * 3);
+    }
+
+    #[test]
+    fn test_flex_budget_has_floor() {
+        let rows = vec![["x".repeat(100), "desc".to_string()]];
+        assert_eq!(flex_budget(40, &["Plugin", "Description"], &rows, 1), MIN_FLEX_WIDTH);
+    }
+}
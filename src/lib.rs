@@ -1,10 +1,28 @@
+pub mod archive;
 pub mod clienv;
 pub mod completions;
+pub mod crash_report;
 pub mod daemon;
+pub mod disk_space;
 pub mod error;
+pub mod http;
+pub mod install_queue;
+pub mod logging;
+pub mod plugin_changelog;
+pub mod plugin_health;
+pub mod plugin_lock;
+pub mod plugin_pin;
+pub mod plugin_prune;
 pub mod plugin_registry;
 pub mod plugin_runtime;
+pub mod plural;
+pub mod registry_auth;
+pub mod registry_cache;
+pub mod retry;
 pub mod self_update;
+pub mod state_lock;
+pub mod suggest;
+pub mod system_binary;
 pub mod user_config;
 
 pub use error::{InstallerError, Result};
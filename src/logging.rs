@@ -0,0 +1,202 @@
+//! Log output format for the CLI and daemon (`ADI_LOG_FORMAT`,
+//! `adi daemon run --log-format`).
+//!
+//! `text` is the default human-readable tracing output. `json` emits one JSON
+//! object per line (timestamp, level, target, message, fields and spans) for
+//! log aggregation, and makes the daemon store captured service output as
+//! JSON lines tagged with the service name and stream.
+
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+static FORMAT: OnceLock<LogFormat> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "text" | "pretty" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Install the global tracing subscriber. Call once, before anything logs.
+pub fn init(format: LogFormat) {
+    let _ = FORMAT.set(format);
+    let filter = tracing_subscriber::EnvFilter::from_default_env();
+    match format {
+        LogFormat::Text => tracing_subscriber::fmt().with_env_filter(filter).with_target(false).init(),
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_env_filter(filter)
+            .init(),
+    }
+}
+
+/// The format passed to [`init`] (text if logging wasn't initialized).
+pub fn format() -> LogFormat {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+/// A captured line of service output as it is stored in the daemon's log
+/// buffer: unchanged for text, a tagged JSON object for json.
+pub fn service_line(format: LogFormat, service: &str, stream: &str, line: String) -> String {
+    match format {
+        LogFormat::Text => line,
+        LogFormat::Json => serde_json::json!({
+            "timestamp": rfc3339_utc(SystemTime::now()),
+            "service": service,
+            "stream": stream,
+            "message": line,
+        })
+        .to_string(),
+    }
+}
+
+/// `2024-05-01T12:00:00.000Z`
+fn rfc3339_utc(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Inverse of [`rfc3339_utc`], also taking a date alone (`2024-05-01`), a
+/// space for the `T`, no seconds, and a `+02:00` offset. No offset means UTC.
+pub fn parse_rfc3339(value: &str) -> Option<SystemTime> {
+    let (date, time) = match value.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (value, None),
+    };
+    let mut fields = date.splitn(3, '-');
+    let year: i64 = fields.next()?.parse().ok()?;
+    let month: u32 = fields.next()?.parse().ok()?;
+    let day: u32 = fields.next()?.parse().ok()?;
+    if date.len() != 10 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut secs = days_from_civil(year, month, day) * 86_400;
+    let mut nanos = 0;
+    if let Some(time) = time {
+        let (clock, offset) = split_offset(time)?;
+        let mut fields = clock.splitn(3, ':');
+        let hours: i64 = fields.next()?.parse().ok()?;
+        let minutes: i64 = fields.next()?.parse().ok()?;
+        let (seconds, fraction) = match fields.next() {
+            Some(seconds) => seconds.split_once('.').unwrap_or((seconds, "")),
+            None => ("0", ""),
+        };
+        let seconds: i64 = seconds.parse().ok()?;
+        if hours > 23 || minutes > 59 || seconds > 60 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        if !fraction.is_empty() {
+            nanos = format!("{:0<9}", &fraction[..fraction.len().min(9)]).parse().ok()?;
+        }
+        secs += hours * 3600 + minutes * 60 + seconds - offset;
+    }
+    let secs = u64::try_from(secs).ok()?;
+    Some(UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+/// `12:00:00Z` -> (`12:00:00`, 0), `12:00+02:00` -> (`12:00`, 7200).
+fn split_offset(time: &str) -> Option<(&str, i64)> {
+    if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        return Some((clock, 0));
+    }
+    let Some(at) = time.rfind(['+', '-']) else {
+        return Some((time, 0));
+    };
+    let (hours, minutes) = time[at + 1..].split_once(':')?;
+    let offset = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+    let sign = if time.as_bytes()[at] == b'-' { -1 } else { 1 };
+    Some((&time[..at], sign * offset))
+}
+
+/// Day count since 1970-01-01 of a Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = i64::from(if month > 2 { month - 3 } else { month + 9 });
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Gregorian date of a day count since 1970-01-01 (Howard Hinnant's algorithm).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc3339_utc() {
+        assert_eq!(rfc3339_utc(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        let time = UNIX_EPOCH + std::time::Duration::from_millis(1_709_210_096_789);
+        assert_eq!(rfc3339_utc(time), "2024-02-29T12:34:56.789Z");
+    }
+
+    #[test]
+    fn test_parse_rfc3339() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_709_210_096_789);
+        assert_eq!(parse_rfc3339(&rfc3339_utc(time)), Some(time));
+        assert_eq!(parse_rfc3339("2024-02-29 14:34:56.789+02:00"), Some(time));
+        assert_eq!(
+            parse_rfc3339("2024-02-29T12:34"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_210_040))
+        );
+        assert_eq!(parse_rfc3339("2024-02-29"), Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800)));
+        assert_eq!(parse_rfc3339("INFO"), None);
+        assert_eq!(parse_rfc3339("2024-13-01"), None);
+    }
+
+    #[test]
+    fn test_service_line() {
+        assert_eq!(service_line(LogFormat::Text, "hive", "stdout", "ready".into()), "ready");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&service_line(LogFormat::Json, "hive", "stderr", "oops \"x\"".into())).unwrap();
+        assert_eq!(json["service"], "hive");
+        assert_eq!(json["stream"], "stderr");
+        assert_eq!(json["message"], "oops \"x\"");
+        assert!(json["timestamp"].as_str().unwrap().ends_with('Z'));
+    }
+}
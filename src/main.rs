@@ -1,38 +1,62 @@
 mod args;
+mod cmd_completions;
 mod cmd_config;
 mod cmd_daemon;
 mod cmd_external;
 mod cmd_info;
 mod cmd_interactive;
 mod cmd_logs;
+mod cmd_open;
 mod cmd_plugin;
 mod cmd_run;
 mod cmd_search;
 mod cmd_start;
 mod cmd_theme;
 mod init;
+mod layout;
 
-use args::{Cli, Commands};
+use args::{Cli, Commands, CompletionsCommands, DaemonCommands};
 use clap::Parser;
 use cli::completions;
+use lib_console_output::{out_error, out_info};
+use lib_i18n_core::{t, LocalizedError};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-        )
-        .with_target(false)
-        .init();
-
-    tracing::trace!("ADI CLI starting");
+    cli::crash_report::install_panic_hook();
 
     dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+    cli::logging::init(log_format(&cli));
 
-    completions::ensure_completions_installed::<Cli>("adi");
+    tracing::trace!("ADI CLI starting");
+    cli::clienv::apply_proxy_override();
+
+    let uninstalling_completions = matches!(
+        cli.command,
+        Some(Commands::Completions { command: CompletionsCommands::Uninstall })
+    );
+    if !running_as_root() && !uninstalling_completions {
+        completions::ensure_completions_installed::<Cli>("adi");
+    }
 
-    let cli = Cli::parse();
     tracing::trace!(lang = ?cli.lang, has_command = cli.command.is_some(), "CLI arguments parsed");
+    refuse_root(&cli);
+
+    // Held until main returns so the temp dirs outlive every command.
+    let _ephemeral = apply_plugin_dirs(&cli)?;
+    if let Some(jobs) = cli.jobs {
+        cli::clienv::set_jobs(jobs as usize);
+    }
+    if let Some(width) = cli.width {
+        cli::clienv::set_output_width(width as usize);
+    }
+    if cli.offline {
+        cli::clienv::set_offline();
+    }
+    if cli.json {
+        cli::clienv::set_json_output();
+    }
 
     init::initialize_i18n(cli.lang.as_deref()).await?;
     init::initialize_theme();
@@ -48,17 +72,80 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
-    dispatch_command(command).await?;
+    if let Err(e) = dispatch_command(command).await {
+        if cli::clienv::json_output() {
+            let message = match e.downcast_ref::<cli::InstallerError>() {
+                Some(e) => e.localized(),
+                None => format!("{:#}", e),
+            };
+            cmd_search::print_json(&serde_json::json!({ "error": message }))?;
+            std::process::exit(1);
+        }
+        return Err(e);
+    }
 
     tracing::trace!("ADI CLI finished");
     Ok(())
 }
 
+/// `adi daemon run --log-format`, else $ADI_LOG_FORMAT.
+fn log_format(cli: &Cli) -> cli::logging::LogFormat {
+    match &cli.command {
+        Some(Commands::Daemon { command: DaemonCommands::Run { log_format: Some(format) }, .. }) => *format,
+        _ => cli::clienv::log_format(),
+    }
+}
+
+#[cfg(unix)]
+fn running_as_root() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(not(unix))]
+fn running_as_root() -> bool {
+    false
+}
+
+/// Exit before anything touches the user's config or data dirs as root:
+/// files created there would be root-owned and break later normal runs.
+/// The daemon is exempt, since it is where privileged work legitimately runs.
+fn refuse_root(cli: &Cli) {
+    let is_daemon = matches!(cli.command, Some(Commands::Daemon { .. }));
+    if !running_as_root() || is_daemon || cli.allow_root || cli::clienv::allow_root() {
+        return;
+    }
+    tracing::trace!("Refusing to run as root");
+    init::initialize_embedded_i18n();
+    out_error!("{} {}", t!("common-error-prefix"), t!("root-refused"));
+    out_info!("{}", t!("root-refused-hint"));
+    std::process::exit(1);
+}
+
+/// Applies `--plugins-dir` / `--ephemeral`. The returned guard deletes the
+/// ephemeral directory when dropped.
+fn apply_plugin_dirs(cli: &Cli) -> anyhow::Result<Option<tempfile::TempDir>> {
+    if let Some(dir) = &cli.plugins_dir {
+        cli::clienv::set_plugin_dirs(dir, None);
+        return Ok(None);
+    }
+    if !cli.ephemeral {
+        return Ok(None);
+    }
+
+    let root = tempfile::Builder::new().prefix("adi-ephemeral-").tempdir()?;
+    tracing::trace!(root = %root.path().display(), "Using ephemeral plugin directories");
+    cli::clienv::set_plugin_dirs(&root.path().join("plugins"), Some(&root.path().join("cache")));
+    Ok(Some(root))
+}
+
 async fn dispatch_command(command: Commands) -> anyhow::Result<()> {
     match command {
-        Commands::SelfUpdate { force } => {
-            tracing::trace!(force = force, "Dispatching: self-update");
-            cli::self_update::self_update(force).await?
+        Commands::SelfUpdate { force, no_verify, channel, to } => {
+            tracing::trace!(force = force, no_verify = no_verify, channel = ?channel, to = ?to, "Dispatching: self-update");
+            match to {
+                Some(version) => cli::self_update::self_update_to(&version, force, !no_verify).await?,
+                None => cli::self_update::self_update(force, !no_verify, channel).await?,
+            }
         }
         Commands::Start { port } => {
             tracing::trace!(port = port, "Dispatching: start");
@@ -66,11 +153,17 @@ async fn dispatch_command(command: Commands) -> anyhow::Result<()> {
         }
         Commands::Plugin { command } => {
             tracing::trace!("Dispatching: plugin");
-            cmd_plugin::cmd_plugin(command).await?
+            cli::plugin_registry::cancel_on_interrupt(cmd_plugin::cmd_plugin(command)).await?
         }
-        Commands::Run { plugin_id, args } => {
+        Commands::Run {
+            context_file,
+            args_file,
+            plugin_id,
+            args,
+        } => {
             tracing::trace!(plugin_id = ?plugin_id, "Dispatching: run");
-            cmd_run::cmd_run(plugin_id, args).await?
+            let args_file = args_file.or_else(cli::clienv::plugin_args_file);
+            cmd_run::cmd_run(plugin_id, args, context_file, args_file).await?
         }
         Commands::Logs {
             plugin_id,
@@ -94,10 +187,21 @@ async fn dispatch_command(command: Commands) -> anyhow::Result<()> {
             tracing::trace!("Dispatching: info");
             cmd_info::cmd_info().await?
         }
-        Commands::Daemon { command } => {
-            tracing::trace!("Dispatching: daemon");
+        Commands::Daemon { timeout, command } => {
+            tracing::trace!(timeout = ?timeout, "Dispatching: daemon");
+            if let Some(secs) = timeout {
+                cli::clienv::set_daemon_timeout(secs);
+            }
             cmd_daemon::cmd_daemon(command).await?
         }
+        Commands::Open { target, print } => {
+            tracing::trace!(target = ?target, print = print, "Dispatching: open");
+            cmd_open::cmd_open(target, print)?
+        }
+        Commands::Completions { command } => {
+            tracing::trace!("Dispatching: completions");
+            cmd_completions::cmd_completions(command)?
+        }
         Commands::External(args) => {
             tracing::trace!(args = ?args, "Dispatching: external");
             cmd_external::cmd_external(args).await?
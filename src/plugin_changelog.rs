@@ -0,0 +1,149 @@
+//! Release notes for installed plugins (`adi plugin changelog`).
+//!
+//! The registry doesn't carry release notes, so they come from the GitHub
+//! releases of the repository named in the plugin's manifest
+//! (`[plugin] repository = "https://github.com/owner/repo"`). Monorepos that
+//! tag per plugin (`hive-v1.2.0`) are matched against the plugin id.
+
+use anyhow::{anyhow, Result};
+use semver::Version;
+
+#[derive(Debug, Clone)]
+pub struct ReleaseNotes {
+    pub version: Version,
+    pub tag: String,
+    pub body: String,
+}
+
+/// GitHub `(owner, repo)` of an installed plugin, from its manifest.
+pub fn installed_repository(plugin_dir: &std::path::Path) -> Option<(String, String)> {
+    let manifest_path = crate::plugin_runtime::find_plugin_toml_path(plugin_dir)?;
+    github_repository(&std::fs::read_to_string(manifest_path).ok()?)
+}
+
+/// GitHub `(owner, repo)` from a plugin.toml's `repository` field.
+pub fn github_repository(manifest: &str) -> Option<(String, String)> {
+    let table = manifest.parse::<toml::Table>().ok()?;
+    let url = ["plugin", "package"]
+        .iter()
+        .find_map(|section| table.get(*section)?.get("repository")?.as_str())?;
+    let path = url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .split_once("github.com/")?
+        .1;
+    let (owner, repo) = path.split_once('/')?;
+    if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+        return None;
+    }
+    Some((owner.to_string(), repo.to_string()))
+}
+
+/// Release notes for `plugin_id`: exactly `version` if given, otherwise every
+/// release newer than `installed` (or just the latest). Newest first.
+pub async fn fetch_release_notes(
+    plugin_id: &str,
+    owner: &str,
+    repo: &str,
+    installed: Option<&str>,
+    version: Option<&str>,
+) -> Result<Vec<ReleaseNotes>> {
+    tracing::trace!(plugin_id = %plugin_id, owner = %owner, repo = %repo, "Fetching plugin releases");
+    let releases = crate::self_update::build_github_client()?
+        .list_releases(owner, repo)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch releases: {}", e))?;
+
+    let notes = releases
+        .into_iter()
+        .filter_map(|release| {
+            let version = tag_version(plugin_id, &release.tag_name)?;
+            Some(ReleaseNotes {
+                version,
+                tag: release.tag_name,
+                body: release.body.unwrap_or_default(),
+            })
+        })
+        .collect();
+    Ok(select_notes(notes, installed, version))
+}
+
+fn select_notes(mut notes: Vec<ReleaseNotes>, installed: Option<&str>, version: Option<&str>) -> Vec<ReleaseNotes> {
+    let parse = |v: &str| Version::parse(v.trim_start_matches('v')).ok();
+    notes.sort_by(|a, b| b.version.cmp(&a.version));
+
+    if let Some(wanted) = version.and_then(parse) {
+        notes.retain(|n| n.version == wanted);
+        return notes;
+    }
+    let newer = installed.and_then(parse).map(|current| {
+        notes.iter().filter(|n| n.version > current).cloned().collect::<Vec<_>>()
+    });
+    match newer {
+        Some(newer) if !newer.is_empty() => newer,
+        _ => notes.into_iter().take(1).collect(),
+    }
+}
+
+/// Version of a release tag belonging to `plugin_id`: `v1.2.0`, `1.2.0`, or
+/// `<name>-v1.2.0` where the plugin id is or ends with `<name>`.
+fn tag_version(plugin_id: &str, tag: &str) -> Option<Version> {
+    let raw = match tag.rsplit_once("-v") {
+        Some((name, version)) => {
+            if plugin_id != name && !plugin_id.ends_with(&format!(".{}", name)) {
+                return None;
+            }
+            version
+        }
+        None => tag.strip_prefix('v').unwrap_or(tag),
+    };
+    Version::parse(raw).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(versions: &[&str]) -> Vec<ReleaseNotes> {
+        versions
+            .iter()
+            .map(|v| ReleaseNotes {
+                version: Version::parse(v).unwrap(),
+                tag: format!("v{}", v),
+                body: String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_github_repository() {
+        let manifest = "[plugin]\nid = \"adi.hive\"\nrepository = \"https://github.com/adi-family/hive.git\"\n";
+        assert_eq!(
+            github_repository(manifest),
+            Some(("adi-family".to_string(), "hive".to_string()))
+        );
+        assert_eq!(github_repository("[plugin]\nrepository = \"https://gitlab.com/a/b\"\n"), None);
+    }
+
+    #[test]
+    fn test_tag_version_matches_plugin() {
+        assert_eq!(tag_version("adi.hive", "v1.2.0"), Version::parse("1.2.0").ok());
+        assert_eq!(tag_version("adi.hive", "hive-v1.2.0"), Version::parse("1.2.0").ok());
+        assert_eq!(tag_version("adi.hive", "tasks-v1.2.0"), None);
+        assert_eq!(tag_version("adi.hive", "nightly"), None);
+    }
+
+    #[test]
+    fn test_select_notes() {
+        let all = notes(&["1.0.0", "1.2.0", "1.1.0"]);
+        let newer: Vec<String> = select_notes(all.clone(), Some("1.0.0"), None)
+            .iter()
+            .map(|n| n.version.to_string())
+            .collect();
+        assert_eq!(newer, ["1.2.0", "1.1.0"]);
+
+        assert_eq!(select_notes(all.clone(), Some("1.2.0"), None)[0].version.to_string(), "1.2.0");
+        assert_eq!(select_notes(all.clone(), None, Some("v1.1.0"))[0].version.to_string(), "1.1.0");
+        assert!(select_notes(all, None, Some("9.9.9")).is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+//! Auto-disabling of plugins that keep failing to load.
+//!
+//! Each failed load bumps a counter in `<plugins_dir>/<id>/.load-failures`;
+//! a successful load clears it. After [`MAX_CONSECUTIVE_LOAD_FAILURES`] in a
+//! row the plugin gets a `.disabled` marker and is skipped by every command
+//! until `adi plugin doctor <id>` loads it successfully again.
+
+use std::path::Path;
+
+pub const DISABLED_MARKER: &str = ".disabled";
+const FAILURE_COUNT_FILE: &str = ".load-failures";
+
+pub const MAX_CONSECUTIVE_LOAD_FAILURES: u32 = 3;
+
+pub fn is_disabled(plugin_dir: &Path) -> bool {
+    plugin_dir.join(DISABLED_MARKER).exists()
+}
+
+pub fn consecutive_failures(plugin_dir: &Path) -> u32 {
+    std::fs::read_to_string(plugin_dir.join(FAILURE_COUNT_FILE))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Count a failed load. Returns true if this failure disabled the plugin.
+pub fn record_load_failure(plugin_dir: &Path, error: &str) -> bool {
+    if !plugin_dir.is_dir() {
+        return false;
+    }
+    let failures = consecutive_failures(plugin_dir) + 1;
+    tracing::trace!(dir = %plugin_dir.display(), failures = failures, "Recording plugin load failure");
+    if let Err(e) = std::fs::write(plugin_dir.join(FAILURE_COUNT_FILE), failures.to_string()) {
+        tracing::warn!("Failed to record load failure for {}: {}", plugin_dir.display(), e);
+        return false;
+    }
+
+    if failures < MAX_CONSECUTIVE_LOAD_FAILURES || is_disabled(plugin_dir) {
+        return false;
+    }
+    match std::fs::write(plugin_dir.join(DISABLED_MARKER), error) {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("Failed to disable {}: {}", plugin_dir.display(), e);
+            false
+        }
+    }
+}
+
+pub fn record_load_success(plugin_dir: &Path) {
+    let _ = std::fs::remove_file(plugin_dir.join(FAILURE_COUNT_FILE));
+}
+
+/// Clear the disabled marker and failure count.
+pub fn enable(plugin_dir: &Path) -> std::io::Result<()> {
+    record_load_success(plugin_dir);
+    match std::fs::remove_file(plugin_dir.join(DISABLED_MARKER)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_after_consecutive_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        for _ in 1..MAX_CONSECUTIVE_LOAD_FAILURES {
+            assert!(!record_load_failure(dir.path(), "boom"));
+        }
+        assert!(!is_disabled(dir.path()));
+        assert!(record_load_failure(dir.path(), "boom"));
+        assert!(is_disabled(dir.path()));
+
+        enable(dir.path()).unwrap();
+        assert!(!is_disabled(dir.path()));
+        assert_eq!(consecutive_failures(dir.path()), 0);
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let dir = tempfile::tempdir().unwrap();
+        record_load_failure(dir.path(), "boom");
+        record_load_failure(dir.path(), "boom");
+        record_load_success(dir.path());
+        assert_eq!(consecutive_failures(dir.path()), 0);
+    }
+}
@@ -0,0 +1,175 @@
+//! Reproducible plugin install manifest (`adi plugin freeze`, written to
+//! `adi-plugins.lock` by default).
+//!
+//! Every entry pins a plugin to an exact version and platform together with a
+//! sha256 of the installed version directory, so `adi plugin restore` (or
+//! `install --locked`) can prove it reproduced the same artifact rather than
+//! just the same version string.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{InstallerError, Result};
+
+const LOCK_FORMAT_VERSION: u32 = 1;
+pub const DEFAULT_LOCK_FILE: &str = "adi-plugins.lock";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockEntry {
+    pub id: String,
+    pub version: String,
+    pub platform: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginLock {
+    pub version: u32,
+    #[serde(default, rename = "plugin")]
+    pub plugins: Vec<LockEntry>,
+}
+
+impl PluginLock {
+    pub fn new(mut plugins: Vec<LockEntry>) -> Self {
+        plugins.sort_by(|a, b| a.id.cmp(&b.id));
+        Self {
+            version: LOCK_FORMAT_VERSION,
+            plugins,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        tracing::trace!(path = %path.display(), "Loading plugin lock");
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let lock: Self = toml::from_str(content)
+            .map_err(|e| InstallerError::ConfigError(format!("invalid lock file: {}", e)))?;
+        if lock.version != LOCK_FORMAT_VERSION {
+            return Err(InstallerError::ConfigError(format!(
+                "unsupported lock file version {} (expected {})",
+                lock.version, LOCK_FORMAT_VERSION
+            )));
+        }
+        Ok(Self::new(lock.plugins))
+    }
+
+    pub fn to_toml_string(&self) -> Result<String> {
+        toml::to_string(self).map_err(|e| InstallerError::Other(e.to_string()))
+    }
+
+    /// Write the lock file, replacing `path` only once the new content is complete.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        tracing::trace!(path = %path.display(), count = self.plugins.len(), "Saving plugin lock");
+        let tmp = path.with_extension("lock.tmp");
+        std::fs::write(&tmp, self.to_toml_string()?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Installed plugin ids the lock doesn't mention, sorted.
+    pub fn unlocked<'a>(&self, installed: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let mut extra: Vec<String> = installed
+            .into_iter()
+            .filter(|id| !self.plugins.iter().any(|entry| entry.id == *id))
+            .map(String::from)
+            .collect();
+        extra.sort();
+        extra
+    }
+}
+
+/// Content hash of an installed plugin version directory.
+///
+/// Files are visited in sorted relative-path order and each contributes its
+/// path and bytes, so the digest is stable across machines and filesystems.
+pub fn hash_plugin_dir(dir: &Path) -> std::io::Result<String> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for rel in &files {
+        let path = dir.join(rel);
+        hasher.update(rel.to_string_lossy().replace('\\', "/").as_bytes());
+        hasher.update([0]);
+        if path.is_symlink() {
+            hasher.update(std::fs::read_link(&path)?.to_string_lossy().as_bytes());
+        } else {
+            hasher.update(std::fs::read(&path)?);
+        }
+        hasher.update([0]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if let Ok(rel) = path.strip_prefix(root) {
+            out.push(rel.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str) -> LockEntry {
+        LockEntry {
+            id: id.to_string(),
+            version: "1.0.0".to_string(),
+            platform: "linux-x86_64".to_string(),
+            sha256: "ab".repeat(32),
+        }
+    }
+
+    #[test]
+    fn test_lock_roundtrip_is_sorted() {
+        let lock = PluginLock::new(vec![entry("b.plugin"), entry("a.plugin")]);
+        let text = lock.to_toml_string().unwrap();
+        assert!(text.find("a.plugin").unwrap() < text.find("b.plugin").unwrap());
+        assert_eq!(PluginLock::parse(&text).unwrap(), lock);
+    }
+
+    #[test]
+    fn test_lock_save_and_unlocked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_LOCK_FILE);
+        let lock = PluginLock::new(vec![entry("adi.hive")]);
+        lock.save(&path).unwrap();
+        assert_eq!(PluginLock::load(&path).unwrap(), lock);
+
+        assert_eq!(lock.unlocked(["adi.tasks", "adi.hive", "adi.agent"]), ["adi.agent", "adi.tasks"]);
+    }
+
+    #[test]
+    fn test_lock_rejects_unknown_version() {
+        assert!(PluginLock::parse("version = 99\n").is_err());
+    }
+
+    #[test]
+    fn test_hash_plugin_dir_is_content_addressed() {
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        for dir in [a.path(), b.path()] {
+            std::fs::create_dir_all(dir.join("lib")).unwrap();
+            std::fs::write(dir.join("plugin.toml"), "id = 'x'").unwrap();
+            std::fs::write(dir.join("lib/plugin.so"), [1, 2, 3]).unwrap();
+        }
+        assert_eq!(hash_plugin_dir(a.path()).unwrap(), hash_plugin_dir(b.path()).unwrap());
+
+        std::fs::write(b.path().join("lib/plugin.so"), [1, 2, 4]).unwrap();
+        assert_ne!(hash_plugin_dir(a.path()).unwrap(), hash_plugin_dir(b.path()).unwrap());
+    }
+}
@@ -0,0 +1,53 @@
+//! Pinned plugins (`adi plugin pin <id>`).
+//!
+//! A pinned plugin has a `.pinned` marker next to its `.version` holding the
+//! version it was pinned at. `adi plugin update` refuses to change it and
+//! `update-all` skips it until `adi plugin unpin <id>`.
+
+use std::path::Path;
+
+pub const PINNED_MARKER: &str = ".pinned";
+
+pub fn is_pinned(plugin_dir: &Path) -> bool {
+    plugin_dir.join(PINNED_MARKER).exists()
+}
+
+/// The version recorded when the plugin was pinned.
+pub fn pinned_version(plugin_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(plugin_dir.join(PINNED_MARKER)).ok()?;
+    let version = content.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+pub fn pin(plugin_dir: &Path, version: &str) -> std::io::Result<()> {
+    tracing::trace!(dir = %plugin_dir.display(), version = %version, "Pinning plugin");
+    std::fs::write(plugin_dir.join(PINNED_MARKER), version)
+}
+
+/// Remove the pin. Returns whether the plugin was pinned.
+pub fn unpin(plugin_dir: &Path) -> std::io::Result<bool> {
+    match std::fs::remove_file(plugin_dir.join(PINNED_MARKER)) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_and_unpin() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!is_pinned(dir.path()));
+
+        pin(dir.path(), "1.2.0").unwrap();
+        assert!(is_pinned(dir.path()));
+        assert_eq!(pinned_version(dir.path()).as_deref(), Some("1.2.0"));
+
+        assert!(unpin(dir.path()).unwrap());
+        assert!(!is_pinned(dir.path()));
+        assert!(!unpin(dir.path()).unwrap());
+    }
+}
@@ -0,0 +1,176 @@
+//! Cleanup of leftovers in the plugins dir (`adi plugin prune`).
+//!
+//! Updates and failed installs can leave old version dirs next to the one
+//! `.version` points at. Those are prunable, as are `.<id>.last-check`
+//! stamps for plugins that are no longer installed. A plugin whose active
+//! version can't be determined (no `.version`, or one naming a missing dir)
+//! is left alone and reported instead, as are symlinked plugin dirs.
+
+use std::path::{Path, PathBuf};
+
+use lib_plugin_host::command_index::{COMMANDS_DIR_NAME, LATEST_LINK_NAME};
+
+const LAST_CHECK_SUFFIX: &str = ".last-check";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prunable {
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct PruneReport {
+    /// Orphaned version dirs and stamps (already removed unless a dry run)
+    pub removed: Vec<Prunable>,
+    /// Plugins skipped because their active version is unknown
+    pub needs_attention: Vec<String>,
+}
+
+impl PruneReport {
+    pub fn reclaimed_bytes(&self) -> u64 {
+        self.removed.iter().map(|p| p.bytes).sum()
+    }
+}
+
+/// Everything under `plugins_dir` that [`remove`] would delete.
+pub fn find_prunable(plugins_dir: &Path) -> PruneReport {
+    let mut report = PruneReport::default();
+    let Ok(entries) = std::fs::read_dir(plugins_dir) else {
+        return report;
+    };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if name == COMMANDS_DIR_NAME {
+            continue;
+        }
+        if let Some(id) = name.strip_prefix('.').and_then(|n| n.strip_suffix(LAST_CHECK_SUFFIX)) {
+            if file_type.is_file() && !plugins_dir.join(id).is_dir() {
+                report.removed.push(prunable(entry.path()));
+            }
+            continue;
+        }
+        // Hidden files (`.lock`, ...) and linked development checkouts are not ours to prune.
+        if name.starts_with('.') || !file_type.is_dir() {
+            continue;
+        }
+
+        let plugin_dir = entry.path();
+        let active = std::fs::read_to_string(plugin_dir.join(".version"))
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default();
+        if active.is_empty() || !plugin_dir.join(&active).is_dir() {
+            tracing::trace!(plugin = %name, active = %active, "Active version unknown, skipping prune");
+            report.needs_attention.push(name);
+            continue;
+        }
+        report.removed.extend(stale_versions(&plugin_dir, &active).into_iter().map(prunable));
+    }
+    report
+}
+
+/// Version dirs of one plugin other than `active`.
+fn stale_versions(plugin_dir: &Path, active: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(plugin_dir) else {
+        return Vec::new();
+    };
+    let mut stale: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && name != LATEST_LINK_NAME && name != active
+        })
+        .map(|entry| entry.path())
+        .collect();
+    stale.sort();
+    stale
+}
+
+/// Delete everything in `report.removed`.
+pub fn remove(report: &PruneReport) -> std::io::Result<()> {
+    for item in &report.removed {
+        tracing::trace!(path = %item.path.display(), bytes = item.bytes, "Pruning");
+        let result = if item.path.is_dir() {
+            std::fs::remove_dir_all(&item.path)
+        } else {
+            std::fs::remove_file(&item.path)
+        };
+        match result {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+fn prunable(path: PathBuf) -> Prunable {
+    let bytes = size_on_disk(&path);
+    Prunable { path, bytes }
+}
+
+/// Total size of the files under `path`, not following symlinks.
+fn size_on_disk(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| size_on_disk(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version_dir(plugin_dir: &Path, version: &str, bytes: usize) {
+        std::fs::create_dir_all(plugin_dir.join(version)).unwrap();
+        std::fs::write(plugin_dir.join(version).join("plugin.toml"), vec![b'x'; bytes]).unwrap();
+    }
+
+    #[test]
+    fn test_prunes_inactive_versions_and_dangling_stamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let hive = dir.path().join("adi.hive");
+        version_dir(&hive, "1.0.0", 10);
+        version_dir(&hive, "1.1.0", 20);
+        version_dir(&hive, "1.2.0", 30);
+        std::fs::write(hive.join(".version"), "1.2.0\n").unwrap();
+        // No `.version`: can't tell which one is live.
+        let tasks = dir.path().join("adi.tasks");
+        version_dir(&tasks, "0.1.0", 5);
+        version_dir(&tasks, "0.2.0", 5);
+        std::fs::write(dir.path().join(".adi.hive.last-check"), "").unwrap();
+        std::fs::write(dir.path().join(".adi.lang.uk.last-check"), "").unwrap();
+        std::fs::write(dir.path().join(".lock"), "").unwrap();
+
+        let report = find_prunable(dir.path());
+        let paths: Vec<_> = report.removed.iter().map(|p| p.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                dir.path().join(".adi.lang.uk.last-check"),
+                hive.join("1.0.0"),
+                hive.join("1.1.0"),
+            ]
+        );
+        assert_eq!(report.reclaimed_bytes(), 30);
+        assert_eq!(report.needs_attention, ["adi.tasks"]);
+
+        remove(&report).unwrap();
+        assert!(hive.join("1.2.0").exists() && !hive.join("1.0.0").exists());
+        assert!(tasks.join("0.1.0").exists() && tasks.join("0.2.0").exists());
+        assert!(dir.path().join(".adi.hive.last-check").exists());
+        assert!(find_prunable(dir.path()).removed.is_empty());
+    }
+}
@@ -1,16 +1,164 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use indicatif::{ProgressBar, ProgressStyle};
+use futures::future::BoxFuture;
+use futures::stream::{self, StreamExt};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use lib_console_output::input::Confirm;
 use lib_console_output::{theme, out_info, out_success, out_warn};
-use lib_i18n_core::t;
+use lib_i18n_core::{t, LocalizedError};
+use lib_plugin_host::command_index::LATEST_LINK_NAME;
 use lib_plugin_host::{is_glob_pattern, InstallResult, PluginConfig, PluginInstaller, UpdateCheck};
 use registry_client::{PluginEntry, PluginInfo, SearchResults};
 
-use crate::error::Result;
+use crate::archive::ExtractLimits;
+use crate::error::{InstallerError, Result};
+use crate::install_queue::InstallQueue;
+use crate::plugin_lock::{hash_plugin_dir, LockEntry, PluginLock};
+use crate::plugin_prune::{self, PruneReport};
+use crate::registry_auth::{self, RegistryToken};
+use crate::registry_cache::{self, RegistryCache};
+use crate::retry;
+use crate::state_lock::StateLock;
+use crate::system_binary;
+use crate::tn;
+
+/// Pause before an automatic `--retry` round, giving flaky networks a moment.
+const RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+/// Registry lookups `check_updates` keeps in flight at once.
+const UPDATE_CHECK_CONCURRENCY: usize = 16;
+/// How long the connectivity check waits for the registry to answer.
+const REGISTRY_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// What a dry run would do with one plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanAction {
+    New,
+    Upgrade { from: String },
+    Downgrade { from: String },
+    /// Already installed at a suitable version; nothing to download
+    Satisfied,
+}
+
+/// One step of a dry-run plan, in the order the real run would take it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedInstall {
+    pub id: String,
+    pub version: String,
+    /// Download size; unknown for versions other than the registry's latest
+    pub size_bytes: Option<u64>,
+    pub action: PlanAction,
+}
+
+/// Where installs and updates get plugin releases from, instead of the
+/// plugin registry (see [`PluginManager::with_source`]).
+pub trait PluginSource: Send + Sync {
+    /// Current version and download size of `id` on `platform`, `None` if
+    /// the source doesn't have it.
+    fn release<'a>(&'a self, id: &'a str, platform: &'a str) -> BoxFuture<'a, Result<Option<(String, u64)>>>;
+
+    /// Unpack `id` at `version` (the current one if `None`) into
+    /// `<plugin_dir>/<version>` and make it the installed version.
+    fn fetch<'a>(&'a self, id: &'a str, version: Option<&'a str>, plugin_dir: &'a std::path::Path) -> BoxFuture<'a, Result<FetchedRelease>>;
+}
+
+/// A release a [`PluginSource`] has unpacked.
+pub struct FetchedRelease {
+    pub version: String,
+    /// The unpacked version dir
+    pub path: PathBuf,
+}
+
+impl From<InstallResult> for FetchedRelease {
+    fn from(result: InstallResult) -> Self {
+        Self {
+            version: result.version,
+            path: result.path,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchKind {
+    #[default]
+    All,
+    Packages,
+    Plugins,
+}
+
+/// `--type`/`--tag` narrowing of search results. Matching ignores case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFilter {
+    pub plugin_type: Option<String>,
+    /// All of these must be present
+    pub tags: Vec<String>,
+}
+
+impl SearchFilter {
+    pub fn is_empty(&self) -> bool {
+        self.plugin_type.is_none() && self.tags.is_empty()
+    }
+
+    /// Whether an entry with these types and tags passes. Packages have no
+    /// types, so they never pass a `--type` filter.
+    pub fn matches(&self, types: &[String], tags: &[String]) -> bool {
+        let has = |list: &[String], wanted: &str| list.iter().any(|v| v.eq_ignore_ascii_case(wanted));
+        self.plugin_type.as_deref().is_none_or(|t| has(types, t))
+            && self.tags.iter().all(|tag| has(tags, tag))
+    }
+
+    fn apply(&self, results: &mut SearchResults) {
+        results.packages.retain(|pkg| self.matches(&[], &pkg.tags));
+        results.plugins.retain(|plugin| self.matches(&plugin.plugin_types, &plugin.tags));
+    }
+}
+
+impl std::fmt::Display for SearchFilter {
+    /// `type=translation, tag=indexer`, for "no results" hints.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self
+            .plugin_type
+            .iter()
+            .map(|t| format!("type={}", t))
+            .chain(self.tags.iter().map(|tag| format!("tag={}", tag)))
+            .collect();
+        f.write_str(&parts.join(", "))
+    }
+}
 
 pub struct PluginManager {
     installer: PluginInstaller,
+    jobs: usize,
+    install_jobs: usize,
+    /// Keeps concurrent download bars from overwriting each other
+    progress: MultiProgress,
+    /// Per-plugin locks so concurrent installs sharing a dependency don't
+    /// install it twice
+    install_locks: tokio::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    optional_deps: bool,
+    prefer_system: bool,
+    resume: bool,
+    retries: u32,
+    token: Option<RegistryToken>,
+    registry_url: String,
+    /// Result of the first connectivity check, reused for the manager's lifetime
+    registry_reachable: tokio::sync::OnceCell<bool>,
+    /// Registry responses, written on every lookup and the only source when offline
+    cache: RegistryCache,
+    offline: bool,
+    plugins_dir: PathBuf,
+    /// Previous versions are backed up under `<cache_dir>/rollback` during updates
+    cache_dir: PathBuf,
+    /// Cross-process lock for installs, updates and uninstalls, shared by
+    /// every operation of this manager while one holds it
+    state_lock: tokio::sync::Mutex<std::sync::Weak<StateLock>>,
+    /// Resolve installs and updates into [`Self::take_plan`] instead of
+    /// downloading anything
+    dry_run: bool,
+    plan: std::sync::Mutex<Vec<PlannedInstall>>,
+    /// Replaces the registry for release lookups and downloads
+    source: Option<Arc<dyn PluginSource>>,
 }
 
 impl Default for PluginManager {
@@ -22,54 +170,311 @@ impl Default for PluginManager {
 impl PluginManager {
     pub fn new() -> Self {
         let registry_url = crate::clienv::registry_url();
-        let config = PluginConfig::default().with_registry(&registry_url);
-
+        let config = plugin_config(&registry_url);
         tracing::trace!(
             registry_url = %registry_url,
             plugins_dir = %config.plugins_dir.display(),
             cache_dir = %config.cache_dir.display(),
             "Creating PluginManager"
         );
+        Self::from_config(registry_url, &config)
+    }
+
+    pub fn with_registry_url(url: &str) -> Self {
+        tracing::trace!(registry_url = %url, "Creating PluginManager with custom registry URL");
+        Self::from_config(url.to_string(), &plugin_config(url))
+    }
+
+    fn from_config(registry_url: String, config: &PluginConfig) -> Self {
+        let token = registry_auth::token_for(&registry_url);
+        tracing::trace!(authenticated = token.is_some(), "Registry credentials");
 
         Self {
-            installer: PluginInstaller::from_config(&config),
+            installer: PluginInstaller::from_config(config),
+            jobs: crate::clienv::jobs(),
+            install_jobs: crate::clienv::install_concurrency(),
+            progress: MultiProgress::new(),
+            install_locks: Default::default(),
+            optional_deps: false,
+            prefer_system: false,
+            resume: false,
+            retries: 0,
+            token,
+            cache: RegistryCache::new(&config.cache_dir, &registry_url),
+            registry_url,
+            registry_reachable: Default::default(),
+            offline: crate::clienv::offline(),
+            plugins_dir: config.plugins_dir.clone(),
+            cache_dir: config.cache_dir.clone(),
+            state_lock: Default::default(),
+            dry_run: false,
+            plan: Default::default(),
+            source: None,
         }
     }
 
-    pub fn with_registry_url(url: &str) -> Self {
-        let config = PluginConfig::default().with_registry(url);
+    /// Max concurrent operations for batch methods (1 = serial).
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self.install_jobs = self.jobs;
+        self
+    }
 
-        tracing::trace!(registry_url = %url, "Creating PluginManager with custom registry URL");
+    pub fn jobs(&self) -> usize {
+        self.jobs
+    }
 
-        Self {
-            installer: PluginInstaller::from_config(&config),
+    /// Also install dependencies a plugin lists under `optional_deps`.
+    pub fn with_optional_deps(mut self, enabled: bool) -> Self {
+        self.optional_deps = enabled;
+        self
+    }
+
+    /// Point plugins that declare a `[system_binary]` at a compatible copy
+    /// already on PATH instead of their bundled one.
+    pub fn with_prefer_system(mut self, enabled: bool) -> Self {
+        self.prefer_system = enabled;
+        self
+    }
+
+    /// Continue an interrupted pattern install from its saved queue instead
+    /// of searching the registry again.
+    pub fn with_resume(mut self, enabled: bool) -> Self {
+        self.resume = enabled;
+        self
+    }
+
+    /// Automatically retry failed pattern installs up to `retries` times.
+    /// With 0, an interactive session is asked instead.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Look up and download releases from `source` instead of the registry.
+    /// Searches, listings and update checks still go to the registry.
+    pub fn with_source(mut self, source: Arc<dyn PluginSource>) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Answer registry lookups from the registry cache only (defaults to $ADI_OFFLINE).
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Run installs and updates through resolution only (pattern matching,
+    /// dependencies, versions) and record what they would do; see
+    /// [`Self::take_plan`]. Nothing is downloaded or written.
+    pub fn with_dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// The steps recorded by dry-run operations so far, in order.
+    pub fn take_plan(&self) -> Vec<PlannedInstall> {
+        std::mem::take(&mut *self.plan.lock().expect("plan lock poisoned"))
+    }
+
+    fn record_plan(&self, step: PlannedInstall) {
+        let mut plan = self.plan.lock().expect("plan lock poisoned");
+        // A dependency shared by several plugins is only fetched once.
+        if !plan.iter().any(|planned| planned.id == step.id) {
+            tracing::trace!(step = ?step, "Planned install step");
+            plan.push(step);
+        }
+    }
+
+    fn plan_satisfied(&self, id: &str, installed: &str) {
+        if self.dry_run {
+            self.record_plan(PlannedInstall {
+                id: id.to_string(),
+                version: installed.to_string(),
+                size_bytes: None,
+                action: PlanAction::Satisfied,
+            });
+        }
+    }
+
+    /// Record the download an install (`current` is `None`) or version
+    /// switch of `id` would make.
+    async fn plan_download(&self, id: &str, version: Option<&str>, current: Option<&str>) -> Result<()> {
+        let platform = lib_plugin_manifest::current_platform();
+        let (latest, size_bytes) = self.fetch_install_metadata(id, &platform).await?;
+        let version = version.unwrap_or(&latest);
+        let action = match current {
+            None => PlanAction::New,
+            Some(from) if is_older_version(version, from) => PlanAction::Downgrade { from: from.to_string() },
+            Some(from) => PlanAction::Upgrade { from: from.to_string() },
+        };
+        self.record_plan(PlannedInstall {
+            id: id.to_string(),
+            version: version.to_string(),
+            // The registry only reports sizes for its latest version.
+            size_bytes: (version == latest).then_some(size_bytes),
+            action,
+        });
+        Ok(())
+    }
+
+    /// Hold the plugins dir lock for a mutating operation. Nested and
+    /// concurrent operations of this manager share one hold, so installing
+    /// dependencies doesn't wait on the install that asked for them.
+    async fn lock_state(&self) -> Result<Option<Arc<StateLock>>> {
+        if self.dry_run {
+            return Ok(None);
+        }
+        let mut held = self.state_lock.lock().await;
+        if let Some(lock) = held.upgrade() {
+            return Ok(Some(lock));
+        }
+        let lock = Arc::new(StateLock::acquire(&self.plugins_dir, crate::clienv::lock_timeout()).await?);
+        *held = Arc::downgrade(&lock);
+        Ok(Some(lock))
+    }
+
+    /// A cached registry response, for offline mode.
+    fn cached<T: serde::de::DeserializeOwned>(&self, key: &str, what: impl FnOnce() -> String) -> Result<T> {
+        tracing::trace!(key = %key, "Offline, reading registry cache");
+        self.cache
+            .load(key)
+            .ok_or_else(|| InstallerError::OfflineNotCached { what: what() })
+    }
+
+    /// A registry lookup through the cache: an entry younger than the TTL is
+    /// used as is, otherwise `fetch` runs and its result is cached. If the
+    /// fetch fails, a stale entry is better than nothing, and when the registry
+    /// can't be reached at all the fetch isn't tried. Offline mode only reads
+    /// the cache.
+    async fn through_cache<T, F>(&self, key: &str, what: impl FnOnce() -> String, fetch: F) -> Result<T>
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+        F: std::future::Future<Output = Result<T>>,
+    {
+        if self.offline {
+            return self.cached(key, what);
+        }
+        if let Some(fresh) = self.cache.load_fresh(key, crate::clienv::registry_cache_ttl()) {
+            return Ok(fresh);
+        }
+        if let Err(e) = self.ensure_registry_reachable().await {
+            let Some(stale) = self.cache.load(key) else {
+                return Err(e);
+            };
+            tracing::trace!(key = %key, "Registry unreachable, using cached entry");
+            out_warn!("{}", t!("plugin-registry-unreachable-using-cache"));
+            return Ok(stale);
+        }
+        match fetch.await {
+            Ok(value) => {
+                self.cache.store(key, &value);
+                Ok(value)
+            }
+            Err(e @ InstallerError::PluginNotFound { .. }) => Err(e),
+            Err(e) => match self.cache.load(key) {
+                Some(stale) => {
+                    tracing::trace!(key = %key, error = %e, "Registry fetch failed, using stale cache entry");
+                    out_warn!("{}", t!("plugin-registry-using-stale-cache", "error" => &e.localized()));
+                    Ok(stale)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Fail fast with [`InstallerError::RegistryUnreachable`] instead of
+    /// letting each request hang until its own timeout. Checked once per manager.
+    async fn ensure_registry_reachable(&self) -> Result<()> {
+        let reachable = *self
+            .registry_reachable
+            .get_or_init(|| registry_reachable(&self.registry_url, self.token.as_ref(), REGISTRY_PROBE_TIMEOUT))
+            .await;
+        if !reachable {
+            return Err(InstallerError::RegistryUnreachable { url: self.registry_url.clone() });
         }
+        Ok(())
+    }
+
+    /// Wipe the registry cache. Returns the bytes freed.
+    pub fn clear_registry_cache(&self) -> Result<u64> {
+        tracing::trace!(dir = %self.cache.dir().display(), "Clearing registry cache");
+        Ok(self.cache.clear()?)
+    }
+
+    pub fn registry_cache_dir(&self) -> &std::path::Path {
+        self.cache.dir()
+    }
+
+    fn rollback_dir(&self) -> PathBuf {
+        self.cache_dir.join("rollback")
     }
 
     pub async fn search(&self, query: &str) -> Result<SearchResults> {
-        tracing::trace!(query = %query, "Searching plugin registry");
-        let results = self.installer.search(query).await?;
+        self.search_kind(query, SearchKind::All, &SearchFilter::default()).await
+    }
+
+    /// Search scoped to packages, plugins, or both, narrowed by `filter`. The
+    /// registry returns both kinds in one response and takes no filters, so
+    /// both are applied to the result.
+    pub async fn search_kind(&self, query: &str, kind: SearchKind, filter: &SearchFilter) -> Result<SearchResults> {
+        tracing::trace!(query = %query, kind = ?kind, filter = ?filter, "Searching plugin registry");
+        let mut results: SearchResults = self
+            .through_cache(
+                &registry_cache::search_key(query),
+                || format!("search results for '{}'", query),
+                async { Ok(self.installer.search(query).await?) },
+            )
+            .await?;
+        match kind {
+            SearchKind::All => {}
+            SearchKind::Packages => results.plugins.clear(),
+            SearchKind::Plugins => results.packages.clear(),
+        }
+        filter.apply(&mut results);
         tracing::trace!(packages = results.packages.len(), plugins = results.plugins.len(), "Search complete");
         Ok(results)
     }
 
     pub async fn list_plugins(&self) -> Result<Vec<PluginEntry>> {
         tracing::trace!("Listing available plugins from registry");
-        let plugins = self.installer.list_available().await?;
+        let plugins: Vec<PluginEntry> = self
+            .through_cache(registry_cache::PLUGINS_KEY, || "the plugin list".to_string(), async {
+                Ok(self.installer.list_available().await?)
+            })
+            .await?;
         tracing::trace!(count = plugins.len(), "Available plugins fetched");
         Ok(plugins)
     }
 
     pub async fn get_plugin_info(&self, id: &str) -> Result<Option<PluginInfo>> {
         tracing::trace!(id = %id, "Fetching plugin info from registry");
-        let info = self.installer.get_plugin_info(id).await?;
+        let lookup = self.through_cache(&registry_cache::info_key(id), || format!("registry info for '{}'", id), async {
+            self.get_plugin_info_with_retries(id)
+                .await?
+                .ok_or_else(|| InstallerError::PluginNotFound { id: id.to_string() })
+        });
+        let info = match lookup.await {
+            Ok(info) => Some(info),
+            Err(InstallerError::PluginNotFound { .. }) => None,
+            Err(e) => return Err(e),
+        };
         tracing::trace!(id = %id, found = info.is_some(), "Plugin info result");
         Ok(info)
     }
 
     pub async fn list_installed(&self) -> Result<Vec<(String, String)>> {
         tracing::trace!("Listing installed plugins");
-        let installed = self.installer.list_installed().await?;
+        let mut installed = self.installer.list_installed().await?;
+        installed.sort();
         tracing::trace!(count = installed.len(), "Installed plugins listed");
         Ok(installed)
     }
@@ -87,6 +492,19 @@ impl PluginManager {
     }
 
     pub async fn install_plugin(&self, id: &str, version: Option<&str>) -> Result<()> {
+        self.install_plugin_verified(id, version, |_| Ok(())).await
+    }
+
+    /// [`Self::install_plugin`], with `verify` run on the extracted version
+    /// dir before the install is committed. If it fails, the new version is
+    /// removed and the previous `.version` marker restored.
+    async fn install_plugin_verified(
+        &self,
+        id: &str,
+        version: Option<&str>,
+        verify: impl FnOnce(&std::path::Path) -> Result<()>,
+    ) -> Result<()> {
+        let _state = self.lock_state().await?;
         let platform = lib_plugin_manifest::current_platform();
         tracing::trace!(id = %id, version = ?version, platform = %platform, "Installing plugin");
 
@@ -98,7 +516,20 @@ impl PluginManager {
             "platform" => &platform
         ));
 
-        let result = self.download_with_progress(id, version, size_bytes).await?;
+        crate::disk_space::ensure_available(&self.plugin_path(id), crate::disk_space::estimate_from_download(size_bytes))?;
+
+        let target_version = version.unwrap_or(&plugin_version);
+        let guard = PartialInstall::begin(self.plugin_path(id), target_version);
+
+        let result = match &self.source {
+            Some(source) => source.fetch(id, version, &self.plugin_path(id)).await?,
+            None => self.download_with_progress(id, version, size_bytes).await?.into(),
+        };
+        // The plugin host unpacks the archive itself, so the limits can only
+        // be checked after the fact; the guard removes an oversized result.
+        crate::archive::check_extracted(&result.path, &ExtractLimits::from_env().with_declared_size(size_bytes))?;
+        verify(&self.plugin_path(id).join(&result.version))?;
+        guard.commit();
 
         tracing::trace!(id = %id, version = %result.version, path = %result.path.display(), "Plugin downloaded and extracted");
         out_info!("{}", t!("plugin-install-extracting", "path" => &result.path.display().to_string()));
@@ -107,8 +538,75 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Install a plugin from a local build (`adi plugin install --from <path>`):
+    /// a directory containing plugin.toml, or a `.tar.gz` of one. Platform and
+    /// checksum checks are skipped; the files land in the same layout as a
+    /// registry install. Missing required dependencies come from the registry.
+    /// Returns the installed plugin id.
+    pub async fn install_from_path(&self, source: &std::path::Path) -> Result<String> {
+        let _state = self.lock_state().await?;
+        tracing::trace!(source = %source.display(), "Installing plugin from local path");
+        let _staging;
+        // Only known for archives; a directory is copied as is.
+        let mut unpacked_size = 0;
+        let root = if source.is_dir() {
+            source.to_path_buf()
+        } else if is_tarball(source) {
+            let cache_dir = crate::clienv::cache_dir();
+            std::fs::create_dir_all(&cache_dir)?;
+            unpacked_size = crate::disk_space::tar_gz_unpacked_size(std::fs::File::open(source)?)?;
+            crate::disk_space::ensure_available(&cache_dir, unpacked_size)?;
+            let dir = tempfile::Builder::new().prefix("adi-local-install-").tempdir_in(&cache_dir)?;
+            crate::archive::unpack_tar_gz(std::fs::File::open(source)?, dir.path(), &ExtractLimits::from_env())?;
+            let root = dir.path().to_path_buf();
+            _staging = dir;
+            root
+        } else {
+            return Err(InstallerError::ConfigError(format!(
+                "{} is not a plugin directory or .tar.gz archive",
+                source.display()
+            )));
+        };
+
+        let manifest_path = crate::plugin_runtime::find_plugin_toml_path(&root).ok_or_else(|| {
+            InstallerError::ConfigError(format!("no plugin.toml found in {}", source.display()))
+        })?;
+        let manifest = lib_plugin_manifest::PluginManifest::from_file(&manifest_path)
+            .map_err(|e| InstallerError::ConfigError(e.to_string()))?;
+        let id = manifest.plugin.id.clone();
+        let version = manifest.plugin.version.clone();
+        let manifest_dir = manifest_path.parent().unwrap_or(&root);
+
+        out_info!("{}", t!("plugin-install-local", "id" => &id, "version" => &version, "path" => &source.display().to_string()));
+        {
+            let lock = self.install_lock(&id).await;
+            let _held = lock.lock().await;
+            let plugin_dir = self.plugin_path(&id);
+            crate::disk_space::ensure_available(&plugin_dir, unpacked_size)?;
+            let guard = PartialInstall::begin(plugin_dir.clone(), &version);
+            install_local_files(manifest_dir, &plugin_dir, &version)?;
+            guard.commit();
+        }
+        if self.prefer_system {
+            self.use_system_binary(&id);
+        }
+        out_success!("{}", t!("plugin-install-success", "id" => &id, "version" => &version));
+
+        let mut installing = HashSet::from([id.clone()]);
+        for dep in self.dependencies(&id)? {
+            self.install_dependency(&id, &dep, &mut installing).await?;
+        }
+        Ok(id)
+    }
+
     async fn fetch_install_metadata(&self, id: &str, platform: &str) -> Result<(String, u64)> {
-        let info = self.installer.get_plugin_info(id).await?
+        if let Some(source) = &self.source {
+            return source
+                .release(id, platform)
+                .await?
+                .ok_or_else(|| InstallerError::PluginNotFound { id: id.to_string() });
+        }
+        let info = self.get_plugin_info_with_retries(id).await?
             .ok_or_else(|| crate::error::InstallerError::PluginNotFound { id: id.to_string() })?;
 
         let size_bytes = info
@@ -124,24 +622,61 @@ impl PluginManager {
         Ok((info.version, size_bytes))
     }
 
+    /// Always asks the registry when online (installs need the current
+    /// version), caching the answer for offline mode and [`Self::get_plugin_info`].
+    async fn get_plugin_info_with_retries(&self, id: &str) -> Result<Option<PluginInfo>> {
+        let key = registry_cache::info_key(id);
+        if self.offline {
+            return self.cached(&key, || format!("registry info for '{}'", id)).map(Some);
+        }
+        self.ensure_registry_reachable().await?;
+        let info = retry::with_retries(crate::clienv::download_retries(), retry::BASE_DELAY, |_| async move {
+            Ok(self.installer.get_plugin_info(id).await?)
+        })
+        .await?;
+        if let Some(info) = &info {
+            self.cache.store(&key, info);
+        }
+        Ok(info)
+    }
+
     async fn download_with_progress(&self, id: &str, version: Option<&str>, size_bytes: u64) -> Result<InstallResult> {
-        let pb = create_progress_bar(size_bytes);
-        let result = self
-            .installer
-            .install(id, version, |done, total| {
-                pb.set_length(total);
-                pb.set_position(done);
-            })
-            .await?;
+        let pb = self.progress.add(create_progress_bar(size_bytes));
+        pb.set_prefix(id.to_string());
+        let pb_ref = &pb;
+        let result = retry::with_retries(crate::clienv::download_retries(), retry::BASE_DELAY, move |attempt| {
+            let pb = pb_ref;
+            if attempt > 1 {
+                // Start the bar over rather than appending to the failed attempt.
+                tracing::trace!(id = %id, attempt = attempt, "Retrying plugin download");
+                pb.reset();
+                pb.set_message(format!("retry {}", attempt - 1));
+            }
+            async move {
+                Ok(self
+                    .installer
+                    .install(id, version, |done, total| {
+                        pb.set_length(total);
+                        pb.set_position(done);
+                    })
+                    .await?)
+            }
+        })
+        .await?;
         pb.finish_with_message("downloaded");
         Ok(result)
     }
 
     pub async fn install_with_dependencies(&self, id: &str, version: Option<&str>) -> Result<()> {
+        let _state = self.lock_state().await?;
         tracing::trace!(id = %id, version = ?version, "Installing plugin with dependencies");
         let mut installing = HashSet::new();
 
         if let Some(current_version) = self.installer.is_installed(id) {
+            if self.dry_run {
+                self.plan_satisfied(id, &current_version);
+                return Ok(());
+            }
             out_info!("{}", t!("plugin-install-already-installed",
                 "id" => id,
                 "version" => &current_version
@@ -164,26 +699,238 @@ impl PluginManager {
         }
         installing.insert(id.to_string());
 
-        if self.installer.is_installed(id).is_some() {
-            tracing::trace!(id = %id, "Plugin already installed, skipping");
-            return Ok(());
+        {
+            let lock = self.install_lock(id).await;
+            let _held = lock.lock().await;
+            if let Some(installed) = self.installer.is_installed(id) {
+                // Only an installed dependency too old for its dependent comes with a version.
+                let Some(target) = version.filter(|v| *v != installed) else {
+                    tracing::trace!(id = %id, "Plugin already installed, skipping");
+                    self.plan_satisfied(id, &installed);
+                    return Ok(());
+                };
+                self.apply_update(id, &installed, target).await?;
+            } else if self.dry_run {
+                // Dependencies of a plugin that isn't installed come from
+                // the installer's registry view below.
+                self.plan_download(id, version, None).await?;
+            } else {
+                self.install_plugin(id, version).await?;
+                if self.prefer_system {
+                    self.use_system_binary(id);
+                }
+            }
         }
 
-        self.install_plugin(id, version).await?;
+        let optional = self.optional_dependencies(id);
+        let mut deps: Vec<DependencySpec> = self
+            .dependencies(id)?
+            .into_iter()
+            .filter(|d| !optional.contains(&d.id))
+            .collect();
+        tracing::trace!(id = %id, deps = ?deps, optional = ?optional, "Checking plugin dependencies");
 
-        let deps = self.installer.get_dependencies(id);
-        tracing::trace!(id = %id, deps = ?deps, "Checking plugin dependencies");
-        for dep in deps {
-            if !installing.contains(&dep) {
-                out_info!("{}", t!("plugin-install-dependency", "id" => &dep));
-                Box::pin(self.install_recursive(&dep, None, installing)).await?;
+        if self.optional_deps {
+            deps.extend(optional.iter().map(|dep| DependencySpec { id: dep.clone(), requirement: None }));
+        } else {
+            for dep in optional.iter().filter(|d| self.installer.is_installed(d).is_none()) {
+                out_info!("{}", t!("plugin-install-optional-skipped", "id" => dep));
             }
         }
 
+        for dep in deps {
+            self.install_dependency(id, &dep, installing).await?;
+        }
+
         Ok(())
     }
 
+    /// Install `dep` of `plugin` if it's missing, or upgrade it if the
+    /// installed version is too old for `plugin`, then its own dependencies.
+    async fn install_dependency(&self, plugin: &str, dep: &DependencySpec, installing: &mut HashSet<String>) -> Result<()> {
+        if installing.contains(&dep.id) {
+            return Ok(());
+        }
+        let version = match self.installer.is_installed(&dep.id) {
+            Some(installed) => self.installed_dependency_upgrade(plugin, dep, &installed).await?,
+            None => self.resolve_dependency_version(dep).await?,
+        };
+        out_info!("{}", t!("plugin-install-dependency", "id" => &dep.id));
+        Box::pin(self.install_recursive(&dep.id, version.as_deref(), installing)).await
+    }
+
+    /// Record a compatible system binary for a freshly installed plugin, or
+    /// say why the bundled copy is used.
+    fn use_system_binary(&self, id: &str) {
+        let Some(spec) = self.installed_manifest(id).and_then(|m| system_binary::parse_spec(&m)) else {
+            return;
+        };
+        let plugin_dir = self.plugin_path(id);
+        match system_binary::resolve(&spec) {
+            Ok(path) => {
+                tracing::trace!(id = %id, path = %path.display(), "Using system binary");
+                if let Err(e) = system_binary::record(&plugin_dir, &path) {
+                    out_warn!("{}", t!("plugin-install-system-failed", "id" => id, "error" => &e.to_string()));
+                    return;
+                }
+                out_info!("{}", t!("plugin-install-system-used", "name" => &spec.name, "path" => &path.display().to_string()));
+            }
+            Err(reason) => {
+                tracing::trace!(id = %id, reason = ?reason, "System binary unusable, keeping bundled copy");
+                system_binary::clear(&plugin_dir);
+                let reason = match reason {
+                    system_binary::Unusable::NotFound => t!("plugin-install-system-not-found"),
+                    system_binary::Unusable::TooOld { found, required } => {
+                        t!("plugin-install-system-too-old", "found" => &found.to_string(), "required" => &required.to_string())
+                    }
+                    system_binary::Unusable::UnknownVersion { path } => {
+                        t!("plugin-install-system-unknown-version", "path" => &path.display().to_string())
+                    }
+                };
+                out_info!("{}", t!("plugin-install-system-bundled", "name" => &spec.name, "reason" => &reason));
+            }
+        }
+    }
+
+    /// `compatibility.depends_on` of an installed plugin, with any version
+    /// requirements. Falls back to the installer's view if the manifest
+    /// can't be read.
+    fn dependencies(&self, id: &str) -> Result<Vec<DependencySpec>> {
+        self.installed_dependencies(id)
+            .iter()
+            .map(|entry| parse_dependency(entry))
+            .collect()
+    }
+
+    /// `compatibility.depends_on` entries of an installed plugin as written
+    /// (`adi.hive`, `adi.hive >=1.2`).
+    pub fn installed_dependencies(&self, id: &str) -> Vec<String> {
+        match self.installed_manifest(id) {
+            Some(content) => parse_depends_on(&content),
+            None => self.installer.get_dependencies(id),
+        }
+    }
+
+    /// Registry version to install for a dependency that isn't installed
+    /// yet: `None` (latest) when unconstrained. The registry only reports its
+    /// latest version, so a requirement that excludes it can't be met.
+    async fn resolve_dependency_version(&self, dep: &DependencySpec) -> Result<Option<String>> {
+        let Some(requirement) = &dep.requirement else {
+            return Ok(None);
+        };
+        let latest = self
+            .latest_version(&dep.id)
+            .await?
+            .ok_or_else(|| InstallerError::PluginNotFound { id: dep.id.clone() })?;
+        tracing::trace!(id = %dep.id, requirement = %requirement, latest = %latest, "Resolving dependency version");
+
+        if !satisfies(requirement, &latest) {
+            return Err(InstallerError::InstallationFailed {
+                component: dep.id.clone(),
+                reason: format!("no registry version satisfies {} (latest is {})", requirement, latest),
+            });
+        }
+        Ok(Some(latest))
+    }
+
+    /// The registry's current version of `id`, if it has the plugin.
+    async fn latest_version(&self, id: &str) -> Result<Option<String>> {
+        if let Some(source) = &self.source {
+            let platform = lib_plugin_manifest::current_platform();
+            return Ok(source.release(id, &platform).await?.map(|(version, _)| version));
+        }
+        Ok(self.get_plugin_info_with_retries(id).await?.map(|info| info.version))
+    }
+
+    /// Version to upgrade an already installed dependency to when it is too
+    /// old for what `plugin` requires; `None` if it already satisfies it.
+    /// Fails with `DependencyPinned` if the dependency is pinned, and with
+    /// `DependencyConflict` if the registry's version doesn't satisfy the
+    /// requirement or would break another installed plugin's requirement,
+    /// naming the plugins that hold it back.
+    async fn installed_dependency_upgrade(
+        &self,
+        plugin: &str,
+        dep: &DependencySpec,
+        installed: &str,
+    ) -> Result<Option<String>> {
+        let Some(requirement) = &dep.requirement else {
+            return Ok(None);
+        };
+        if satisfies(requirement, installed) {
+            return Ok(None);
+        }
+        if crate::plugin_pin::is_pinned(&self.plugin_path(&dep.id)) {
+            tracing::trace!(plugin = %plugin, dependency = %dep.id, installed = %installed, "Outdated dependency is pinned");
+            return Err(InstallerError::DependencyPinned {
+                plugin: plugin.to_string(),
+                dependency: dep.id.clone(),
+                requirement: requirement.to_string(),
+                version: installed.to_string(),
+            });
+        }
+
+        let others = self.requirements_on(&dep.id, plugin).await?;
+        let latest = self.latest_version(&dep.id).await?;
+        if let Some(target) = latest.as_deref().and_then(|latest| upgrade_target(requirement, installed, latest, &others)) {
+            tracing::trace!(plugin = %plugin, dependency = %dep.id, installed = %installed, target = %target, "Upgrading outdated dependency");
+            return Ok(Some(target));
+        }
+
+        let held_by: Vec<String> = others
+            .into_iter()
+            .map(|(other, req)| format!("{} requires {}", other, req))
+            .collect();
+        tracing::trace!(plugin = %plugin, dependency = %dep.id, installed = %installed, latest = ?latest, held_by = ?held_by, "Dependency conflict");
+
+        Err(InstallerError::DependencyConflict {
+            plugin: plugin.to_string(),
+            dependency: dep.id.clone(),
+            requirement: requirement.to_string(),
+            installed: installed.to_string(),
+            held_by,
+        })
+    }
+
+    /// Version requirements installed plugins other than `except` place on `dependency`.
+    async fn requirements_on(&self, dependency: &str, except: &str) -> Result<Vec<(String, semver::VersionReq)>> {
+        let mut requirements = Vec::new();
+        for (other, _) in self.installer.list_installed().await? {
+            if other == except {
+                continue;
+            }
+            let Ok(specs) = self.dependencies(&other) else {
+                continue;
+            };
+            requirements.extend(
+                specs
+                    .into_iter()
+                    .filter(|spec| spec.id == dependency)
+                    .filter_map(|spec| spec.requirement.map(|req| (other.clone(), req))),
+            );
+        }
+        Ok(requirements)
+    }
+
+    async fn install_lock(&self, id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        let mut locks = self.install_locks.lock().await;
+        Arc::clone(locks.entry(id.to_string()).or_default())
+    }
+
+    /// `compatibility.optional_deps` from the installed plugin's manifest.
+    fn optional_dependencies(&self, id: &str) -> Vec<String> {
+        self.installed_manifest(id)
+            .map(|content| parse_optional_deps(&content))
+            .unwrap_or_default()
+    }
+
+    fn installed_manifest(&self, id: &str) -> Option<String> {
+        let manifest_path = crate::plugin_runtime::find_plugin_toml_path(&self.plugin_path(id))?;
+        std::fs::read_to_string(manifest_path).ok()
+    }
+
     pub async fn uninstall_plugin(&self, id: &str) -> Result<()> {
+        let _state = self.lock_state().await?;
         tracing::trace!(id = %id, "Uninstalling plugin");
         out_info!("{}", t!("plugin-uninstall-progress", "id" => id));
 
@@ -195,60 +942,391 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Delete version dirs other than each plugin's active one and
+    /// `.last-check` stamps of plugins that are gone. A dry run only
+    /// reports them.
+    pub async fn prune(&self) -> Result<PruneReport> {
+        let _state = self.lock_state().await?;
+        let report = plugin_prune::find_prunable(&self.plugins_dir);
+        tracing::trace!(
+            count = report.removed.len(),
+            bytes = report.reclaimed_bytes(),
+            needs_attention = ?report.needs_attention,
+            dry_run = self.dry_run,
+            "Pruning plugins dir"
+        );
+        if !self.dry_run {
+            plugin_prune::remove(&report)?;
+        }
+        Ok(report)
+    }
+
+    /// Refuse to change a plugin pinned with `adi plugin pin`.
+    fn ensure_not_pinned(&self, id: &str) -> Result<()> {
+        let plugin_dir = self.plugin_path(id);
+        if !crate::plugin_pin::is_pinned(&plugin_dir) {
+            return Ok(());
+        }
+        let version = crate::plugin_pin::pinned_version(&plugin_dir)
+            .or_else(|| self.installer.is_installed(id))
+            .unwrap_or_default();
+        tracing::trace!(id = %id, version = %version, "Refusing to update pinned plugin");
+        Err(InstallerError::PluginPinned { id: id.to_string(), version })
+    }
+
     pub async fn update_plugin(&self, id: &str) -> Result<()> {
+        let _state = self.lock_state().await?;
         tracing::trace!(id = %id, "Checking for plugin update");
-        match self.installer.check_update(id).await? {
+        self.ensure_not_pinned(id)?;
+        match self.check_update(id).await? {
+            UpdateCheck::AlreadyLatest { version } if self.dry_run => self.plan_satisfied(id, &version),
             UpdateCheck::AlreadyLatest { version } => {
                 tracing::trace!(id = %id, version = %version, "Plugin is already at latest version");
                 out_info!("{}", t!("plugin-update-already-latest", "id" => id, "version" => &version));
             }
-            UpdateCheck::Available { current, latest } => {
-                tracing::trace!(id = %id, current = %current, latest = %latest, "Plugin update available");
-                out_info!("{}", t!("plugin-update-available",
-                    "id" => id,
-                    "current" => &current,
-                    "latest" => &latest
-                ));
+            UpdateCheck::Available { current, latest } => self.apply_update(id, &current, &latest).await?,
+        }
+
+        Ok(())
+    }
+
+    async fn check_update(&self, id: &str) -> Result<UpdateCheck> {
+        if self.offline {
+            return self.cached_update_check(id).await;
+        }
+        self.ensure_registry_reachable().await?;
+        self.installer.check_update(id).await
+    }
+
+    /// Look up the latest version of every plugin in `ids` concurrently,
+    /// without installing anything. Sorted by id.
+    pub async fn check_updates(&self, ids: &[String]) -> Vec<(String, Result<UpdateCheck>)> {
+        tracing::trace!(count = ids.len(), "Checking plugins for updates");
+        let mut checks: Vec<_> = stream::iter(ids)
+            .map(|id| async move { (id.clone(), self.check_update(id).await) })
+            .buffer_unordered(UPDATE_CHECK_CONCURRENCY)
+            .collect()
+            .await;
+        checks.sort_by(|a, b| a.0.cmp(&b.0));
+        checks
+    }
+
+    /// Install the available updates among `checks` (from [`Self::check_updates`]),
+    /// up to `jobs` at a time. Returns the per-plugin failures.
+    pub async fn apply_updates(&self, checks: Vec<(String, UpdateCheck)>) -> Result<Vec<(String, InstallerError)>> {
+        let _state = self.lock_state().await?;
+        let mut outdated = Vec::new();
+        for (id, check) in checks {
+            match check {
+                UpdateCheck::AlreadyLatest { version } if self.dry_run => self.plan_satisfied(&id, &version),
+                UpdateCheck::AlreadyLatest { .. } => {}
+                UpdateCheck::Available { current, latest } => outdated.push((id, current, latest)),
+            }
+        }
+
+        // A dry run goes one at a time so the plan comes out in order.
+        let jobs = if self.dry_run { 1 } else { self.jobs };
+        tracing::trace!(count = outdated.len(), jobs = jobs, "Applying plugin updates");
+        let mut failed: Vec<_> = stream::iter(outdated)
+            .map(|(id, current, latest)| async move {
+                let result = match self.ensure_not_pinned(&id) {
+                    Ok(()) => self.apply_update(&id, &current, &latest).await,
+                    Err(e) => Err(e),
+                };
+                (id, result)
+            })
+            .buffer_unordered(jobs)
+            .filter_map(|(id, result)| async move { result.err().map(|e| (id, e)) })
+            .collect()
+            .await;
+        failed.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(failed)
+    }
+
+    /// Move `id` from `current` to `latest`, rolling back on failure.
+    async fn apply_update(&self, id: &str, current: &str, latest: &str) -> Result<()> {
+        tracing::trace!(id = %id, current = %current, latest = %latest, "Plugin update available");
+        out_info!("{}", t!("plugin-update-available",
+            "id" => id,
+            "current" => current,
+            "latest" => latest
+        ));
+
+        if self.dry_run {
+            return self.plan_download(id, Some(latest), Some(current)).await;
+        }
+        let install = self.install_plugin(id, Some(latest));
+        with_version_rollback(&self.rollback_dir(), &self.plugin_path(id), current, false, install).await
+    }
+
+    /// `check_update` answered from the cached registry info, for offline mode.
+    async fn cached_update_check(&self, id: &str) -> Result<UpdateCheck> {
+        let current = self
+            .installer
+            .is_installed(id)
+            .ok_or_else(|| InstallerError::PluginNotFound { id: id.to_string() })?;
+        let latest = self
+            .get_plugin_info_with_retries(id)
+            .await?
+            .map(|info| info.version)
+            .unwrap_or_else(|| current.clone());
+        Ok(if latest == current {
+            UpdateCheck::AlreadyLatest { version: current }
+        } else {
+            UpdateCheck::Available { current, latest }
+        })
+    }
+
+    /// Snapshot every installed plugin with a content hash of its version dir.
+    pub async fn freeze(&self) -> Result<PluginLock> {
+        let platform = lib_plugin_manifest::current_platform();
+        tracing::trace!(platform = %platform, "Freezing installed plugins");
+
+        let mut entries = Vec::new();
+        for (id, version) in self.list_installed().await? {
+            let sha256 = hash_plugin_dir(&self.plugin_path(&id).join(&version))?;
+            tracing::trace!(id = %id, version = %version, sha256 = %sha256, "Hashed installed plugin");
+            entries.push(LockEntry {
+                id,
+                version,
+                platform: platform.to_string(),
+                sha256,
+            });
+        }
+
+        Ok(PluginLock::new(entries))
+    }
+
+    /// Freeze the installed plugins into a lock file at `path`.
+    pub async fn write_lockfile(&self, path: &std::path::Path) -> Result<PluginLock> {
+        let lock = self.freeze().await?;
+        lock.save(path)?;
+        Ok(lock)
+    }
 
-                self.install_plugin(id, Some(&latest)).await?;
+    /// Reproduce the plugin set in the lock file at `path`. With `exact`,
+    /// installed plugins the lock doesn't list are an error rather than
+    /// being left alone.
+    pub async fn install_from_lockfile(&self, path: &std::path::Path, exact: bool) -> Result<()> {
+        let lock = PluginLock::load(path)?;
+        if exact {
+            let installed = self.list_installed().await?;
+            let extra = lock.unlocked(installed.iter().map(|(id, _)| id.as_str()));
+            if !extra.is_empty() {
+                return Err(InstallerError::ConfigError(format!(
+                    "installed plugins not in {}: {}",
+                    path.display(),
+                    extra.join(", ")
+                )));
             }
         }
+        self.install_locked(&lock).await
+    }
+
+    /// Install exactly the plugins in `lock`, refusing any whose installed
+    /// content does not hash to the locked checksum. Versions always come
+    /// from the lock, never from the registry's latest.
+    pub async fn install_locked(&self, lock: &PluginLock) -> Result<()> {
+        let _state = self.lock_state().await?;
+        let platform = lib_plugin_manifest::current_platform();
+        tracing::trace!(count = lock.plugins.len(), platform = %platform, "Installing from lock");
+
+        if let Some(entry) = lock.plugins.iter().find(|e| e.platform != platform) {
+            return Err(InstallerError::Other(format!(
+                "Lock entry {} was frozen for {}, current platform is {}",
+                entry.id, entry.platform, platform
+            )));
+        }
+
+        for entry in &lock.plugins {
+            let plugin_dir = self.plugin_path(&entry.id);
+            let installed = self.installer.is_installed(&entry.id);
+            if installed.as_deref() == Some(entry.version.as_str()) {
+                out_info!("{}", t!("plugin-install-already-installed", "id" => &entry.id, "version" => &entry.version));
+                // Leave a pre-existing install that doesn't match for the user to inspect.
+                verify_locked(entry, &plugin_dir.join(&entry.version))?;
+                continue;
+            }
 
+            // A mismatch removes only the version just installed: the
+            // previous one is restored, or the plugin dir removed if there was none.
+            let install = async {
+                self.install_plugin_verified(&entry.id, Some(&entry.version), |dir| verify_locked(entry, dir))
+                    .await
+                    .map_err(|e| match e {
+                        e @ InstallerError::ChecksumMismatch { .. } => e,
+                        e => InstallerError::InstallationFailed {
+                            component: entry.id.clone(),
+                            reason: format!("locked version {} could not be installed from the registry: {}", entry.version, e.localized()),
+                        },
+                    })
+            };
+            match &installed {
+                Some(previous) => with_version_rollback(&self.rollback_dir(), &plugin_dir, previous, false, install).await?,
+                None => install.await?,
+            }
+        }
+
+        out_success!("{}", tn!("plugin-install-locked-success", lock.plugins.len()));
         Ok(())
     }
 
+    /// Switch an installed plugin to an exact version, up or down. The
+    /// previously installed version dir is kept next to the new one so the
+    /// switch can be reversed with another `--to`.
+    pub async fn update_plugin_to(&self, id: &str, version: &str) -> Result<()> {
+        let _state = self.lock_state().await?;
+        let current = self
+            .installer
+            .is_installed(id)
+            .ok_or_else(|| InstallerError::PluginNotFound { id: id.to_string() })?;
+        tracing::trace!(id = %id, current = %current, target = %version, "Switching plugin version");
+        self.ensure_not_pinned(id)?;
+
+        if current == version {
+            self.plan_satisfied(id, version);
+            if !self.dry_run {
+                out_info!("{}", t!("plugin-install-already-installed", "id" => id, "version" => version));
+            }
+            return Ok(());
+        }
+        if self.dry_run {
+            return self.plan_download(id, Some(version), Some(&current)).await;
+        }
+        if is_older_version(version, &current) {
+            out_warn!("{}", t!("plugin-update-downgrade", "id" => id, "current" => &current, "target" => version));
+        } else {
+            out_info!("{}", t!("plugin-update-available", "id" => id, "current" => &current, "latest" => version));
+        }
+
+        let install = self.install_plugin(id, Some(version));
+        with_version_rollback(&self.rollback_dir(), &self.plugin_path(id), &current, true, install).await
+    }
+
+    /// Install every plugin matching `pattern`. In a TTY, unless `assume_yes`,
+    /// the matches are offered as a multi-select before anything is installed.
     pub async fn install_plugins_matching(
         &self,
         pattern: &str,
         version: Option<&str>,
+        assume_yes: bool,
     ) -> Result<()> {
+        let _state = self.lock_state().await?;
         if !is_glob_pattern(pattern) {
             tracing::trace!(id = %pattern, "Not a glob pattern, installing single plugin");
             return self.install_with_dependencies(pattern, version).await;
         }
 
+        let saved = InstallQueue::load(pattern)?;
+        match saved {
+            Some(queue) if self.resume => {
+                tracing::trace!(pattern = %pattern, pending = queue.pending.len(), "Resuming pattern install");
+                out_info!("{}", t!("plugin-install-resuming",
+                    "pending" => &queue.pending.len().to_string(),
+                    "completed" => &queue.completed.len().to_string()
+                ));
+                let ids = queue.pending.clone();
+                let version = queue.version.clone();
+                return self.install_queued(queue, &ids, version.as_deref(), assume_yes).await;
+            }
+            Some(queue) => {
+                out_info!("{}", tn!("plugin-install-resume-available", queue.pending.len(), "pattern" => pattern));
+            }
+            None if self.resume => out_info!("{}", t!("plugin-install-resume-none", "pattern" => pattern)),
+            None => {}
+        }
+
         tracing::trace!(pattern = %pattern, "Installing plugins matching glob pattern");
         out_info!("{}", t!("plugin-install-pattern-searching", "pattern" => pattern));
 
-        let matching = self.installer.find_matching(pattern).await?;
+        let matching = if self.offline {
+            let plugins = self.list_plugins().await?;
+            plugins.into_iter().filter(|p| glob_matches(pattern, &p.id)).collect()
+        } else {
+            self.ensure_registry_reachable().await?;
+            self.installer.find_matching(pattern).await?
+        };
 
         if matching.is_empty() {
             out_warn!("{}", t!("plugin-install-pattern-none", "pattern" => pattern));
             return Ok(());
         }
 
-        Self::display_matching_plugins(&matching);
-        out_info!("{}", t!("plugin-install-pattern-installing", "count" => &matching.len().to_string()));
+        let matching = if !assume_yes
+            && !self.dry_run
+            && matching.len() > 1
+            && crate::user_config::UserConfig::is_interactive()
+        {
+            Self::select_matching_plugins(matching)
+        } else {
+            Self::display_matching_plugins(&matching);
+            matching
+        };
+
+        if matching.is_empty() {
+            out_info!("{}", t!("plugin-install-pattern-cancelled"));
+            return Ok(());
+        }
+
+        let ids: Vec<String> = matching.iter().map(|p| p.id.clone()).collect();
+        let queue = InstallQueue::new(pattern, version, ids.clone());
+        self.install_queued(queue, &ids, version, assume_yes).await
+    }
+
+    /// Install `ids`, recording each success in `queue`. The queue is
+    /// deleted if everything installed and kept for `--resume` otherwise.
+    async fn install_queued(&self, queue: InstallQueue, ids: &[String], version: Option<&str>, assume_yes: bool) -> Result<()> {
+        if self.dry_run {
+            // One at a time so the plan comes out in a stable order.
+            for id in ids {
+                self.install_with_dependencies(id, version).await?;
+            }
+            return Ok(());
+        }
+
+        out_info!("{}", tn!("plugin-install-pattern-installing", ids.len()));
+        queue.save()?;
+        let queue = std::sync::Mutex::new(queue);
+        let mut failed = self.install_batch(ids, version, Some(&queue)).await;
 
-        let failed = self.install_batch(&matching, version).await;
+        let mut attempt = 0;
+        while !failed.is_empty() && self.should_retry(attempt, failed.len(), assume_yes) {
+            attempt += 1;
+            if self.retries > 0 {
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+            out_info!("{}", tn!("plugin-install-retrying", failed.len(), "attempt" => attempt));
+            let retry = std::mem::take(&mut failed);
+            failed = self.install_batch(&retry, version, Some(&queue)).await;
+        }
 
-        Self::report_batch_results(matching.len() - failed.len(), &failed);
+        Self::report_batch_results(ids.len() - failed.len(), &failed);
 
+        let queue = queue.into_inner().expect("install queue lock poisoned");
+        if failed.is_empty() {
+            queue.clear()?;
+        } else {
+            out_info!("{}", t!("plugin-install-resume-hint", "pattern" => &queue.pattern));
+        }
         Ok(())
     }
 
+    /// `--retry N` retries automatically; otherwise an interactive user is asked.
+    fn should_retry(&self, attempt: u32, failed: usize, assume_yes: bool) -> bool {
+        if self.retries > 0 {
+            return attempt < self.retries;
+        }
+        if assume_yes || !crate::user_config::UserConfig::is_interactive() {
+            return false;
+        }
+        Confirm::new(tn!("plugin-install-retry-prompt", failed))
+            .default(false)
+            .run()
+            .unwrap_or(false)
+    }
+
     fn display_matching_plugins(plugins: &[registry_client::PluginEntry]) {
-        out_info!("{}", t!("plugin-install-pattern-found", "count" => &plugins.len().to_string()));
+        out_info!("{}", tn!("plugin-install-pattern-found", plugins.len()));
         for plugin in plugins {
             out_info!("  {} {} - {}",
                 theme::brand_bold(&plugin.id),
@@ -258,23 +1336,68 @@ impl PluginManager {
         }
     }
 
+    fn select_matching_plugins(plugins: Vec<PluginEntry>) -> Vec<PluginEntry> {
+        out_info!("{}", tn!("plugin-install-pattern-found", plugins.len()));
+        let items: Vec<String> = plugins
+            .iter()
+            .map(|p| format!("{} v{} - {}", p.id, p.latest_version, p.description))
+            .collect();
+        let defaults = vec![true; plugins.len()];
+
+        let selected = dialoguer::MultiSelect::new()
+            .with_prompt(t!("plugin-install-pattern-select"))
+            .items(&items)
+            .defaults(&defaults)
+            .interact_opt()
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        tracing::trace!(selected = selected.len(), total = plugins.len(), "User selected plugins to install");
+
+        plugins
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| selected.contains(i))
+            .map(|(_, p)| p)
+            .collect()
+    }
+
     async fn install_batch(
         &self,
-        plugins: &[registry_client::PluginEntry],
+        ids: &[String],
         version: Option<&str>,
+        queue: Option<&std::sync::Mutex<InstallQueue>>,
     ) -> Vec<String> {
-        let mut failed = Vec::new();
-        for plugin in plugins {
-            if let Err(e) = self.install_with_dependencies(&plugin.id, version).await {
-                out_warn!("Failed to install {}: {}", plugin.id, e);
-                failed.push(plugin.id.clone());
-            }
-        }
+        tracing::trace!(count = ids.len(), jobs = self.install_jobs, "Installing plugin batch");
+        let mut failed: Vec<String> = stream::iter(ids)
+            .map(|id| async move {
+                let result = self.install_with_dependencies(id, version).await;
+                (id.clone(), result)
+            })
+            .buffer_unordered(self.install_jobs)
+            .filter_map(|(id, result)| async move {
+                match result {
+                    Ok(()) => {
+                        let saved = queue.map(|q| q.lock().expect("install queue lock poisoned").mark_completed(&id));
+                        if let Some(Err(e)) = saved {
+                            tracing::warn!("Failed to update install queue: {}", e);
+                        }
+                        None
+                    }
+                    Err(e) => {
+                        out_warn!("Failed to install {}: {}", id, e);
+                        Some(id)
+                    }
+                }
+            })
+            .collect()
+            .await;
+        failed.sort();
         failed
     }
 
     fn report_batch_results(installed: usize, failed: &[String]) {
-        out_success!("{}", t!("plugin-install-pattern-success", "count" => &installed.to_string()));
+        out_success!("{}", tn!("plugin-install-pattern-success", installed));
         if !failed.is_empty() {
             out_warn!("{}", t!("plugin-install-pattern-failed"));
             for id in failed {
@@ -284,13 +1407,697 @@ impl PluginManager {
     }
 }
 
+/// Fail with `ChecksumMismatch` unless `version_dir` hashes to the checksum
+/// `entry` was frozen with.
+fn verify_locked(entry: &LockEntry, version_dir: &std::path::Path) -> Result<()> {
+    let actual = hash_plugin_dir(version_dir)?;
+    if actual != entry.sha256 {
+        tracing::trace!(id = %entry.id, expected = %entry.sha256, actual = %actual, "Locked checksum mismatch");
+        return Err(InstallerError::ChecksumMismatch {
+            id: entry.id.clone(),
+            expected: entry.sha256.clone(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Run a plugin-installing command so that Ctrl+C cancels it and exits with
+/// 130. `command` is dropped first, so the [`PartialInstall`] guards of any
+/// in-flight installs clean up. The handler takes over SIGINT for the rest of
+/// the process: wrap a whole command once, not each install.
+pub async fn cancel_on_interrupt<T>(command: impl std::future::Future<Output = T>) -> T {
+    tokio::select! {
+        output = command => return output,
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    tracing::trace!("Interrupted, partial installs removed");
+    std::process::exit(130)
+}
+
+/// Scope guard for an in-flight install. Unless committed, dropping it (on
+/// error, panic, or cancellation) removes a version dir the install created and
+/// restores the previous `.version` marker.
+struct PartialInstall {
+    plugin_dir: PathBuf,
+    version_dir: PathBuf,
+    created_plugin_dir: bool,
+    created_version_dir: bool,
+    previous_marker: Option<String>,
+    #[cfg_attr(not(unix), allow(dead_code))]
+    previous_latest: Option<PathBuf>,
+    committed: bool,
+}
+
+impl PartialInstall {
+    fn begin(plugin_dir: PathBuf, version: &str) -> Self {
+        let version_dir = plugin_dir.join(version);
+        Self {
+            created_plugin_dir: !plugin_dir.exists(),
+            created_version_dir: !version_dir.exists(),
+            previous_marker: std::fs::read_to_string(plugin_dir.join(".version")).ok(),
+            previous_latest: std::fs::read_link(plugin_dir.join(LATEST_LINK_NAME)).ok(),
+            plugin_dir,
+            version_dir,
+            committed: false,
+        }
+    }
+
+    fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for PartialInstall {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        tracing::trace!(dir = %self.version_dir.display(), "Cleaning up partial install");
+
+        if self.created_plugin_dir {
+            let _ = std::fs::remove_dir_all(&self.plugin_dir);
+            return;
+        }
+        if self.created_version_dir {
+            let _ = std::fs::remove_dir_all(&self.version_dir);
+        }
+        let marker = self.plugin_dir.join(".version");
+        match &self.previous_marker {
+            Some(previous) => {
+                let _ = std::fs::write(&marker, previous);
+            }
+            None => {
+                let _ = std::fs::remove_file(&marker);
+            }
+        }
+
+        let latest = self.plugin_dir.join(LATEST_LINK_NAME);
+        let _ = std::fs::remove_file(&latest);
+        #[cfg(unix)]
+        if let Some(target) = &self.previous_latest {
+            let _ = std::os::unix::fs::symlink(target, &latest);
+        }
+    }
+}
+
+/// Copy of an installed version dir, taken before the installer replaces it.
+struct VersionBackup {
+    original: PathBuf,
+    backup: PathBuf,
+}
+
+impl VersionBackup {
+    /// Copy `version` of the plugin in `plugin_dir` to `<root>/<id>/<version>`.
+    fn take(root: &std::path::Path, plugin_dir: &std::path::Path, version: &str) -> Result<Option<Self>> {
+        let original = plugin_dir.join(version);
+        if !original.is_dir() {
+            return Ok(None);
+        }
+        let id = plugin_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let backup = root.join(id).join(version);
+        tracing::trace!(from = %original.display(), to = %backup.display(), "Backing up plugin version");
+
+        if backup.exists() {
+            std::fs::remove_dir_all(&backup)?;
+        }
+        copy_dir_all(&original, &backup)?;
+        Ok(Some(Self { original, backup }))
+    }
+
+    /// Put the backed-up version back in place if the install removed it.
+    fn restore_alongside(self) -> Result<()> {
+        if !self.original.exists() {
+            tracing::trace!(dir = %self.original.display(), "Restoring previous plugin version");
+            copy_dir_all(&self.backup, &self.original)?;
+        }
+        self.discard()
+    }
+
+    fn discard(self) -> Result<()> {
+        std::fs::remove_dir_all(&self.backup)?;
+        Ok(())
+    }
+}
+
+impl Drop for VersionBackup {
+    /// An update cancelled mid-flight (Ctrl+C) never reaches
+    /// `restore_alongside`; put the old version back on the way out.
+    fn drop(&mut self) {
+        if !self.backup.exists() {
+            return;
+        }
+        if !self.original.exists() && copy_dir_all(&self.backup, &self.original).is_err() {
+            return;
+        }
+        let _ = std::fs::remove_dir_all(&self.backup);
+    }
+}
+
+/// Run `install` (a new version of the plugin in `plugin_dir`) so that a
+/// failure leaves `current` installed and runnable. The installer removes the
+/// old version dir before extracting, so it is backed up first and restored
+/// on error (the copy goes under `backup_root`); `PartialInstall` already
+/// resets `.version` and `latest`. With `keep_current`, the old version is
+/// kept next to the new one on success.
+async fn with_version_rollback(
+    backup_root: &std::path::Path,
+    plugin_dir: &std::path::Path,
+    current: &str,
+    keep_current: bool,
+    install: impl std::future::Future<Output = Result<()>>,
+) -> Result<()> {
+    let backup = VersionBackup::take(backup_root, plugin_dir, current)?;
+    let result = install.await;
+    if let Some(backup) = backup {
+        if result.is_err() {
+            tracing::trace!(dir = %plugin_dir.display(), version = %current, "Update failed, rolling back");
+        }
+        if result.is_err() || keep_current {
+            backup.restore_alongside()?;
+        } else {
+            backup.discard()?;
+        }
+    }
+    result
+}
+
+/// `*` (any run of characters) and `?` (one character) wildcard match, for
+/// pattern installs answered from the cached plugin list.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn is_tarball(path: &std::path::Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// Copy a local plugin build into `plugin_dir/<version>` (replacing an
+/// earlier copy of that version) and make it the active version, as the
+/// registry installer would: `.version` marker, `latest` link, and
+/// executable bits on the plugin's binaries.
+fn install_local_files(manifest_dir: &std::path::Path, plugin_dir: &std::path::Path, version: &str) -> Result<()> {
+    let version_dir = plugin_dir.join(version);
+    // Copy next to the target first so a failed copy leaves any existing version alone.
+    let staging = plugin_dir.join(format!(".{}.local-install", version));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    if let Err(e) = copy_dir_all(manifest_dir, &staging).and_then(|_| mark_executables(&staging)) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e.into());
+    }
+    if version_dir.exists() {
+        std::fs::remove_dir_all(&version_dir)?;
+    }
+    std::fs::rename(&staging, &version_dir)?;
+    tracing::trace!(dir = %version_dir.display(), "Copied local plugin files");
+
+    std::fs::write(plugin_dir.join(".version"), version)?;
+    let latest = plugin_dir.join(LATEST_LINK_NAME);
+    let _ = std::fs::remove_file(&latest);
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(version, &latest)?;
+    Ok(())
+}
+
+/// Make native libraries and anything under a `bin/` directory executable.
+#[cfg(unix)]
+fn mark_executables(dir: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            mark_executables(&path)?;
+            continue;
+        }
+        if !file_type.is_file() {
+            continue;
+        }
+        let is_library = matches!(path.extension().and_then(|e| e.to_str()), Some("so" | "dylib"));
+        let in_bin = dir.file_name().is_some_and(|name| name == "bin");
+        if is_library || in_bin {
+            let mut perms = std::fs::metadata(&path)?.permissions();
+            perms.set_mode(perms.mode() | 0o755);
+            std::fs::set_permissions(&path, perms)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn mark_executables(_dir: &std::path::Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+fn copy_dir_all(from: &std::path::Path, to: &std::path::Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &target)?;
+            #[cfg(not(unix))]
+            std::fs::copy(entry.path(), &target).map(|_| ())?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether anything answers HTTP at `url` within `timeout`. Any status
+/// counts; only connection failures and timeouts don't. Sends `token` so a
+/// private registry sees an authenticated request.
+async fn registry_reachable(url: &str, token: Option<&RegistryToken>, timeout: std::time::Duration) -> bool {
+    let client = match crate::clienv::http_client_builder().connect_timeout(timeout).timeout(timeout).build() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::trace!(error = %e, "Failed to build registry probe client, assuming reachable");
+            return true;
+        }
+    };
+    let mut request = client.head(url);
+    if let Some(token) = token {
+        request = request.header(reqwest::header::AUTHORIZATION, token.authorization_header());
+    }
+    match request.send().await {
+        Ok(response) => {
+            tracing::trace!(url = %url, status = %response.status(), "Registry reachable");
+            true
+        }
+        Err(e) => {
+            tracing::trace!(url = %url, error = %e, "Registry unreachable");
+            false
+        }
+    }
+}
+
+fn parse_optional_deps(manifest: &str) -> Vec<String> {
+    compatibility_list(manifest, "optional_deps")
+}
+
+fn parse_depends_on(manifest: &str) -> Vec<String> {
+    compatibility_list(manifest, "depends_on")
+}
+
+fn compatibility_list(manifest: &str, key: &str) -> Vec<String> {
+    let Ok(table) = manifest.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    table
+        .get("compatibility")
+        .and_then(|c| c.get(key))
+        .and_then(|d| d.as_array())
+        .map(|deps| deps.iter().filter_map(|d| d.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+/// A `depends_on` entry: a plugin id, optionally followed by a semver
+/// requirement (`"adi.indexer"`, `"adi.indexer >=2.1, <3.0"`, `"adi.core^1"`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DependencySpec {
+    id: String,
+    requirement: Option<semver::VersionReq>,
+}
+
+fn parse_dependency(entry: &str) -> Result<DependencySpec> {
+    let entry = entry.trim();
+    let split = entry
+        .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '=' | '^' | '~' | '*'))
+        .unwrap_or(entry.len());
+    let (id, requirement) = entry.split_at(split);
+    let requirement = requirement.trim();
+
+    let requirement = if requirement.is_empty() {
+        None
+    } else {
+        let parsed = semver::VersionReq::parse(requirement).map_err(|e| {
+            InstallerError::ConfigError(format!("invalid version requirement in dependency '{}': {}", entry, e))
+        })?;
+        Some(parsed)
+    };
+    Ok(DependencySpec {
+        id: id.to_string(),
+        requirement,
+    })
+}
+
+/// Whether `version` meets `requirement`; unparseable versions never do.
+fn satisfies(requirement: &semver::VersionReq, version: &str) -> bool {
+    semver::Version::parse(version.trim_start_matches('v')).is_ok_and(|v| requirement.matches(&v))
+}
+
+/// `latest`, the only version the registry offers, if upgrading an installed
+/// dependency to it meets `requirement` while keeping `others` (other
+/// plugins' requirements on it) met.
+fn upgrade_target(
+    requirement: &semver::VersionReq,
+    installed: &str,
+    latest: &str,
+    others: &[(String, semver::VersionReq)],
+) -> Option<String> {
+    let upgradable = is_older_version(installed, latest)
+        && satisfies(requirement, latest)
+        && others.iter().all(|(_, other)| satisfies(other, latest));
+    upgradable.then(|| latest.to_string())
+}
+
+fn is_older_version(candidate: &str, current: &str) -> bool {
+    let parse = |v: &str| semver::Version::parse(v.trim_start_matches('v')).ok();
+    match (parse(candidate), parse(current)) {
+        (Some(candidate), Some(current)) => candidate < current,
+        _ => false,
+    }
+}
+
+fn plugin_config(registry_url: &str) -> PluginConfig {
+    let mut config = PluginConfig::default().with_registry(registry_url);
+    config.plugins_dir = crate::clienv::plugins_dir();
+    config.cache_dir = crate::clienv::cache_dir();
+    config
+}
+
 fn create_progress_bar(size_bytes: u64) -> ProgressBar {
     let pb = ProgressBar::new(size_bytes);
     pb.set_style(
         ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+            .template("{spinner:.green} {prefix} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
             .unwrap()
             .progress_chars("#>-"),
     );
     pb
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_install_rolls_back_on_drop() {
+        let root = tempfile::tempdir().unwrap();
+        let plugin_dir = root.path().join("adi.test");
+        std::fs::create_dir_all(plugin_dir.join("1.0.0")).unwrap();
+        std::fs::write(plugin_dir.join(".version"), "1.0.0").unwrap();
+
+        let guard = PartialInstall::begin(plugin_dir.clone(), "2.0.0");
+        std::fs::create_dir_all(plugin_dir.join("2.0.0")).unwrap();
+        std::fs::write(plugin_dir.join(".version"), "2.0.0").unwrap();
+        drop(guard);
+
+        assert!(!plugin_dir.join("2.0.0").exists());
+        assert!(plugin_dir.join("1.0.0").exists());
+        assert_eq!(std::fs::read_to_string(plugin_dir.join(".version")).unwrap(), "1.0.0");
+    }
+
+    #[test]
+    fn test_parse_optional_deps() {
+        let manifest = r#"
+[plugin]
+id = "adi.example"
+
+[compatibility]
+depends_on = ["adi.core"]
+optional_deps = ["adi.heavy", "adi.extra"]
+"#;
+        assert_eq!(parse_optional_deps(manifest), vec!["adi.heavy", "adi.extra"]);
+        assert!(parse_optional_deps("[plugin]\nid = \"x\"\n").is_empty());
+    }
+
+    #[test]
+    fn test_is_older_version() {
+        assert!(is_older_version("1.2.0", "1.10.0"));
+        assert!(is_older_version("v1.0.0-beta.1", "1.0.0"));
+        assert!(!is_older_version("2.0.0", "1.9.9"));
+        assert!(!is_older_version("not-a-version", "1.0.0"));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("adi.lang.*", "adi.lang.rust"));
+        assert!(glob_matches("adi.*.rust", "adi.lang.rust"));
+        assert!(glob_matches("adi.hiv?", "adi.hive"));
+        assert!(!glob_matches("adi.lang.*", "adi.hive"));
+        assert!(!glob_matches("adi.hiv?", "adi.hives"));
+    }
+
+    #[test]
+    fn test_search_filter() {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let filter = SearchFilter {
+            plugin_type: Some("Translation".to_string()),
+            tags: strings(&["i18n", "ukrainian"]),
+        };
+        assert!(filter.matches(&strings(&["translation"]), &strings(&["ukrainian", "i18n", "core"])));
+        assert!(!filter.matches(&strings(&["translation"]), &strings(&["i18n"])));
+        assert!(!filter.matches(&[], &strings(&["i18n", "ukrainian"])));
+        assert_eq!(filter.to_string(), "type=Translation, tag=i18n, tag=ukrainian");
+
+        assert!(SearchFilter::default().is_empty());
+        assert!(SearchFilter::default().matches(&[], &[]));
+    }
+
+    #[tokio::test]
+    async fn test_registry_reachable() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let timeout = std::time::Duration::from_millis(500);
+        assert!(!registry_reachable(&url, None, timeout).await);
+    }
+
+    #[tokio::test]
+    async fn test_registry_probe_sends_token() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            use std::io::{Read, Write};
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4096];
+            let len = stream.read(&mut request).unwrap();
+            stream.write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n").unwrap();
+            String::from_utf8_lossy(&request[..len]).to_lowercase()
+        });
+
+        let token = RegistryToken::new("s3cret");
+        assert!(registry_reachable(&url, Some(&token), std::time::Duration::from_secs(5)).await);
+        assert!(server.join().unwrap().contains("authorization: bearer s3cret"));
+    }
+
+    #[test]
+    fn test_dry_run_plan_records_each_plugin_once() {
+        let manager = PluginManager::with_registry_url("http://127.0.0.1:9");
+        manager.plan_satisfied("adi.hive", "1.0.0");
+        assert!(manager.take_plan().is_empty());
+
+        let manager = manager.with_dry_run(true);
+        manager.plan_satisfied("adi.hive", "1.0.0");
+        manager.plan_satisfied("adi.tasks", "0.3.0");
+        manager.plan_satisfied("adi.hive", "1.0.0");
+        let plan = manager.take_plan();
+        assert_eq!(plan.iter().map(|step| step.id.as_str()).collect::<Vec<_>>(), ["adi.hive", "adi.tasks"]);
+        assert!(plan.iter().all(|step| step.action == PlanAction::Satisfied && step.size_bytes.is_none()));
+        assert!(manager.take_plan().is_empty());
+    }
+
+    #[test]
+    fn test_partial_install_commit_keeps_files() {
+        let root = tempfile::tempdir().unwrap();
+        let plugin_dir = root.path().join("adi.test");
+
+        let guard = PartialInstall::begin(plugin_dir.clone(), "1.0.0");
+        std::fs::create_dir_all(plugin_dir.join("1.0.0")).unwrap();
+        guard.commit();
+
+        assert!(plugin_dir.join("1.0.0").exists());
+    }
+
+    #[tokio::test]
+    async fn test_failed_update_keeps_previous_version() {
+        let root = tempfile::tempdir().unwrap();
+        let plugin_dir = root.path().join("plugins").join("adi.test");
+        std::fs::create_dir_all(plugin_dir.join("1.0.0")).unwrap();
+        std::fs::write(plugin_dir.join("1.0.0").join("plugin.so"), "v1").unwrap();
+        std::fs::write(plugin_dir.join(".version"), "1.0.0").unwrap();
+
+        // Mimics the installer: drop the old version, then fail mid-download.
+        let failing_install = async {
+            let guard = PartialInstall::begin(plugin_dir.clone(), "2.0.0");
+            std::fs::remove_dir_all(plugin_dir.join("1.0.0")).unwrap();
+            std::fs::create_dir_all(plugin_dir.join("2.0.0")).unwrap();
+            std::fs::write(plugin_dir.join(".version"), "2.0.0").unwrap();
+            drop(guard);
+            Err(InstallerError::Other("connection reset".to_string()))
+        };
+        let result = with_version_rollback(&root.path().join("rollback"), &plugin_dir, "1.0.0", false, failing_install).await;
+
+        assert!(result.is_err());
+        assert!(!plugin_dir.join("2.0.0").exists());
+        assert_eq!(std::fs::read_to_string(plugin_dir.join("1.0.0").join("plugin.so")).unwrap(), "v1");
+        assert_eq!(std::fs::read_to_string(plugin_dir.join(".version")).unwrap(), "1.0.0");
+    }
+
+    #[test]
+    fn test_parse_dependency_requirements() {
+        let bare = parse_dependency("adi.core").unwrap();
+        assert_eq!(bare.id, "adi.core");
+        assert!(bare.requirement.is_none());
+
+        let ranged = parse_dependency("adi.indexer >=2.1, <3.0").unwrap();
+        assert_eq!(ranged.id, "adi.indexer");
+        let req = ranged.requirement.unwrap();
+        assert!(satisfies(&req, "2.4.0"));
+        assert!(!satisfies(&req, "3.0.0"));
+        assert!(!satisfies(&req, "2.0.9"));
+
+        assert_eq!(parse_dependency("adi.core^1").unwrap().requirement.unwrap().to_string(), "^1");
+        assert!(parse_dependency("adi.core >=banana").is_err());
+    }
+
+    #[test]
+    fn test_outdated_dependency_is_upgraded() {
+        // adi.a depends on `adi.b >=2.0` while adi.b 1.5.0 is installed.
+        let dep = parse_dependency("adi.b >=2.0").unwrap();
+        let requirement = dep.requirement.unwrap();
+        assert!(!satisfies(&requirement, "1.5.0"));
+        assert_eq!(upgrade_target(&requirement, "1.5.0", "2.1.0", &[]).as_deref(), Some("2.1.0"));
+
+        // Not if the upgrade breaks another plugin, or the registry has nothing newer that fits.
+        let held = [("adi.c".to_string(), semver::VersionReq::parse("<2").unwrap())];
+        assert_eq!(upgrade_target(&requirement, "1.5.0", "2.1.0", &held), None);
+        assert_eq!(upgrade_target(&requirement, "1.5.0", "1.9.0", &[]), None);
+        let below_2 = semver::VersionReq::parse("<2").unwrap();
+        assert_eq!(upgrade_target(&below_2, "2.5.0", "2.6.0", &[]), None);
+    }
+
+    /// Releases unpacked from local build dirs, as a registry would serve them.
+    struct LocalSource {
+        releases: HashMap<String, (String, PathBuf)>,
+    }
+
+    impl PluginSource for LocalSource {
+        fn release<'a>(&'a self, id: &'a str, _platform: &'a str) -> BoxFuture<'a, Result<Option<(String, u64)>>> {
+            Box::pin(async move { Ok(self.releases.get(id).map(|(version, _)| (version.clone(), 1024))) })
+        }
+
+        fn fetch<'a>(&'a self, id: &'a str, version: Option<&'a str>, plugin_dir: &'a std::path::Path) -> BoxFuture<'a, Result<FetchedRelease>> {
+            Box::pin(async move {
+                let (latest, build) = self.releases.get(id).ok_or_else(|| InstallerError::PluginNotFound { id: id.to_string() })?;
+                let version = version.unwrap_or(latest);
+                std::fs::create_dir_all(plugin_dir)?;
+                install_local_files(build, plugin_dir, version)?;
+                Ok(FetchedRelease {
+                    version: version.to_string(),
+                    path: plugin_dir.join(version),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_install_upgrades_outdated_installed_dependency() {
+        // adi.a needs `adi.b >=2.0`, adi.b 1.5.0 is installed and the source has 2.1.0.
+        let root = tempfile::tempdir().unwrap();
+        let registry_url = "http://127.0.0.1:9";
+        let mut config = PluginConfig::default().with_registry(registry_url);
+        config.plugins_dir = root.path().join("plugins");
+        config.cache_dir = root.path().join("cache");
+        let build = |name: &str, manifest: &str| {
+            let dir = root.path().join("build").join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("plugin.toml"), manifest).unwrap();
+            dir
+        };
+        let a = build("adi.a", "[plugin]\nid = \"adi.a\"\n\n[compatibility]\ndepends_on = [\"adi.b >=2.0\"]\n");
+        let b_old = build("adi.b-1.5.0", "[plugin]\nid = \"adi.b\"\n");
+        let b_new = build("adi.b-2.1.0", "[plugin]\nid = \"adi.b\"\n# 2.1.0\n");
+        for (id, version, dir) in [("adi.a", "1.0.0", &a), ("adi.b", "1.5.0", &b_old)] {
+            let plugin_dir = config.plugins_dir.join(id);
+            std::fs::create_dir_all(&plugin_dir).unwrap();
+            install_local_files(dir, &plugin_dir, version).unwrap();
+        }
+        let source = Arc::new(LocalSource {
+            releases: HashMap::from([("adi.b".to_string(), ("2.1.0".to_string(), b_new))]),
+        });
+        let manager = || PluginManager::from_config(registry_url.to_string(), &config).with_source(source.clone());
+
+        let dry_run = manager().with_dry_run(true);
+        dry_run.install_recursive("adi.a", None, &mut HashSet::new()).await.unwrap();
+        let plan = dry_run.take_plan();
+        assert_eq!(plan.len(), 2);
+        assert_eq!((plan[0].id.as_str(), &plan[0].action), ("adi.a", &PlanAction::Satisfied));
+        assert_eq!((plan[1].id.as_str(), plan[1].version.as_str()), ("adi.b", "2.1.0"));
+        assert_eq!(plan[1].action, PlanAction::Upgrade { from: "1.5.0".to_string() });
+        assert_eq!(plan[1].size_bytes, Some(1024));
+        assert_eq!(dry_run.installer.is_installed("adi.b").as_deref(), Some("1.5.0"));
+
+        let manager = manager();
+        manager.install_recursive("adi.a", None, &mut HashSet::new()).await.unwrap();
+        assert_eq!(manager.installer.is_installed("adi.b").as_deref(), Some("2.1.0"));
+        assert!(manager.installed_manifest("adi.b").unwrap().contains("# 2.1.0"));
+    }
+
+    #[test]
+    fn test_parse_depends_on() {
+        let manifest = "[compatibility]\ndepends_on = [\"adi.core\", \"adi.indexer >=2.1, <3.0\"]\n";
+        assert_eq!(parse_depends_on(manifest), vec!["adi.core", "adi.indexer >=2.1, <3.0"]);
+    }
+
+    #[test]
+    fn test_install_local_files_matches_registry_layout() {
+        let root = tempfile::tempdir().unwrap();
+        let source = root.path().join("build");
+        std::fs::create_dir_all(source.join("bin")).unwrap();
+        std::fs::write(source.join("plugin.toml"), "[plugin]\nid = \"adi.dev\"\n").unwrap();
+        std::fs::write(source.join("bin").join("adi-dev"), "#!/bin/sh\n").unwrap();
+        let plugin_dir = root.path().join("plugins").join("adi.dev");
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+
+        install_local_files(&source, &plugin_dir, "0.1.0").unwrap();
+        std::fs::write(source.join("plugin.toml"), "[plugin]\nid = \"adi.dev\"\n# rebuilt\n").unwrap();
+        install_local_files(&source, &plugin_dir, "0.1.0").unwrap();
+
+        assert_eq!(std::fs::read_to_string(plugin_dir.join(".version")).unwrap(), "0.1.0");
+        let manifest = std::fs::read_to_string(plugin_dir.join("0.1.0").join("plugin.toml")).unwrap();
+        assert!(manifest.contains("# rebuilt"));
+        assert!(!plugin_dir.join(".0.1.0.local-install").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::read_link(plugin_dir.join(LATEST_LINK_NAME)).unwrap(), PathBuf::from("0.1.0"));
+            let mode = std::fs::metadata(plugin_dir.join("0.1.0").join("bin").join("adi-dev")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+    }
+}
@@ -1,10 +1,17 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use lib_plugin_host::{LoadedPluginV3, PluginManagerV3};
 use lib_plugin_manifest::PluginManifest;
 
-use crate::error::Result;
+use lib_console_output::theme;
+use lib_i18n_core::{t, LocalizedError};
+
+use crate::error::{InstallerError, Result};
+use crate::plugin_health;
+use crate::tn;
 
 /// Discovered from plugin.toml manifests without loading binaries.
 #[derive(Debug, Clone)]
@@ -15,6 +22,70 @@ pub struct PluginCliCommand {
     pub aliases: Vec<String>,
 }
 
+/// Context keys the CLI fills in itself. Any other top-level key in the
+/// context JSON is passed to the plugin as an option.
+pub const RESERVED_CONTEXT_KEYS: &[&str] = &["command", "args", "cwd", "options"];
+
+/// A plugin found in the plugins dir by [`PluginRuntime::list_installed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledPlugin {
+    pub id: String,
+    /// Active version from `.version`; `None` for a linked dev checkout or
+    /// an install whose `.version` is missing
+    pub version: Option<String>,
+    /// False once auto-disabled after repeated load failures
+    pub enabled: bool,
+    /// Directory of the active version (the plugin dir itself without one)
+    pub path: PathBuf,
+}
+
+/// A plugin that was skipped by `load_all_plugins`.
+#[derive(Debug, Clone)]
+pub struct PluginLoadFailure {
+    pub plugin_id: String,
+    pub error: String,
+}
+
+/// Print a summary of plugins that failed to load to stderr (no-op if none).
+pub fn print_load_failures(failures: &[PluginLoadFailure]) {
+    if failures.is_empty() {
+        return;
+    }
+    eprintln!(
+        "{} {}",
+        theme::icons::WARNING,
+        theme::warning(tn!("plugin-load-failed-summary", failures.len()))
+    );
+    for failure in failures {
+        eprintln!("  - {}: {}", theme::bold(&failure.plugin_id), failure.error);
+    }
+    eprintln!("  {}", theme::muted(t!("plugin-load-failed-hint")));
+}
+
+/// Result of `adi plugin test`.
+#[derive(Debug, Clone)]
+pub enum SelfTestOutcome {
+    /// The plugin's `selftest` CLI command ran; a non-zero exit code is a failure.
+    Ran {
+        exit_code: i32,
+        stdout: String,
+        stderr: String,
+    },
+    /// No self-test declared: the plugin loaded and listed this many CLI commands.
+    LoadOnly { commands: usize },
+}
+
+impl SelfTestOutcome {
+    pub fn passed(&self) -> bool {
+        match self {
+            Self::Ran { exit_code, .. } => *exit_code == 0,
+            Self::LoadOnly { .. } => true,
+        }
+    }
+}
+
+pub const DEFAULT_INVOKE_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Debug, Clone)]
 pub struct RuntimeConfig {
     pub plugins_dir: PathBuf,
@@ -22,16 +93,19 @@ pub struct RuntimeConfig {
     pub registry_url: Option<String>,
     pub require_signatures: bool,
     pub host_version: String,
+    /// Limit on a single call into a plugin; `None` waits forever.
+    pub invoke_timeout: Option<Duration>,
 }
 
 impl Default for RuntimeConfig {
     fn default() -> Self {
         Self {
-            plugins_dir: lib_plugin_host::PluginConfig::default_plugins_dir(),
-            cache_dir: lib_plugin_host::PluginConfig::default_cache_dir(),
+            plugins_dir: crate::clienv::plugins_dir(),
+            cache_dir: crate::clienv::cache_dir(),
             registry_url: crate::clienv::registry_url_override(),
             require_signatures: false,
             host_version: env!("CARGO_PKG_VERSION").to_string(),
+            invoke_timeout: crate::clienv::plugin_invoke_timeout(),
         }
     }
 }
@@ -67,43 +141,98 @@ impl PluginRuntime {
         &self.config
     }
 
-    pub async fn load_all_plugins(&self) -> Result<()> {
+    /// Load every installed plugin. A plugin that fails to load is skipped and
+    /// reported in the returned list; the rest stay usable.
+    pub async fn load_all_plugins(&self) -> Result<Vec<PluginLoadFailure>> {
         let plugins_dir = &self.config.plugins_dir;
         if !plugins_dir.exists() {
             tracing::trace!(dir = %plugins_dir.display(), "Plugins directory does not exist, skipping load");
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         tracing::trace!(dir = %plugins_dir.display(), "Scanning plugins directory");
 
+        let scan = scan_plugin_dirs(plugins_dir);
         let mut plugin_ids = Vec::new();
-        if let Ok(entries) = std::fs::read_dir(plugins_dir) {
-            for entry in entries.flatten() {
-                let plugin_dir = entry.path();
-                if plugin_dir.is_dir() {
-                    if entry.file_name() == lib_plugin_host::command_index::COMMANDS_DIR_NAME {
-                        continue;
-                    }
-                    if let Some(name) = plugin_dir.file_name() {
-                        plugin_ids.push(name.to_string_lossy().to_string());
-                    }
-                }
+        for plugin_dir in scan.dirs {
+            if plugin_health::is_disabled(&plugin_dir) {
+                tracing::trace!(dir = %plugin_dir.display(), "Skipping disabled plugin");
+                continue;
+            }
+            if let Some(name) = plugin_dir.file_name() {
+                plugin_ids.push(name.to_string_lossy().to_string());
             }
         }
 
         tracing::trace!(count = plugin_ids.len(), "Discovered plugin directories");
 
+        let mut failures: Vec<PluginLoadFailure> = scan
+            .skipped
+            .into_iter()
+            .map(|skipped| PluginLoadFailure {
+                plugin_id: skipped.name,
+                error: skipped.reason,
+            })
+            .collect();
         for plugin_id in plugin_ids {
             tracing::trace!(plugin_id = %plugin_id, "Loading plugin");
             if let Err(e) = self.load_plugin_internal(&plugin_id).await {
                 tracing::warn!("Failed to enable plugin {}: {}", plugin_id, e);
+                failures.push(PluginLoadFailure {
+                    plugin_id,
+                    error: e.localized(),
+                });
             }
         }
 
-        Ok(())
+        failures.sort_by(|a, b| a.plugin_id.cmp(&b.plugin_id));
+        Ok(failures)
     }
 
     async fn load_plugin_internal(&self, plugin_id: &str) -> Result<()> {
+        if plugin_health::is_disabled(&self.config.plugins_dir.join(plugin_id)) {
+            return Err(crate::error::InstallerError::PluginDisabled {
+                id: plugin_id.to_string(),
+            });
+        }
+        self.load_and_track(plugin_id).await
+    }
+
+    /// Load a plugin and update its consecutive-failure count, disabling it
+    /// once the limit is reached.
+    async fn load_and_track(&self, plugin_id: &str) -> Result<()> {
+        let plugin_dir = self.config.plugins_dir.join(plugin_id);
+        let result = self.load_manifest_and_plugin(plugin_id).await;
+        match &result {
+            Ok(()) => plugin_health::record_load_success(&plugin_dir),
+            Err(crate::error::InstallerError::PluginNotFound { .. }) => {}
+            Err(e) => {
+                if plugin_health::record_load_failure(&plugin_dir, &e.localized()) {
+                    tracing::warn!("Disabled plugin {} after repeated load failures", plugin_id);
+                    eprintln!(
+                        "{} {}",
+                        theme::icons::WARNING,
+                        theme::warning(t!(
+                            "plugin-load-auto-disabled",
+                            "id" => plugin_id,
+                            "count" => &plugin_health::MAX_CONSECUTIVE_LOAD_FAILURES.to_string()
+                        ))
+                    );
+                }
+            }
+        }
+        result
+    }
+
+    /// Load a plugin even if it was auto-disabled, re-enabling it on success.
+    pub async fn revive_plugin(&self, plugin_id: &str) -> Result<()> {
+        tracing::trace!(plugin_id = %plugin_id, "Reviving plugin");
+        self.load_and_track(plugin_id).await?;
+        plugin_health::enable(&self.config.plugins_dir.join(plugin_id))?;
+        Ok(())
+    }
+
+    async fn load_manifest_and_plugin(&self, plugin_id: &str) -> Result<()> {
         tracing::trace!(plugin_id = %plugin_id, "Finding plugin manifest");
 
         let manifest = self.find_plugin_manifest(plugin_id)?;
@@ -184,19 +313,20 @@ impl PluginRuntime {
         self.load_plugin_internal(plugin_id).await
     }
 
-    pub fn list_installed(&self) -> Vec<String> {
-        self.manager_v3
-            .read()
-            .expect("plugin manager lock poisoned")
-            .list_plugins()
-            .into_iter()
-            .map(|p| p.id)
-            .collect()
+    /// Plugins installed in the plugins dir, loaded or not, sorted by id
+    pub fn list_installed(&self) -> Vec<InstalledPlugin> {
+        installed_plugins(&self.config.plugins_dir)
+    }
+
+    /// Ids of installed plugins, sorted
+    pub fn list_installed_ids(&self) -> Vec<String> {
+        self.list_installed().into_iter().map(|p| p.id).collect()
     }
 
+    /// Loaded plugins with a CLI interface as (id, description), sorted by id
     pub fn list_runnable_plugins(&self) -> Vec<(String, String)> {
         let manager = self.manager_v3.read().expect("plugin manager lock poisoned");
-        manager
+        let mut runnable: Vec<(String, String)> = manager
             .all_cli_commands()
             .into_iter()
             .map(|(id, _)| {
@@ -206,7 +336,22 @@ impl PluginRuntime {
                     .unwrap_or_default();
                 (id, description)
             })
-            .collect()
+            .collect();
+        runnable.sort_by(|a, b| a.0.cmp(&b.0));
+        runnable
+    }
+
+    /// Runnable plugins read from manifests only. No plugin binary is loaded,
+    /// so listing is fast and a broken plugin can't take it down.
+    pub fn list_runnable_from_manifests(&self) -> Vec<(String, String)> {
+        let mut runnable: Vec<(String, String)> = self
+            .discover_cli_commands()
+            .into_iter()
+            .map(|cmd| (cmd.plugin_id, cmd.description))
+            .collect();
+        runnable.sort_by(|a, b| a.0.cmp(&b.0));
+        runnable.dedup_by(|a, b| a.0 == b.0);
+        runnable
     }
 
     pub fn get_log_provider(&self, plugin_id: &str) -> Option<std::sync::Arc<dyn lib_plugin_abi_v3::logs::LogProvider>> {
@@ -229,12 +374,15 @@ impl PluginRuntime {
                 })?
         };
 
-        let ctx = self.parse_cli_context(context_json)?;
+        let mut ctx = self.parse_cli_context(context_json)?;
+        if let Some(binary) = crate::system_binary::recorded(&self.config.plugins_dir.join(plugin_id)) {
+            ctx.env.insert(crate::system_binary::ENV_VAR.to_string(), binary.display().to_string());
+        }
         tracing::trace!(plugin_id = %plugin_id, command = %ctx.command, subcommand = ?ctx.subcommand, args = ?ctx.args, "Dispatching command to plugin");
 
-        let result = plugin
-            .run_command(&ctx)
-            .await
+        let handle = tokio::runtime::Handle::current();
+        let result = with_timeout(plugin_id, self.config.invoke_timeout, move || handle.block_on(plugin.run_command(&ctx)))
+            .await?
             .map_err(|e| crate::error::InstallerError::Other(e.to_string()))?;
 
         tracing::trace!(plugin_id = %plugin_id, exit_code = result.exit_code, "Plugin command completed");
@@ -247,6 +395,47 @@ impl PluginRuntime {
         .expect("JSON serialization cannot fail for known structure"))
     }
 
+    /// Run a loaded plugin's self-test. Plugins declaring `selftest = true`
+    /// under `[cli]` in plugin.toml get `<command> selftest` invoked; others
+    /// only have to list their commands without error.
+    pub async fn self_test(&self, plugin_id: &str) -> Result<SelfTestOutcome> {
+        let manifest_path = Self::find_plugin_toml_path(&self.config.plugins_dir.join(plugin_id));
+        let declared = manifest_path
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .is_some_and(|content| declares_selftest(&content));
+        tracing::trace!(plugin_id = %plugin_id, declared = declared, "Running plugin self-test");
+
+        let plugin = self.manager_v3.read().expect("plugin manager lock poisoned").get_cli_commands(plugin_id);
+        let Some(plugin) = plugin else {
+            if declared {
+                return Err(crate::error::InstallerError::Other(format!(
+                    "{} declares a self-test but has no CLI interface",
+                    plugin_id
+                )));
+            }
+            return Ok(SelfTestOutcome::LoadOnly { commands: 0 });
+        };
+
+        if !declared {
+            let commands = plugin.list_commands().await;
+            return Ok(SelfTestOutcome::LoadOnly {
+                commands: commands.len(),
+            });
+        }
+
+        let context = serde_json::json!({ "command": plugin_id, "args": ["selftest"] });
+        let ctx = self.parse_cli_context(&context.to_string())?;
+        let handle = tokio::runtime::Handle::current();
+        let result = with_timeout(plugin_id, self.config.invoke_timeout, move || handle.block_on(plugin.run_command(&ctx)))
+            .await?
+            .map_err(|e| crate::error::InstallerError::Other(e.to_string()))?;
+        Ok(SelfTestOutcome::Ran {
+            exit_code: result.exit_code,
+            stdout: result.stdout,
+            stderr: result.stderr,
+        })
+    }
+
     pub async fn list_cli_commands(&self, plugin_id: &str) -> Result<String> {
         let plugin = {
             let manager = self.manager_v3.read().expect("plugin manager lock poisoned");
@@ -274,7 +463,8 @@ impl PluginRuntime {
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
 
         let subcommand = args.first().cloned();
-        let mut options = Self::parse_json_options(&value);
+        let mut options = Self::parse_extra_context(&value);
+        options.extend(Self::parse_json_options(&value));
         let remaining_args: Vec<String> = args.into_iter().skip(1).collect();
         let positional_args = Self::split_args_and_flags(&remaining_args, &mut options);
 
@@ -312,6 +502,18 @@ impl PluginRuntime {
             .unwrap_or_default()
     }
 
+    fn parse_extra_context(value: &serde_json::Value) -> std::collections::HashMap<String, serde_json::Value> {
+        value
+            .as_object()
+            .map(|obj| {
+                obj.iter()
+                    .filter(|(k, _)| !RESERVED_CONTEXT_KEYS.contains(&k.as_str()))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     fn split_args_and_flags(
         args: &[String],
         options: &mut std::collections::HashMap<String, serde_json::Value>,
@@ -335,7 +537,14 @@ impl PluginRuntime {
         positional
     }
 
+    /// CLI commands declared by installed manifests, sorted by command name
     pub fn discover_cli_commands(&self) -> Vec<PluginCliCommand> {
+        let mut commands = self.discover_cli_commands_unsorted();
+        commands.sort_by(|a, b| a.command.cmp(&b.command).then_with(|| a.plugin_id.cmp(&b.plugin_id)));
+        commands
+    }
+
+    fn discover_cli_commands_unsorted(&self) -> Vec<PluginCliCommand> {
         tracing::trace!("Discovering CLI commands");
 
         let plugins_dir = &self.config.plugins_dir;
@@ -393,29 +602,18 @@ impl PluginRuntime {
         let mut commands = Vec::new();
         let plugins_dir = &self.config.plugins_dir;
 
-        if let Ok(entries) = std::fs::read_dir(plugins_dir) {
-            for entry in entries.flatten() {
-                let plugin_dir = entry.path();
-                if !plugin_dir.is_dir() {
-                    continue;
-                }
-
-                if entry.file_name() == lib_plugin_host::command_index::COMMANDS_DIR_NAME {
-                    continue;
-                }
-
-                let manifest_path = Self::find_plugin_toml_path(&plugin_dir);
-                if let Some(manifest_path) = manifest_path {
-                    if let Ok(manifest) = PluginManifest::from_file(&manifest_path) {
-                        if let Some(cli) = &manifest.cli {
-                            tracing::trace!(command = %cli.command, plugin_id = %manifest.plugin.id, aliases = ?cli.aliases, "Discovered CLI command");
-                            commands.push(PluginCliCommand {
-                                command: cli.command.clone(),
-                                plugin_id: manifest.plugin.id.clone(),
-                                description: cli.description.clone(),
-                                aliases: cli.aliases.clone(),
-                            });
-                        }
+        for plugin_dir in scan_plugin_dirs(plugins_dir).dirs {
+            let manifest_path = Self::find_plugin_toml_path(&plugin_dir);
+            if let Some(manifest_path) = manifest_path {
+                if let Ok(manifest) = PluginManifest::from_file(&manifest_path) {
+                    if let Some(cli) = &manifest.cli {
+                        tracing::trace!(command = %cli.command, plugin_id = %manifest.plugin.id, aliases = ?cli.aliases, "Discovered CLI command");
+                        commands.push(PluginCliCommand {
+                            command: cli.command.clone(),
+                            plugin_id: manifest.plugin.id.clone(),
+                            description: cli.description.clone(),
+                            aliases: cli.aliases.clone(),
+                        });
                     }
                 }
             }
@@ -484,16 +682,11 @@ pub(crate) fn find_plugin_toml_path(plugin_dir: &std::path::Path) -> Option<Path
         return Some(direct_manifest);
     }
 
-    if let Ok(entries) = std::fs::read_dir(plugin_dir) {
-        for entry in entries.flatten() {
-            let subdir = entry.path();
-            if subdir.is_dir() {
-                let manifest = subdir.join("plugin.toml");
-                if manifest.exists() {
-                    tracing::trace!(path = %manifest.display(), "Found plugin.toml in subdirectory");
-                    return Some(manifest);
-                }
-            }
+    for subdir in plugin_subdirs(plugin_dir) {
+        let manifest = subdir.join("plugin.toml");
+        if manifest.exists() {
+            tracing::trace!(path = %manifest.display(), "Found plugin.toml in subdirectory");
+            return Some(manifest);
         }
     }
 
@@ -501,6 +694,160 @@ pub(crate) fn find_plugin_toml_path(plugin_dir: &std::path::Path) -> Option<Path
     None
 }
 
+/// Result of [`scan_plugin_dirs`].
+#[derive(Debug, Default)]
+pub struct PluginDirScan {
+    /// Plugin directories as they appear under the plugins dir (a symlinked
+    /// plugin keeps its link path, so its id is the link name).
+    pub dirs: Vec<PathBuf>,
+    pub skipped: Vec<SkippedPluginDir>,
+}
+
+/// An entry of the plugins dir that can't be used as a plugin directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedPluginDir {
+    pub name: String,
+    pub reason: String,
+}
+
+/// Plugin directories under `plugins_dir`, sorted by name.
+///
+/// Symlinked plugin dirs (local dev installs) are followed. Broken links,
+/// symlink cycles, links back into the plugins dir and second links to an
+/// already listed directory are skipped and reported instead.
+pub fn scan_plugin_dirs(plugins_dir: &Path) -> PluginDirScan {
+    let mut scan = PluginDirScan::default();
+    let Ok(entries) = std::fs::read_dir(plugins_dir) else {
+        return scan;
+    };
+    let root = std::fs::canonicalize(plugins_dir).unwrap_or_else(|_| plugins_dir.to_path_buf());
+
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    let mut seen = HashSet::new();
+    for entry in entries {
+        if entry.file_name() == lib_plugin_host::command_index::COMMANDS_DIR_NAME {
+            continue;
+        }
+        let path = entry.path();
+        let is_symlink = entry.file_type().map(|t| t.is_symlink()).unwrap_or(false);
+        let reason = match std::fs::canonicalize(&path) {
+            Err(e) => format!("unresolvable symlink: {}", e),
+            Ok(target) if !target.is_dir() => continue,
+            Ok(target) if root.starts_with(&target) => {
+                format!("symlink cycle: points back at {}", target.display())
+            }
+            Ok(target) if !seen.insert(target.clone()) => {
+                format!("another entry already links to {}", target.display())
+            }
+            Ok(target) => {
+                if is_symlink {
+                    tracing::trace!(link = %path.display(), target = %target.display(), "Following symlinked plugin directory");
+                }
+                scan.dirs.push(path);
+                continue;
+            }
+        };
+        tracing::warn!(entry = %path.display(), reason = %reason, "Skipping plugins directory entry");
+        scan.skipped.push(SkippedPluginDir {
+            name: entry.file_name().to_string_lossy().to_string(),
+            reason,
+        });
+    }
+    scan
+}
+
+/// Every plugin dir [`scan_plugin_dirs`] finds, with its active version.
+pub fn installed_plugins(plugins_dir: &Path) -> Vec<InstalledPlugin> {
+    scan_plugin_dirs(plugins_dir)
+        .dirs
+        .into_iter()
+        .filter_map(|plugin_dir| {
+            let id = plugin_dir.file_name()?.to_string_lossy().to_string();
+            let version = std::fs::read_to_string(plugin_dir.join(".version"))
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty() && plugin_dir.join(v).is_dir());
+            let path = match &version {
+                Some(version) => plugin_dir.join(version),
+                None => plugin_dir.clone(),
+            };
+            Some(InstalledPlugin {
+                id,
+                version,
+                enabled: !plugin_health::is_disabled(&plugin_dir),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Subdirectories of an installed plugin (version dirs), following
+/// symlinks but skipping any that resolve to the plugin dir itself or one of
+/// its parents, or that don't resolve at all.
+pub fn plugin_subdirs(plugin_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(plugin_dir) else {
+        return Vec::new();
+    };
+    let Ok(root) = std::fs::canonicalize(plugin_dir) else {
+        return Vec::new();
+    };
+
+    let mut subdirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|subdir| match std::fs::canonicalize(subdir) {
+            Ok(target) if root.starts_with(&target) => {
+                tracing::trace!(dir = %subdir.display(), "Skipping subdirectory that loops back to the plugin dir");
+                false
+            }
+            Ok(target) => target.is_dir(),
+            Err(_) => false,
+        })
+        .collect();
+    subdirs.sort();
+    subdirs
+}
+
+/// Run a call into `plugin_id` on a thread of its own, failing with
+/// [`InstallerError::PluginTimeout`] once `timeout` passes. Plugins are native
+/// code that may block their thread outright, so the call can't share the
+/// runtime's; on expiry the thread is left behind and dies with the process.
+async fn with_timeout<T: Send + 'static>(
+    plugin_id: &str,
+    timeout: Option<Duration>,
+    call: impl FnOnce() -> T + Send + 'static,
+) -> Result<T> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    std::thread::Builder::new()
+        .name(format!("plugin {}", plugin_id))
+        .spawn(move || {
+            let _ = tx.send(call());
+        })?;
+    let output = match timeout {
+        None => rx.await,
+        Some(timeout) => tokio::time::timeout(timeout, rx).await.map_err(|_| {
+            tracing::trace!(plugin_id = %plugin_id, timeout = ?timeout, "Plugin call timed out");
+            InstallerError::PluginTimeout {
+                plugin: plugin_id.to_string(),
+                ms: timeout.as_millis() as u64,
+            }
+        })?,
+    };
+    // The sender only goes away without a value if the call panicked.
+    output.map_err(|_| InstallerError::Other(format!("plugin {} panicked", plugin_id)))
+}
+
+/// Whether plugin.toml opts into `adi plugin test` via `[cli] selftest = true`.
+fn declares_selftest(manifest: &str) -> bool {
+    manifest
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|table| table.get("cli")?.get("selftest")?.as_bool())
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -513,9 +860,105 @@ mod tests {
             registry_url: None,
             require_signatures: false,
             host_version: "0.1.0".to_string(),
+            invoke_timeout: Some(DEFAULT_INVOKE_TIMEOUT),
         };
 
         let runtime = PluginRuntime::new(config).await;
         assert!(runtime.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_with_timeout() {
+        let ready = with_timeout("adi.hive", Some(Duration::from_millis(50)), || 7).await;
+        assert_eq!(ready.unwrap(), 7);
+
+        // Blocks its thread without ever yielding.
+        let hung = with_timeout("adi.hive", Some(Duration::from_millis(50)), || std::thread::sleep(Duration::from_secs(30))).await;
+        assert!(matches!(hung, Err(InstallerError::PluginTimeout { ref plugin, ms: 50 }) if plugin == "adi.hive"));
+
+        assert_eq!(with_timeout("adi.hive", None, || 7).await.unwrap(), 7);
+        let panicked = with_timeout("adi.hive", None, || -> i32 { panic!("plugin bug") }).await;
+        assert!(matches!(panicked, Err(InstallerError::Other(_))));
+    }
+
+    #[test]
+    fn test_extra_context_keys_become_options() {
+        let value = serde_json::json!({
+            "command": "adi.hive",
+            "args": [],
+            "session_id": "abc",
+            "options": { "verbose": true },
+        });
+        let options = PluginRuntime::parse_extra_context(&value);
+        assert_eq!(options.len(), 1);
+        assert_eq!(options["session_id"], "abc");
+    }
+
+    #[test]
+    fn test_declares_selftest() {
+        assert!(declares_selftest("[cli]\ncommand = \"hive\"\nselftest = true\n"));
+        assert!(!declares_selftest("[cli]\ncommand = \"hive\"\n"));
+        assert!(!declares_selftest("not toml ["));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_terminates_on_symlink_cycles() {
+        use std::os::unix::fs::symlink;
+
+        let root = tempfile::tempdir().unwrap();
+        let plugins_dir = root.path().join("plugins");
+        let installed = plugins_dir.join("adi.hive");
+        std::fs::create_dir_all(installed.join("1.0.0")).unwrap();
+        std::fs::write(installed.join("1.0.0").join("plugin.toml"), "").unwrap();
+        symlink(&installed, installed.join("self")).unwrap();
+
+        let dev_checkout = root.path().join("src").join("adi.tasks");
+        std::fs::create_dir_all(&dev_checkout).unwrap();
+        symlink(&dev_checkout, plugins_dir.join("adi.tasks")).unwrap();
+        symlink(plugins_dir.join("adi.loop"), plugins_dir.join("adi.loop")).unwrap();
+        symlink(&plugins_dir, plugins_dir.join("adi.parent")).unwrap();
+        symlink(&dev_checkout, plugins_dir.join("adi.tasks-dup")).unwrap();
+
+        let scan = scan_plugin_dirs(&plugins_dir);
+        assert_eq!(scan.dirs, [installed.clone(), plugins_dir.join("adi.tasks")]);
+        let skipped: Vec<&str> = scan.skipped.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(skipped, ["adi.loop", "adi.parent", "adi.tasks-dup"]);
+
+        assert_eq!(plugin_subdirs(&installed), [installed.join("1.0.0")]);
+        assert_eq!(
+            find_plugin_toml_path(&installed),
+            Some(installed.join("1.0.0").join("plugin.toml"))
+        );
+    }
+
+    #[test]
+    fn test_installed_plugins_report_version_and_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let hive = dir.path().join("adi.hive");
+        std::fs::create_dir_all(hive.join("1.2.0")).unwrap();
+        std::fs::write(hive.join(".version"), "1.2.0\n").unwrap();
+        let tasks = dir.path().join("adi.tasks");
+        std::fs::create_dir_all(&tasks).unwrap();
+        std::fs::write(tasks.join(plugin_health::DISABLED_MARKER), "boom").unwrap();
+
+        let installed = installed_plugins(dir.path());
+        assert_eq!(
+            installed,
+            [
+                InstalledPlugin {
+                    id: "adi.hive".to_string(),
+                    version: Some("1.2.0".to_string()),
+                    enabled: true,
+                    path: hive.join("1.2.0"),
+                },
+                InstalledPlugin {
+                    id: "adi.tasks".to_string(),
+                    version: None,
+                    enabled: false,
+                    path: tasks,
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,108 @@
+//! Messages that agree with a count.
+//!
+//! `t!` hands every argument to Fluent as a string, so a selector such as
+//! `{ $count -> [one] ... [few] ... *[other] ... }` can only ever match the
+//! default arm. [`tn!`](crate::tn) passes `count` as a number instead, so
+//! Fluent picks the plural category of the active language (`one`/`few`/
+//! `many` in Russian and Ukrainian, `one`/`other` in English, ...). Other
+//! arguments are passed as strings, like `t!`.
+
+use std::collections::HashMap;
+
+use lib_i18n_core::fluent_bundle::FluentValue;
+use lib_i18n_core::LocalizedError;
+
+/// `t!` with a numeric `count` for plural selection:
+/// `tn!("plugin-install-pattern-found", plugins.len())` or
+/// `tn!("plugin-install-retrying", failed.len(), "attempt" => &attempt.to_string())`.
+#[macro_export]
+macro_rules! tn {
+    ($key:literal, $count:expr $(, $name:literal => $value:expr)* $(,)?) => {
+        $crate::plural::format($key, $count, &[$(($name, ::std::string::ToString::to_string(&$value))),*])
+    };
+}
+
+/// Message `key` with `count` as the number `$count` and `args` as strings.
+pub fn format(key: &'static str, count: usize, args: &[(&str, String)]) -> String {
+    PluralMessage {
+        key,
+        count,
+        args: args.iter().map(|(name, value)| (name.to_string(), value.clone())).collect(),
+    }
+    .localized()
+}
+
+/// Goes through [`LocalizedError`], the one lookup path that takes typed
+/// Fluent arguments.
+#[derive(Debug)]
+struct PluralMessage {
+    key: &'static str,
+    count: usize,
+    args: Vec<(String, String)>,
+}
+
+impl std::fmt::Display for PluralMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.key)
+    }
+}
+
+impl std::error::Error for PluralMessage {}
+
+impl LocalizedError for PluralMessage {
+    fn slug(&self) -> &str {
+        self.key
+    }
+
+    fn i18n_args(&self) -> HashMap<String, FluentValue<'static>> {
+        let mut args: HashMap<String, FluentValue<'static>> = self
+            .args
+            .iter()
+            .map(|(name, value)| (name.clone(), FluentValue::from(value.clone())))
+            .collect();
+        args.insert("count".into(), FluentValue::from(self.count));
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib_i18n_core::fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+
+    const RU_FTL: &str = r#"
+plugin-count = { $count ->
+    [one] { $count } плагин
+    [few] { $count } плагина
+   *[many] { $count } плагинов
+}
+"#;
+
+    /// Format `message` with its `tn!` arguments through a local ru-RU
+    /// bundle, leaving the process-wide i18n alone.
+    fn render_ru(message: &PluralMessage) -> String {
+        let mut bundle = FluentBundle::new(vec!["ru-RU".parse().unwrap()]);
+        bundle.set_use_isolating(false);
+        let resource = FluentResource::try_new(RU_FTL.to_string()).map_err(|(_, errors)| errors).unwrap();
+        bundle.add_resource(resource).unwrap();
+
+        let mut args = FluentArgs::new();
+        for (name, value) in message.i18n_args() {
+            args.set(name, value);
+        }
+        let pattern = bundle.get_message(message.slug()).and_then(|m| m.value()).unwrap();
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, Some(&args), &mut errors).to_string();
+        assert!(errors.is_empty(), "{:?}", errors);
+        text
+    }
+
+    #[test]
+    fn test_russian_plural_categories() {
+        let render = |count| render_ru(&PluralMessage { key: "plugin-count", count, args: Vec::new() });
+        assert_eq!(render(1), "1 плагин");
+        assert_eq!(render(2), "2 плагина");
+        assert_eq!(render(5), "5 плагинов");
+        assert_eq!(render(21), "21 плагин");
+    }
+}
@@ -0,0 +1,120 @@
+//! Credentials for private plugin registries.
+//!
+//! A token comes from `$ADI_REGISTRY_TOKEN` or, per registry host, from
+//! `$ADI_CONFIG_DIR/credentials.toml`:
+//!
+//! ```toml
+//! [registries."plugins.example.com"]
+//! token = "..."
+//! ```
+//!
+//! Tokens are wrapped in [`RegistryToken`] so they never end up in logs.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct RegistryToken(String);
+
+impl RegistryToken {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+
+    /// Value for an `Authorization` header.
+    pub fn authorization_header(&self) -> String {
+        format!("Bearer {}", self.0)
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for RegistryToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RegistryToken(***)")
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CredentialsFile {
+    #[serde(default)]
+    registries: HashMap<String, HostCredentials>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HostCredentials {
+    token: String,
+}
+
+/// $ADI_CONFIG_DIR/credentials.toml or ~/.config/adi/credentials.toml
+pub fn credentials_path() -> PathBuf {
+    crate::clienv::config_dir().join("credentials.toml")
+}
+
+/// Token to send to `registry_url` (env var > credentials file entry for its host).
+pub fn token_for(registry_url: &str) -> Option<RegistryToken> {
+    if let Some(token) = crate::clienv::registry_token() {
+        tracing::trace!("Using registry token from ADI_REGISTRY_TOKEN");
+        return Some(RegistryToken::new(token));
+    }
+
+    let content = std::fs::read_to_string(credentials_path()).ok()?;
+    let token = token_from_credentials(&content, registry_url);
+    tracing::trace!(found = token.is_some(), "Looked up registry token in credentials file");
+    token
+}
+
+fn token_from_credentials(content: &str, registry_url: &str) -> Option<RegistryToken> {
+    let file: CredentialsFile = match toml::from_str(content) {
+        Ok(file) => file,
+        Err(e) => {
+            tracing::warn!("Ignoring malformed credentials file: {}", e);
+            return None;
+        }
+    };
+    let url = reqwest::Url::parse(registry_url).ok()?;
+    let host = url.host_str()?;
+    let key = match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    };
+
+    file.registries
+        .get(&key)
+        .or_else(|| file.registries.get(host))
+        .map(|c| RegistryToken::new(c.token.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CREDENTIALS: &str = r#"
+[registries."plugins.example.com"]
+token = "secret-a"
+
+[registries."mirror.example.com:8443"]
+token = "secret-b"
+"#;
+
+    #[test]
+    fn test_token_is_keyed_by_host() {
+        let token = token_from_credentials(CREDENTIALS, "https://plugins.example.com/v1").unwrap();
+        assert_eq!(token.expose(), "secret-a");
+
+        let token = token_from_credentials(CREDENTIALS, "https://mirror.example.com:8443").unwrap();
+        assert_eq!(token.authorization_header(), "Bearer secret-b");
+
+        assert!(token_from_credentials(CREDENTIALS, "https://other.example.com").is_none());
+    }
+
+    #[test]
+    fn test_token_debug_is_redacted() {
+        let token = RegistryToken::new("secret");
+        assert!(!format!("{:?}", token).contains("secret"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Local copy of registry responses in `<cache_dir>/registry-cache/`.
+//!
+//! Every plugin list, search and plugin info fetched from the registry is
+//! written here. [`crate::PluginManager`] reuses an entry until it is older
+//! than the TTL (`ADI_REGISTRY_CACHE_TTL`, default one hour), and falls back
+//! to a stale one when the refetch fails. In offline mode (`--offline`,
+//! `ADI_OFFLINE=1`) it answers those lookups from these files alone, whatever
+//! their age, and never touches the network; a lookup that was never cached
+//! fails with [`crate::InstallerError::OfflineNotCached`].
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const CACHE_SUBDIR: &str = "registry-cache";
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+pub const PLUGINS_KEY: &str = "plugins";
+
+pub fn info_key(id: &str) -> String {
+    format!("info-{}", id)
+}
+
+pub fn search_key(query: &str) -> String {
+    format!("search-{}", query)
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    /// Registry URL the response came from
+    registry: String,
+    /// Unix seconds
+    fetched_at: u64,
+    value: T,
+}
+
+pub struct RegistryCache {
+    dir: PathBuf,
+    registry: String,
+}
+
+impl RegistryCache {
+    pub fn new(cache_dir: &Path, registry: &str) -> Self {
+        Self {
+            dir: cache_dir.join(CACHE_SUBDIR),
+            registry: registry.to_string(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The cached response for `key`, however old, if there is one from
+    /// this registry.
+    pub fn load<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.read(key).map(|entry| entry.value)
+    }
+
+    /// The cached response for `key` if it was fetched less than `ttl` ago.
+    pub fn load_fresh<T: DeserializeOwned>(&self, key: &str, ttl: Duration) -> Option<T> {
+        let entry = self.read::<T>(key)?;
+        let age = Duration::from_secs(now_secs().saturating_sub(entry.fetched_at));
+        if age >= ttl {
+            tracing::trace!(key = %key, age_secs = age.as_secs(), "Registry cache entry is stale");
+            return None;
+        }
+        Some(entry.value)
+    }
+
+    /// Delete every cached response. Returns the bytes freed.
+    pub fn clear(&self) -> std::io::Result<u64> {
+        let freed = dir_size(&self.dir);
+        match std::fs::remove_dir_all(&self.dir) {
+            Ok(()) => Ok(freed),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn read<T: DeserializeOwned>(&self, key: &str) -> Option<Entry<T>> {
+        let path = self.path(key);
+        let content = std::fs::read(&path).ok()?;
+        let entry: Entry<T> = match serde_json::from_slice(&content) {
+            Ok(entry) => entry,
+            Err(e) => {
+                tracing::trace!(path = %path.display(), error = %e, "Ignoring unreadable registry cache entry");
+                return None;
+            }
+        };
+        if entry.registry != self.registry {
+            tracing::trace!(key = %key, cached = %entry.registry, "Registry cache entry is for another registry");
+            return None;
+        }
+        tracing::trace!(key = %key, fetched_at = entry.fetched_at, "Registry cache hit");
+        Some(entry)
+    }
+
+    /// Save a response. Best effort: a cache that can't be written only
+    /// costs offline mode that entry.
+    pub fn store<T: Serialize>(&self, key: &str, value: &T) {
+        let entry = Entry {
+            registry: self.registry.clone(),
+            fetched_at: now_secs(),
+            value,
+        };
+        let path = self.path(key);
+        let result = serde_json::to_vec(&entry)
+            .map_err(std::io::Error::from)
+            .and_then(|json| {
+                std::fs::create_dir_all(&self.dir)?;
+                // Write then rename so a concurrent reader never sees half a file.
+                let tmp = path.with_extension("json.tmp");
+                std::fs::write(&tmp, json)?;
+                std::fs::rename(&tmp, &path)
+            });
+        if let Err(e) = result {
+            tracing::trace!(path = %path.display(), error = %e, "Failed to write registry cache entry");
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(file_name(key))
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|meta| meta.is_file())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// `key` with anything but `[A-Za-z0-9._-]` replaced, plus a short hash of
+/// the original when something was replaced so distinct keys stay distinct.
+fn file_name(key: &str) -> String {
+    let safe: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .take(100)
+        .collect();
+    if safe == key {
+        return format!("{}.json", safe);
+    }
+    let hash = Sha256::digest(key.as_bytes());
+    let hex: String = hash.iter().take(4).map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}.json", safe, hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RegistryCache::new(dir.path(), "https://registry.example");
+        assert_eq!(cache.load::<Vec<String>>(PLUGINS_KEY), None);
+
+        cache.store(PLUGINS_KEY, &vec!["adi.hive".to_string()]);
+        assert_eq!(cache.load::<Vec<String>>(PLUGINS_KEY), Some(vec!["adi.hive".to_string()]));
+
+        let other = RegistryCache::new(dir.path(), "https://other.example");
+        assert_eq!(other.load::<Vec<String>>(PLUGINS_KEY), None);
+    }
+
+    #[test]
+    fn test_ttl_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RegistryCache::new(dir.path(), "https://registry.example");
+        cache.store(PLUGINS_KEY, &vec!["adi.hive".to_string()]);
+
+        assert!(cache.load_fresh::<Vec<String>>(PLUGINS_KEY, DEFAULT_TTL).is_some());
+        assert!(cache.load_fresh::<Vec<String>>(PLUGINS_KEY, Duration::ZERO).is_none());
+        assert!(cache.load::<Vec<String>>(PLUGINS_KEY).is_some());
+
+        assert!(cache.clear().unwrap() > 0);
+        assert!(!cache.dir().exists());
+        assert_eq!(cache.clear().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("info-adi.hive"), "info-adi.hive.json");
+        let a = file_name("search-a b");
+        let b = file_name("search-a/b");
+        assert!(a.starts_with("search-a_b-") && b.starts_with("search-a_b-"));
+        assert_ne!(a, b);
+    }
+}
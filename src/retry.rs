@@ -0,0 +1,159 @@
+//! Retrying registry requests that fail on a flaky network.
+//!
+//! Only connection and timeout failures are retried; "not found" or "forbidden"
+//! answers won't change on a second try. Waits double after each attempt
+//! (500ms, 1s, 2s, ... by default).
+
+use std::future::Future;
+use std::time::Duration;
+
+use lib_i18n_core::LocalizedError;
+
+use crate::error::{InstallerError, Result};
+
+/// Wait before the first retry; doubled for each one after.
+pub const BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Run `op` up to `attempts` times while it fails with a transient error.
+/// `op` gets the 1-based attempt number. Giving up on a transient error
+/// yields [`InstallerError::RetriesExhausted`].
+pub async fn with_retries<T, F, Fut>(attempts: u32, base_delay: Duration, mut op: F) -> Result<T>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let attempts = attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        let err = match op(attempt).await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        if !is_transient(&err) {
+            return Err(err);
+        }
+        if attempt >= attempts {
+            if attempts == 1 {
+                return Err(err);
+            }
+            return Err(InstallerError::RetriesExhausted {
+                attempts,
+                detail: err.localized(),
+            });
+        }
+        let delay = backoff(base_delay, attempt);
+        tracing::trace!(attempt = attempt, delay_ms = delay.as_millis() as u64, error = %err.localized(), "Transient network error, retrying");
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Delay after failed attempt `attempt` (1-based).
+fn backoff(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1 << (attempt - 1).min(16))
+}
+
+/// Whether `err` looks like a dropped connection or timeout rather than an
+/// answer from the server.
+pub fn is_transient(err: &InstallerError) -> bool {
+    use std::io::ErrorKind;
+    match err {
+        InstallerError::Io(e) => matches!(
+            e.kind(),
+            ErrorKind::TimedOut
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::ConnectionRefused
+                | ErrorKind::NotConnected
+                | ErrorKind::BrokenPipe
+                | ErrorKind::UnexpectedEof
+                | ErrorKind::Interrupted
+        ),
+        // Registry and host errors only carry a message.
+        InstallerError::Registry(_) | InstallerError::PluginHost(_) | InstallerError::Other(_) => {
+            is_transient_message(&err_detail(err))
+        }
+        _ => false,
+    }
+}
+
+fn err_detail(err: &InstallerError) -> String {
+    match err {
+        InstallerError::Registry(e) => e.to_string(),
+        InstallerError::PluginHost(e) => e.to_string(),
+        InstallerError::Other(detail) => detail.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn is_transient_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    let permanent = ["404", "403", "401", "not found", "forbidden", "unauthorized"];
+    if permanent.iter().any(|word| message.contains(word)) {
+        return false;
+    }
+    let transient = [
+        "timed out",
+        "timeout",
+        "connection",
+        "connect error",
+        "error sending request",
+        "dns",
+        "temporarily unavailable",
+        "502",
+        "503",
+        "504",
+    ];
+    transient.iter().any(|word| message.contains(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient() {
+        let io = |kind| InstallerError::Io(std::io::Error::from(kind));
+        assert!(is_transient(&io(std::io::ErrorKind::TimedOut)));
+        assert!(!is_transient(&io(std::io::ErrorKind::PermissionDenied)));
+        assert!(is_transient(&InstallerError::Other("error sending request: connection reset".into())));
+        assert!(!is_transient(&InstallerError::Other("HTTP 404 Not Found".into())));
+        assert!(!is_transient(&InstallerError::PluginNotFound { id: "adi.hive".into() }));
+        assert_eq!(backoff(BASE_DELAY, 3), Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_with_retries_stops_on_success_or_permanent_error() {
+        let mut calls = 0;
+        let result = with_retries(3, Duration::ZERO, |attempt| {
+            calls += 1;
+            async move {
+                if attempt < 3 {
+                    Err(InstallerError::Other("operation timed out".into()))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls, 3);
+
+        let err = with_retries(3, Duration::ZERO, |_| async {
+            Err::<(), _>(InstallerError::Other("timeout".into()))
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(err, InstallerError::RetriesExhausted { attempts: 3, .. }));
+
+        let mut calls = 0;
+        let err = with_retries(3, Duration::ZERO, |_| {
+            calls += 1;
+            async { Err::<(), _>(InstallerError::PluginNotFound { id: "adi.hive".into() }) }
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(err, InstallerError::PluginNotFound { .. }));
+        assert_eq!(calls, 1);
+    }
+}
@@ -1,17 +1,89 @@
 use anyhow::{anyhow, Result};
 use lib_client_github::{no_auth, Client, Release, ReleaseAsset};
-use lib_console_output::{out_info, out_success};
-use lib_i18n_core::t;
+use lib_console_output::{out_info, out_success, out_warn};
+use lib_i18n_core::{t, LocalizedError};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::archive::ExtractLimits;
+use crate::user_config::UserConfig;
+
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
 
+/// Sidecar next to each release archive holding its sha256 (`sha256sum` format).
+const CHECKSUM_SUFFIX: &str = ".sha256";
+/// Release-wide checksum list, used when an archive has no sidecar of its own.
+const CHECKSUM_LIST: &str = "SHA256SUMS";
+
+/// Release channel to update from.
+///
+/// - stable: `cli-v1.2.0`
+/// - beta: stable plus `cli-v1.2.0-beta.3`
+/// - nightly: all of the above plus `cli-nightly-<version>`, e.g.
+///   `cli-nightly-1.3.0-nightly.20260101`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    #[default]
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl Channel {
+    /// Version of a release tag if it belongs to this channel.
+    fn release_version(self, tag: &str) -> Option<Version> {
+        if let Some(raw) = tag.strip_prefix("cli-nightly-") {
+            if self != Channel::Nightly {
+                return None;
+            }
+            return Version::parse(raw).ok();
+        }
+        let version = Version::parse(tag.strip_prefix("cli-v")?).ok()?;
+        let accepted = match self {
+            Channel::Stable => version.pre.is_empty(),
+            Channel::Beta => version.pre.is_empty() || version.pre.as_str().starts_with("beta"),
+            Channel::Nightly => true,
+        };
+        accepted.then_some(version)
+    }
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Channel::Stable => "stable",
+            Channel::Beta => "beta",
+            Channel::Nightly => "nightly",
+        })
+    }
+}
+
+/// `--channel` if given (remembered for next time), else the saved channel, else stable.
+fn resolve_channel(requested: Option<Channel>) -> Channel {
+    let mut config = UserConfig::load().unwrap_or_default();
+    let Some(channel) = requested else {
+        return config.update_channel.unwrap_or_default();
+    };
+    if config.update_channel != Some(channel) {
+        config.update_channel = Some(channel);
+        if let Err(e) = config.save() {
+            tracing::warn!("Failed to save update channel: {}", e);
+        }
+    }
+    channel
+}
+
 pub async fn check_for_updates() -> Result<Option<String>> {
     tracing::trace!(current = CURRENT_VERSION, "Checking for updates");
-    let latest = fetch_latest_version().await?;
+    let channel = UserConfig::load().unwrap_or_default().update_channel.unwrap_or_default();
+    let latest = fetch_latest_version(channel).await?;
     tracing::trace!(current = CURRENT_VERSION, latest = %latest, "Version check complete");
 
     if version_is_newer(&latest, CURRENT_VERSION) {
@@ -23,11 +95,51 @@ pub async fn check_for_updates() -> Result<Option<String>> {
     }
 }
 
-pub async fn self_update(force: bool) -> Result<()> {
-    tracing::trace!(force = force, current = CURRENT_VERSION, "Starting self-update");
+/// Install a specific CLI release (`adi self-update --to <version>`), even if
+/// it's older than the running one.
+pub async fn self_update_to(version: &str, force: bool, verify: bool) -> Result<()> {
+    let wanted = Version::parse(version.trim_start_matches('v'))
+        .map_err(|_| anyhow!(t!("self-update-error-no-version", "version" => version)))?;
+    tracing::trace!(version = %wanted, current = CURRENT_VERSION, "Starting self-update to a specific version");
     out_info!("{}", t!("self-update-checking"));
 
-    let latest_version = fetch_latest_version().await?;
+    let releases = fetch_releases().await?;
+    let tags: Vec<&str> = releases.iter().map(|release| release.tag_name.as_str()).collect();
+    let index = find_version_tag(&tags, &wanted)
+        .ok_or_else(|| anyhow!(t!("self-update-error-no-version", "version" => wanted.to_string())))?;
+    let release = &releases[index];
+    let target = wanted.to_string();
+    tracing::trace!(tag = %release.tag_name, "Selected CLI release");
+
+    if !force && target == CURRENT_VERSION {
+        out_success!("{}", t!("self-update-already-latest", "version" => CURRENT_VERSION));
+        return Ok(());
+    }
+    if version_is_newer(CURRENT_VERSION, &target) {
+        out_warn!("{}", t!("self-update-downgrade", "current" => CURRENT_VERSION, "target" => &target));
+    } else {
+        out_info!("{}", t!("self-update-new-version", "current" => CURRENT_VERSION, "latest" => &target));
+    }
+    download_and_install(release, &target, verify).await?;
+
+    out_success!("{}", t!("self-update-success", "version" => &target));
+    tracing::trace!(version = %target, "Self-update complete");
+    Ok(())
+}
+
+/// Update the running `adi`. With `verify`, the downloaded binary must run and
+/// report `latest_version` before it replaces the current one.
+pub async fn self_update(force: bool, verify: bool, channel: Option<Channel>) -> Result<()> {
+    let channel = resolve_channel(channel);
+    tracing::trace!(force = force, verify = verify, channel = %channel, current = CURRENT_VERSION, "Starting self-update");
+    if channel == Channel::Stable {
+        out_info!("{}", t!("self-update-checking"));
+    } else {
+        out_info!("{}", t!("self-update-checking-channel", "channel" => channel.to_string()));
+    }
+
+    let release = fetch_latest_release(channel).await?;
+    let latest_version = release_version(&release, channel);
 
     if !force && !version_is_newer(&latest_version, CURRENT_VERSION) {
         tracing::trace!("No update needed");
@@ -36,21 +148,20 @@ pub async fn self_update(force: bool) -> Result<()> {
     }
 
     out_info!("{}", t!("self-update-new-version", "current" => CURRENT_VERSION, "latest" => &latest_version));
-    download_and_install().await?;
+    download_and_install(&release, &latest_version, verify).await?;
 
     out_success!("{}", t!("self-update-success", "version" => &latest_version));
     tracing::trace!(version = %latest_version, "Self-update complete");
     Ok(())
 }
 
-async fn download_and_install() -> Result<()> {
+async fn download_and_install(release: &Release, latest_version: &str, verify: bool) -> Result<()> {
     let current_exe = env::current_exe()?;
     let platform = detect_platform()?;
     tracing::trace!(platform = %platform, exe = %current_exe.display(), "Detected platform");
 
     out_info!("{}", t!("self-update-downloading"));
-    let release = fetch_latest_release().await?;
-    let asset = select_asset(&release, &platform)?;
+    let asset = select_asset(release, &platform)?;
     tracing::trace!(asset = %asset.name, url = %asset.browser_download_url, "Selected release asset");
 
     let temp_dir = env::temp_dir().join("adi-update");
@@ -61,10 +172,21 @@ async fn download_and_install() -> Result<()> {
     download_file(&asset.browser_download_url, &archive_path).await?;
     tracing::trace!("Download complete");
 
+    if verify {
+        verify_archive(release, asset, &fs::read(&archive_path)?).await?;
+    } else {
+        tracing::warn!("Skipping checksum verification of {} (--no-verify)", asset.name);
+    }
+
     out_info!("{}", t!("self-update-extracting"));
     let binary_path = extract_binary(&archive_path, &temp_dir)?;
     tracing::trace!(binary = %binary_path.display(), "Binary extracted");
 
+    if verify {
+        out_info!("{}", t!("self-update-verifying"));
+        verify_binary(&binary_path, latest_version)?;
+    }
+
     out_info!("{}", t!("self-update-installing"));
     tracing::trace!(src = %binary_path.display(), dest = %current_exe.display(), "Replacing binary");
     replace_binary(&binary_path, &current_exe)?;
@@ -74,18 +196,25 @@ async fn download_and_install() -> Result<()> {
     Ok(())
 }
 
-async fn fetch_latest_version() -> Result<String> {
-    tracing::trace!("Fetching latest version from GitHub");
-    let release = fetch_latest_release().await?;
-    let version = release.tag_name.trim_start_matches("cli-v").to_string();
+async fn fetch_latest_version(channel: Channel) -> Result<String> {
+    tracing::trace!(channel = %channel, "Fetching latest version from GitHub");
+    let release = fetch_latest_release(channel).await?;
+    Ok(release_version(&release, channel))
+}
+
+fn release_version(release: &Release, channel: Channel) -> String {
+    let version = channel
+        .release_version(&release.tag_name)
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| release.tag_name.trim_start_matches("cli-v").to_string());
     tracing::trace!(tag = %release.tag_name, version = %version, "Parsed latest version");
-    Ok(version)
+    version
 }
 
-fn build_github_client() -> Result<Client> {
+pub(crate) fn build_github_client() -> Result<Client> {
     tracing::trace!("Building GitHub API client");
     Client::builder()
-        .user_agent("adi-installer")
+        .user_agent(&crate::http::user_agent())
         .auth(no_auth())
         .build()
         .map_err(|e| anyhow!("Failed to build GitHub client: {}", e))
@@ -97,7 +226,7 @@ fn parse_repository() -> (&'static str, &'static str) {
     (parts[1], parts[0])
 }
 
-async fn fetch_latest_release() -> Result<Release> {
+async fn fetch_releases() -> Result<Vec<Release>> {
     let (repo_owner, repo_name) = parse_repository();
     tracing::trace!(owner = %repo_owner, repo = %repo_name, "Fetching releases from GitHub");
 
@@ -108,29 +237,56 @@ async fn fetch_latest_release() -> Result<Release> {
         .map_err(|e| anyhow!("Failed to fetch releases: {}", e))?;
 
     tracing::trace!(count = releases.len(), "Fetched releases");
+    Ok(releases)
+}
 
-    // Priority: cli-v* (new format), fallback to v* without component prefix (legacy)
-    let cli_release = releases
-        .iter()
-        .find(|release| release.tag_name.starts_with("cli-v"))
-        .or_else(|| {
-            releases.iter().find(|release| {
-                let tag = &release.tag_name;
-                tag.starts_with('v') && !tag.contains("indexer-") && !tag.contains("cli-")
-            })
-        })
-        .ok_or_else(|| anyhow!(t!("self-update-error-no-release")))?
-        .clone();
+async fn fetch_latest_release(channel: Channel) -> Result<Release> {
+    tracing::trace!(channel = %channel, "Fetching latest release");
+    let releases = fetch_releases().await?;
+
+    let tags: Vec<&str> = releases.iter().map(|release| release.tag_name.as_str()).collect();
+    let selected = select_release_tag(&tags, channel)
+        .ok_or_else(|| anyhow!(t!("self-update-error-no-release")))?;
+    let cli_release = releases[selected].clone();
 
     tracing::trace!(tag = %cli_release.tag_name, "Selected CLI release");
     Ok(cli_release)
 }
 
+/// Index of the CLI release tagged with exactly `version`, on any channel
+/// (`cli-v3.0.2`, `cli-nightly-...`), or a legacy `v3.0.2` tag.
+fn find_version_tag(tags: &[&str], version: &Version) -> Option<usize> {
+    tags.iter()
+        .position(|tag| Channel::Nightly.release_version(tag).as_ref() == Some(version))
+        .or_else(|| {
+            let legacy = format!("v{}", version);
+            tags.iter().position(|tag| *tag == legacy)
+        })
+}
+
+/// Index of the newest tag on `channel`. Falls back to the first legacy `v*`
+/// tag (no component prefix) when the channel has no `cli-*` release at all.
+fn select_release_tag(tags: &[&str], channel: Channel) -> Option<usize> {
+    tags.iter()
+        .enumerate()
+        .filter_map(|(i, tag)| Some((i, channel.release_version(tag)?)))
+        .max_by(|(_, a), (_, b)| a.cmp(b))
+        .map(|(i, _)| i)
+        .or_else(|| {
+            tags.iter()
+                .position(|tag| tag.starts_with('v') && !tag.contains("indexer-") && !tag.contains("cli-"))
+        })
+}
+
 fn detect_platform() -> Result<String> {
     let os = if cfg!(target_os = "macos") {
         "apple-darwin"
     } else if cfg!(target_os = "linux") {
-        "unknown-linux-gnu"
+        if cfg!(target_env = "musl") {
+            "unknown-linux-musl"
+        } else {
+            linux_libc_target(Path::new("/"))
+        }
     } else if cfg!(target_os = "windows") {
         "pc-windows-msvc"
     } else {
@@ -150,33 +306,123 @@ fn detect_platform() -> Result<String> {
     Ok(platform)
 }
 
+/// `unknown-linux-musl` when the system under `root` has musl's dynamic
+/// loader (`/lib/ld-musl-<arch>.so.1`) and no glibc one (Alpine and other
+/// musl distros, where a glibc build won't start), else `unknown-linux-gnu`.
+fn linux_libc_target(root: &Path) -> &'static str {
+    let has_loader = |dir: &str, prefix: &str| {
+        fs::read_dir(root.join(dir))
+            .map(|entries| entries.flatten().any(|entry| entry.file_name().to_string_lossy().starts_with(prefix)))
+            .unwrap_or(false)
+    };
+    let glibc = ["lib", "lib64"].iter().any(|dir| has_loader(dir, "ld-linux"));
+    if has_loader("lib", "ld-musl-") && !glibc {
+        tracing::trace!(root = %root.display(), "Detected musl libc");
+        "unknown-linux-musl"
+    } else {
+        "unknown-linux-gnu"
+    }
+}
+
 fn select_asset<'a>(release: &'a Release, platform: &str) -> Result<&'a ReleaseAsset> {
     tracing::trace!(platform = %platform, assets = release.assets.len(), "Selecting asset for platform");
-    release
-        .assets
-        .iter()
-        .find(|asset| asset.name.contains(platform))
-        .ok_or_else(|| anyhow!(t!("self-update-error-no-asset", "platform" => platform)))
+    for candidate in asset_platforms(platform) {
+        let Some(asset) = release.assets.iter().find(|asset| asset.name.contains(candidate.as_str())) else {
+            continue;
+        };
+        if candidate != platform {
+            out_warn!("{}", t!("self-update-musl-fallback", "platform" => platform, "fallback" => &candidate));
+        }
+        return Ok(asset);
+    }
+    Err(anyhow!(t!("self-update-error-no-asset", "platform" => platform)))
+}
+
+/// Builds that can run on `platform`, best first. A musl system falls back
+/// to the gnu build when a release has no musl one.
+fn asset_platforms(platform: &str) -> Vec<String> {
+    let mut platforms = vec![platform.to_string()];
+    if let Some(arch) = platform.strip_suffix("-unknown-linux-musl") {
+        platforms.push(format!("{}-unknown-linux-gnu", arch));
+    }
+    platforms
 }
 
 async fn download_file(url: &str, dest: &Path) -> Result<()> {
     tracing::trace!(url = %url, dest = %dest.display(), "Downloading file");
-    let response = reqwest::get(url).await?;
-    let bytes = response.bytes().await?;
+    let bytes = download_bytes(url).await?;
     tracing::trace!(bytes = bytes.len(), "Downloaded, writing to disk");
     fs::write(dest, bytes)?;
     Ok(())
 }
 
+async fn download_bytes(url: &str) -> Result<Vec<u8>> {
+    let response = crate::http::client()?.get(url).send().await?.error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+async fn download_text(url: &str) -> Result<String> {
+    Ok(String::from_utf8_lossy(&download_bytes(url).await?).into_owned())
+}
+
+/// The asset named `<archive>` + `suffix`, if the release has one.
+fn sidecar_asset<'a>(release: &'a Release, archive: &str, suffix: &str) -> Option<&'a ReleaseAsset> {
+    let name = format!("{}{}", archive, suffix);
+    release.assets.iter().find(|asset| asset.name == name)
+}
+
+/// Check the downloaded archive against the release's published sha256. A
+/// release without one is refused; `--no-verify` skips this check.
+async fn verify_archive(release: &Release, asset: &ReleaseAsset, archive: &[u8]) -> Result<()> {
+    out_info!("{}", t!("self-update-verifying-archive"));
+
+    let checksums = sidecar_asset(release, &asset.name, CHECKSUM_SUFFIX)
+        .or_else(|| release.assets.iter().find(|a| a.name == CHECKSUM_LIST));
+    let checksums = checksums.ok_or_else(|| anyhow!(t!("self-update-error-no-checksum", "asset" => &asset.name)))?;
+    tracing::trace!(asset = %checksums.name, "Downloading checksum");
+    let listing = download_text(&checksums.browser_download_url).await?;
+    let expected = expected_checksum(&listing, &asset.name).ok_or_else(|| {
+        anyhow!(t!("self-update-error-checksum-missing", "asset" => &asset.name, "source" => &checksums.name))
+    })?;
+    verify_checksum(&asset.name, archive, &expected)
+}
+
+/// The hash for `asset` in `sha256sum` output: either a bare hash, or
+/// `<hash>  <name>` lines (a `*` before the name marks binary mode).
+fn expected_checksum(listing: &str, asset: &str) -> Option<String> {
+    listing.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let hash = fields.next()?;
+        match fields.next() {
+            Some(name) if name.trim_start_matches('*') != asset => None,
+            _ => Some(hash.to_string()),
+        }
+    })
+}
+
+fn verify_checksum(asset: &str, archive: &[u8], expected: &str) -> Result<()> {
+    let actual = format!("{:x}", Sha256::digest(archive));
+    tracing::trace!(asset = %asset, expected = %expected, actual = %actual, "Verifying archive checksum");
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(anyhow!(t!("self-update-error-checksum",
+            "asset" => asset,
+            "expected" => expected,
+            "actual" => &actual
+        )));
+    }
+    Ok(())
+}
+
 fn extract_binary(archive_path: &Path, temp_dir: &Path) -> Result<PathBuf> {
     let binary_name = if cfg!(windows) { "adi.exe" } else { "adi" };
     let binary_path = temp_dir.join(binary_name);
     tracing::trace!(archive = %archive_path.display(), binary_name = %binary_name, "Extracting binary from archive");
 
+    let limits = ExtractLimits::from_env();
     if archive_path.extension().and_then(|s| s.to_str()) == Some("zip") {
-        extract_from_zip(archive_path, binary_name, &binary_path)?;
+        extract_from_zip(archive_path, binary_name, &binary_path, &limits)?;
     } else {
-        extract_from_tar_gz(archive_path, binary_name, &binary_path)?;
+        extract_from_tar_gz(archive_path, binary_name, &binary_path, &limits)?;
     }
 
     #[cfg(unix)]
@@ -191,21 +437,29 @@ fn extract_binary(archive_path: &Path, temp_dir: &Path) -> Result<PathBuf> {
     Ok(binary_path)
 }
 
-fn extract_from_zip(archive_path: &Path, binary_name: &str, dest: &Path) -> Result<()> {
+fn extract_from_zip(archive_path: &Path, binary_name: &str, dest: &Path, limits: &ExtractLimits) -> Result<()> {
     use std::io::Read;
     use zip::ZipArchive;
 
     tracing::trace!("Using zip extraction");
     let file = fs::File::open(archive_path)?;
     let mut archive = ZipArchive::new(file)?;
+    if archive.len() as u64 > limits.max_entries {
+        return Err(too_large("entries", limits.max_entries));
+    }
 
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)?;
+        checked_entry_path(Path::new(file.name()))?;
         if file.name() != binary_name {
             continue;
         }
         let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)?;
+        // Declared zip sizes can lie; stop reading once past the limit.
+        file.take(limits.max_bytes + 1).read_to_end(&mut buffer)?;
+        if buffer.len() as u64 > limits.max_bytes {
+            return Err(too_large("bytes", limits.max_bytes));
+        }
         fs::write(dest, buffer)?;
         tracing::trace!("Binary extracted from zip");
         return Ok(());
@@ -213,7 +467,7 @@ fn extract_from_zip(archive_path: &Path, binary_name: &str, dest: &Path) -> Resu
     Err(anyhow!("Binary '{}' not found in zip archive", binary_name))
 }
 
-fn extract_from_tar_gz(archive_path: &Path, binary_name: &str, dest: &Path) -> Result<()> {
+fn extract_from_tar_gz(archive_path: &Path, binary_name: &str, dest: &Path, limits: &ExtractLimits) -> Result<()> {
     use flate2::read::GzDecoder;
     use std::io::Read;
     use tar::Archive;
@@ -222,11 +476,18 @@ fn extract_from_tar_gz(archive_path: &Path, binary_name: &str, dest: &Path) -> R
     let tar_gz = fs::File::open(archive_path)?;
     let mut archive = Archive::new(GzDecoder::new(tar_gz));
 
-    for entry in archive.entries()? {
+    for (index, entry) in archive.entries()?.enumerate() {
+        if index as u64 >= limits.max_entries {
+            return Err(too_large("entries", limits.max_entries));
+        }
         let mut entry = entry?;
-        if entry.path()?.file_name().and_then(|s| s.to_str()) != Some(binary_name) {
+        let path = checked_entry_path(&entry.path()?)?;
+        if path.file_name().and_then(|s| s.to_str()) != Some(binary_name) {
             continue;
         }
+        if entry.size() > limits.max_bytes {
+            return Err(too_large("bytes", limits.max_bytes));
+        }
         let mut buffer = Vec::new();
         entry.read_to_end(&mut buffer)?;
         fs::write(dest, buffer)?;
@@ -236,12 +497,65 @@ fn extract_from_tar_gz(archive_path: &Path, binary_name: &str, dest: &Path) -> R
     Err(anyhow!("Binary '{}' not found in tar.gz archive", binary_name))
 }
 
+/// Release archives are untrusted too: refuse one with absolute or `..` paths.
+fn checked_entry_path(path: &Path) -> Result<PathBuf> {
+    crate::archive::entry_path(path).map_err(|e| anyhow!(e.localized()))
+}
+
+fn too_large(kind: &str, limit: u64) -> anyhow::Error {
+    let err = crate::error::InstallerError::ArchiveTooLarge {
+        kind: kind.to_string(),
+        limit,
+    };
+    anyhow!(err.localized())
+}
+
+/// Run the new binary with `--version` and check that it starts and reports
+/// `expected`. Catches corrupt and wrong-arch downloads before they replace
+/// the working binary.
+fn verify_binary(binary: &Path, expected: &str) -> Result<()> {
+    tracing::trace!(binary = %binary.display(), expected = %expected, "Verifying downloaded binary");
+    let fail = |reason: String| anyhow!(t!("self-update-error-verify", "reason" => reason));
+
+    let output = std::process::Command::new(binary)
+        .arg("--version")
+        .output()
+        .map_err(|e| fail(e.to_string()))?;
+    if !output.status.success() {
+        return Err(fail(format!("--version exited with {}", output.status)));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !reports_version(&stdout, expected) {
+        return Err(fail(format!("expected version {}, got '{}'", expected, stdout.trim())));
+    }
+    tracing::trace!(output = %stdout.trim(), "Downloaded binary verified");
+    Ok(())
+}
+
+/// Whether `--version` output (`adi 1.2.0`) names `expected`.
+fn reports_version(output: &str, expected: &str) -> bool {
+    let expected = expected.trim_start_matches('v');
+    output
+        .split_whitespace()
+        .any(|word| word.trim_start_matches('v') == expected)
+}
+
 fn replace_binary(new_binary: &Path, current_exe: &Path) -> Result<()> {
     tracing::trace!(src = %new_binary.display(), dest = %current_exe.display(), "Replacing binary");
 
     #[cfg(unix)]
     {
-        fs::copy(new_binary, current_exe)?;
+        // Keep the working binary as adi.bak so a failed copy can be undone
+        // (and a bad release rolled back by hand).
+        let backup = current_exe.with_file_name("adi.bak");
+        fs::copy(current_exe, &backup)?;
+        tracing::trace!(backup = %backup.display(), "Backed up current binary");
+
+        if let Err(e) = fs::copy(new_binary, current_exe) {
+            tracing::trace!(error = %e, "Copy failed, restoring backup");
+            fs::copy(&backup, current_exe)?;
+            return Err(e.into());
+        }
         tracing::trace!("Binary copied");
 
         // Re-sign: extracted binary loses its signature
@@ -263,40 +577,70 @@ fn replace_binary(new_binary: &Path, current_exe: &Path) -> Result<()> {
 
     #[cfg(windows)]
     {
-        let old_exe = current_exe.with_extension("exe.old");
+        // A running exe can be renamed but not deleted, so move it aside and
+        // delete it once this process has exited.
+        let mut old_exe = current_exe.with_extension("exe.old");
+        if old_exe.exists() && fs::remove_file(&old_exe).is_err() {
+            // Still locked by another adi process from an earlier update.
+            old_exe = current_exe.with_extension(format!("exe.old.{}", std::process::id()));
+        }
         tracing::trace!(old = %old_exe.display(), "Windows binary replacement");
 
-        if old_exe.exists() {
-            let _ = fs::remove_file(&old_exe);
+        fs::rename(current_exe, &old_exe)?;
+        if let Err(e) = fs::copy(new_binary, current_exe) {
+            tracing::trace!(error = %e, "Copy failed, restoring previous binary");
+            fs::rename(&old_exe, current_exe)?;
+            return Err(e.into());
         }
 
-        fs::rename(current_exe, &old_exe)?;
-        fs::copy(new_binary, current_exe)?;
-        let _ = fs::remove_file(&old_exe);
+        if fs::remove_file(&old_exe).is_err() {
+            schedule_delete_after_exit(&old_exe);
+        }
 
         Ok(())
     }
 }
 
-fn version_is_newer(latest: &str, current: &str) -> bool {
-    let latest = latest.trim_start_matches('v');
-    let current = current.trim_start_matches('v');
-
-    let parse_version =
-        |v: &str| -> Vec<u32> { v.split('.').filter_map(|s| s.parse().ok()).collect() };
-
-    let latest_parts = parse_version(latest);
-    let current_parts = parse_version(current);
+/// Delete `path` from a hidden PowerShell that waits for this process to
+/// exit first. Best effort: a leftover `.exe.old` is removed by the next
+/// update.
+#[cfg(windows)]
+fn schedule_delete_after_exit(path: &Path) {
+    use std::os::windows::process::CommandExt;
+    use std::process::{Command, Stdio};
+
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let script = format!(
+        "Wait-Process -Id {} -ErrorAction SilentlyContinue; Remove-Item -LiteralPath '{}' -Force -ErrorAction SilentlyContinue",
+        std::process::id(),
+        path.display().to_string().replace('\'', "''")
+    );
+    let spawned = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .creation_flags(CREATE_NEW_PROCESS_GROUP | CREATE_NO_WINDOW)
+        .spawn();
+    match spawned {
+        Ok(_) => tracing::trace!(path = %path.display(), "Scheduled deletion of old binary"),
+        Err(e) => tracing::trace!(path = %path.display(), error = %e, "Could not schedule deletion of old binary"),
+    }
+}
 
-    for (l, c) in latest_parts.iter().zip(current_parts.iter()) {
-        if l > c {
-            return true;
-        } else if l < c {
-            return false;
+/// Semver comparison, so pre-releases order before their release
+/// (`1.2.0-beta.3 < 1.2.0`). Unparseable versions are never newer.
+fn version_is_newer(latest: &str, current: &str) -> bool {
+    let parse = |v: &str| Version::parse(v.trim_start_matches('v')).ok();
+    match (parse(latest), parse(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => {
+            tracing::trace!(latest = %latest, current = %current, "Unparseable version, not updating");
+            false
         }
     }
-
-    latest_parts.len() > current_parts.len()
 }
 
 #[cfg(test)]
@@ -311,5 +655,70 @@ mod tests {
         assert!(!version_is_newer("1.0.0", "1.0.0"));
         assert!(!version_is_newer("1.0.0", "1.0.1"));
         assert!(version_is_newer("v1.0.1", "v1.0.0"));
+        assert!(version_is_newer("1.2.0", "1.2.0-beta.3"));
+        assert!(version_is_newer("1.2.0-beta.3", "1.2.0-beta.2"));
+        assert!(!version_is_newer("1.2.0-beta.3", "1.2.0"));
+    }
+
+    #[test]
+    fn test_select_release_tag_by_channel() {
+        let tags = [
+            "cli-nightly-1.3.0-nightly.20260101",
+            "indexer-v2.0.0",
+            "cli-v1.3.0-beta.1",
+            "cli-v1.2.0",
+            "cli-v1.2.0-beta.3",
+        ];
+        assert_eq!(select_release_tag(&tags, Channel::Stable), Some(3));
+        assert_eq!(select_release_tag(&tags, Channel::Beta), Some(2));
+        assert_eq!(select_release_tag(&tags, Channel::Nightly), Some(0));
+        assert_eq!(select_release_tag(&["v0.9.0", "indexer-v1.0.0"], Channel::Stable), Some(0));
+    }
+
+    #[test]
+    fn test_find_version_tag() {
+        let tags = ["cli-v3.1.0", "indexer-v3.0.2", "cli-v3.0.2", "v2.0.0"];
+        let version = |v: &str| Version::parse(v).unwrap();
+        assert_eq!(find_version_tag(&tags, &version("3.0.2")), Some(2));
+        assert_eq!(find_version_tag(&tags, &version("2.0.0")), Some(3));
+        assert_eq!(find_version_tag(&tags, &version("3.0.3")), None);
+    }
+
+    #[test]
+    fn test_reports_version() {
+        assert!(reports_version("adi 1.2.0\n", "1.2.0"));
+        assert!(reports_version("adi v1.2.0", "1.2.0"));
+        assert!(!reports_version("adi 1.2.01", "1.2.0"));
+        assert!(!reports_version("", "1.2.0"));
+    }
+
+    #[test]
+    fn test_linux_libc_target() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(linux_libc_target(root.path()), "unknown-linux-gnu");
+
+        fs::create_dir_all(root.path().join("lib")).unwrap();
+        fs::write(root.path().join("lib/ld-musl-x86_64.so.1"), "").unwrap();
+        assert_eq!(linux_libc_target(root.path()), "unknown-linux-musl");
+        assert_eq!(asset_platforms("x86_64-unknown-linux-musl"), ["x86_64-unknown-linux-musl", "x86_64-unknown-linux-gnu"]);
+        assert_eq!(asset_platforms("x86_64-unknown-linux-gnu"), ["x86_64-unknown-linux-gnu"]);
+
+        // musl installed alongside glibc: the glibc build runs fine.
+        fs::create_dir_all(root.path().join("lib64")).unwrap();
+        fs::write(root.path().join("lib64/ld-linux-x86-64.so.2"), "").unwrap();
+        assert_eq!(linux_libc_target(root.path()), "unknown-linux-gnu");
+    }
+
+    #[test]
+    fn test_expected_checksum() {
+        let archive = "adi-x86_64-unknown-linux-gnu.tar.gz";
+        assert_eq!(expected_checksum("abc123\n", archive), Some("abc123".to_string()));
+        let sums = "111  adi-aarch64-apple-darwin.tar.gz\n222 *adi-x86_64-unknown-linux-gnu.tar.gz\n";
+        assert_eq!(expected_checksum(sums, archive), Some("222".to_string()));
+        assert_eq!(expected_checksum(sums, "adi-x86_64-pc-windows-msvc.zip"), None);
+
+        let digest = format!("{:x}", Sha256::digest(b"archive"));
+        assert!(verify_checksum(archive, b"archive", &digest.to_uppercase()).is_ok());
+        assert!(verify_checksum(archive, b"tampered", &digest).is_err());
     }
 }
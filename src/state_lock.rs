@@ -0,0 +1,87 @@
+//! Cross-process lock around changes to the plugins dir.
+//!
+//! Installs, updates and uninstalls hold an exclusive lock on
+//! `<plugins_dir>/.lock` (`flock` on Unix, `LockFileEx` on Windows, via
+//! [`std::fs::File::try_lock`]) so two `adi` processes can't interleave
+//! writes to the same version dirs and `.version` markers. A second process
+//! waits up to `ADI_LOCK_TIMEOUT` for the first to finish, then gives up with
+//! [`InstallerError::OperationInProgress`]. Read-only commands never take it.
+//!
+//! The OS releases the lock when the holder exits, so a crashed process
+//! can't leave it stuck.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use lib_console_output::out_info;
+use lib_i18n_core::t;
+
+use crate::error::{InstallerError, Result};
+
+pub const LOCK_FILE_NAME: &str = ".lock";
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Held lock; released on drop.
+#[derive(Debug)]
+pub struct StateLock {
+    path: PathBuf,
+    _file: File,
+}
+
+impl StateLock {
+    /// Take the lock on `<plugins_dir>/.lock`, waiting up to `timeout` for
+    /// another process to release it.
+    pub async fn acquire(plugins_dir: &Path, timeout: Duration) -> Result<Self> {
+        std::fs::create_dir_all(plugins_dir)?;
+        let path = plugins_dir.join(LOCK_FILE_NAME);
+        let file = OpenOptions::new().create(true).truncate(false).write(true).open(&path)?;
+
+        let started = Instant::now();
+        let mut announced = false;
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) => {}
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+            if started.elapsed() >= timeout {
+                tracing::trace!(path = %path.display(), waited = ?started.elapsed(), "Gave up waiting for plugin state lock");
+                return Err(InstallerError::OperationInProgress {
+                    lock_path: path.display().to_string(),
+                });
+            }
+            if !announced {
+                announced = true;
+                out_info!("{}", t!("plugin-state-lock-waiting", "path" => &path.display().to_string()));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        tracing::trace!(path = %path.display(), waited = ?started.elapsed(), "Acquired plugin state lock");
+        Ok(Self { path, _file: file })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_second_holder_times_out_until_released() {
+        let dir = tempfile::tempdir().unwrap();
+        let held = StateLock::acquire(dir.path(), Duration::ZERO).await.unwrap();
+        assert!(held.path().ends_with(LOCK_FILE_NAME));
+
+        let contended = StateLock::acquire(dir.path(), Duration::from_millis(300)).await;
+        assert!(matches!(contended, Err(InstallerError::OperationInProgress { .. })));
+
+        drop(held);
+        StateLock::acquire(dir.path(), Duration::ZERO).await.unwrap();
+    }
+}
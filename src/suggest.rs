@@ -0,0 +1,59 @@
+//! "Did you mean" suggestions for mistyped plugin ids and commands.
+
+/// Edit distance (insertions, deletions, substitutions) between two strings,
+/// counted in chars.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Largest distance still worth suggesting for an input: a third of its
+/// length, at least 1 and at most 3.
+pub fn max_distance(input: &str) -> usize {
+    (input.chars().count() / 3).clamp(1, 3)
+}
+
+/// The candidate closest to `input` (case-insensitively) if it is within
+/// [`max_distance`]. Ties go to the earliest candidate.
+pub fn closest<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let input = input.to_lowercase();
+    let limit = max_distance(&input);
+    candidates
+        .into_iter()
+        .map(|candidate| (levenshtein(&input, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("tasks", "tasks"), 0);
+        assert_eq!(levenshtein("tsks", "tasks"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_closest() {
+        let known = ["adi.tasks", "adi.hive", "adi.indexer"];
+        assert_eq!(closest("adi.tsks", known), Some("adi.tasks"));
+        assert_eq!(closest("ADI.HIVE", known), Some("adi.hive"));
+        assert_eq!(closest("adi.workflow", known), None);
+        assert_eq!(closest("tsks", ["tasks", "hive"]), Some("tasks"));
+    }
+}
@@ -0,0 +1,141 @@
+//! Reusing a system-wide tool instead of a plugin's bundled copy
+//! (`adi plugin install --prefer-system`).
+//!
+//! A plugin that wraps an external tool declares it in plugin.toml:
+//!
+//! ```toml
+//! [system_binary]
+//! name = "rg"
+//! min_version = "13.0.0"
+//! ```
+//!
+//! If that tool is on PATH at a compatible version, its path is recorded in
+//! `<plugins_dir>/<id>/.system-binary` and handed to the plugin as
+//! `$ADI_SYSTEM_BINARY`. Otherwise the plugin keeps using its bundled copy.
+
+use std::path::{Path, PathBuf};
+
+use semver::Version;
+
+pub const POINTER_FILE: &str = ".system-binary";
+pub const ENV_VAR: &str = "ADI_SYSTEM_BINARY";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemBinarySpec {
+    pub name: String,
+    pub min_version: Option<Version>,
+}
+
+/// Why a declared system binary can't be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unusable {
+    NotFound,
+    TooOld { found: Version, required: Version },
+    UnknownVersion { path: PathBuf },
+}
+
+/// `[system_binary]` from a plugin.toml, if declared.
+pub fn parse_spec(manifest: &str) -> Option<SystemBinarySpec> {
+    let table = manifest.parse::<toml::Table>().ok()?;
+    let section = table.get("system_binary")?;
+    let name = section.get("name")?.as_str()?.to_string();
+    let min_version = section
+        .get("min_version")
+        .and_then(|v| v.as_str())
+        .and_then(lenient_version);
+    Some(SystemBinarySpec { name, min_version })
+}
+
+/// Path of a compatible system binary for `spec`.
+pub fn resolve(spec: &SystemBinarySpec) -> std::result::Result<PathBuf, Unusable> {
+    let path = find_on_path(&spec.name).ok_or(Unusable::NotFound)?;
+    let Some(required) = &spec.min_version else {
+        return Ok(path);
+    };
+    let found = detect_version(&path).ok_or_else(|| Unusable::UnknownVersion { path: path.clone() })?;
+    tracing::trace!(path = %path.display(), found = %found, required = %required, "Checked system binary version");
+    if found < *required {
+        return Err(Unusable::TooOld {
+            found,
+            required: required.clone(),
+        });
+    }
+    Ok(path)
+}
+
+pub fn find_on_path(name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    let file_name = if cfg!(windows) && Path::new(name).extension().is_none() {
+        format!("{}.exe", name)
+    } else {
+        name.to_string()
+    };
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Version reported by `<path> --version`.
+pub fn detect_version(path: &Path) -> Option<Version> {
+    let output = std::process::Command::new(path).arg("--version").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    version_in_output(&text)
+}
+
+/// First version-looking word in a tool's `--version` output
+/// (`ripgrep 13.0.0 (rev af6b6c543b)`, `jq-1.7.1`, `git version 2.43`).
+fn version_in_output(output: &str) -> Option<Version> {
+    output
+        .split(|c: char| c.is_whitespace() || c == '-' || c == ',')
+        .find_map(|word| lenient_version(word.trim_start_matches('v')))
+}
+
+/// Parse `1`, `1.2` or `1.2.3`, padding missing parts with zero.
+fn lenient_version(raw: &str) -> Option<Version> {
+    if let Ok(version) = Version::parse(raw) {
+        return Some(version);
+    }
+    let parts: Vec<u64> = raw.split('.').map(|p| p.parse().ok()).collect::<Option<_>>()?;
+    match parts.as_slice() {
+        [major] => Some(Version::new(*major, 0, 0)),
+        [major, minor] => Some(Version::new(*major, *minor, 0)),
+        _ => None,
+    }
+}
+
+pub fn record(plugin_dir: &Path, binary: &Path) -> std::io::Result<()> {
+    std::fs::write(plugin_dir.join(POINTER_FILE), binary.display().to_string())
+}
+
+/// The recorded system binary, whether or not it still exists.
+pub fn recorded(plugin_dir: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(plugin_dir.join(POINTER_FILE)).ok()?;
+    let path = content.trim();
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+pub fn clear(plugin_dir: &Path) {
+    let _ = std::fs::remove_file(plugin_dir.join(POINTER_FILE));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        let manifest = "[plugin]\nid = \"adi.search\"\n\n[system_binary]\nname = \"rg\"\nmin_version = \"13.0\"\n";
+        let spec = parse_spec(manifest).unwrap();
+        assert_eq!(spec.name, "rg");
+        assert_eq!(spec.min_version, Some(Version::new(13, 0, 0)));
+        assert!(parse_spec("[plugin]\nid = \"x\"\n").is_none());
+    }
+
+    #[test]
+    fn test_version_in_output() {
+        assert_eq!(version_in_output("ripgrep 13.0.0 (rev af6b6c543b)\n"), Some(Version::new(13, 0, 0)));
+        assert_eq!(version_in_output("jq-1.7.1"), Some(Version::new(1, 7, 1)));
+        assert_eq!(version_in_output("git version 2.43"), Some(Version::new(2, 43, 0)));
+        assert_eq!(version_in_output("no version here"), None);
+    }
+}
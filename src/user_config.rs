@@ -1,4 +1,4 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -11,8 +11,17 @@ pub struct UserConfig {
     pub theme: Option<String>,
     /// Power user mode - enables advanced features and verbose output
     pub power_user: Option<bool>,
+    /// Default parallelism for bulk plugin operations
+    pub jobs: Option<usize>,
+    /// Release channel for `adi self-update` (stable, beta, nightly)
+    pub update_channel: Option<crate::self_update::Channel>,
+    /// Plugin registry URL ($ADI_REGISTRY_URL takes precedence)
+    pub registry_url: Option<String>,
 }
 
+/// Keys `adi config get`/`set` accept, in display order.
+pub const KEYS: &[&str] = &["language", "theme", "power_user", "jobs", "registry_url", "channel"];
+
 impl UserConfig {
     /// $ADI_CONFIG_DIR/config.toml or ~/.config/adi/config.toml
     pub fn config_path() -> Result<PathBuf> {
@@ -57,6 +66,61 @@ impl UserConfig {
         Ok(())
     }
 
+    /// Value of `key` as `adi config get` prints it; `None` when unset.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let value = match key {
+            "language" => self.language.clone(),
+            "theme" => self.theme.clone(),
+            "power_user" => self.power_user.map(|v| v.to_string()),
+            "jobs" => self.jobs.map(|v| v.to_string()),
+            "registry_url" => self.registry_url.clone(),
+            "channel" => self.update_channel.map(|v| v.to_string()),
+            _ => bail!("Unknown config key '{}'. Known keys: {}", key, KEYS.join(", ")),
+        };
+        Ok(value)
+    }
+
+    /// Parse and validate `value` for `key` and store it (not saved to disk).
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let value = value.trim();
+        match key {
+            "language" => {
+                if !is_locale(value) {
+                    bail!("Invalid language '{}'. Use a locale such as en-US or uk-UA", value);
+                }
+                self.language = Some(value.to_string());
+            }
+            "theme" => {
+                let themes = lib_console_output::theme::generated::THEMES;
+                if !themes.iter().any(|t| t.id == value) {
+                    let ids: Vec<&str> = themes.iter().map(|t| t.id).collect();
+                    bail!("Unknown theme '{}'. Available: {}", value, ids.join(", "));
+                }
+                self.theme = Some(value.to_string());
+            }
+            "power_user" => self.power_user = Some(parse_bool(value)?),
+            "jobs" => match value.parse::<usize>() {
+                Ok(jobs) if jobs > 0 => self.jobs = Some(jobs),
+                _ => bail!("Invalid jobs '{}'. Use a number of at least 1", value),
+            },
+            "registry_url" => {
+                let valid = reqwest::Url::parse(value).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+                if !valid {
+                    bail!("Invalid registry URL '{}'. Use an http:// or https:// URL", value);
+                }
+                self.registry_url = Some(value.trim_end_matches('/').to_string());
+            }
+            "channel" => {
+                let channel = <crate::self_update::Channel as clap::ValueEnum>::from_str(value, true)
+                    .map_err(|_| anyhow::anyhow!("Invalid channel '{}'. Use stable, beta or nightly", value))?;
+                self.update_channel = Some(channel);
+            }
+            _ => bail!("Unknown config key '{}'. Known keys: {}", key, KEYS.join(", ")),
+        }
+        tracing::trace!(key = %key, value = %value, "Config value set");
+        Ok(())
+    }
+
     pub fn is_first_run() -> Result<bool> {
         let path = Self::config_path()?;
         let first_run = !path.exists();
@@ -68,3 +132,56 @@ impl UserConfig {
         std::io::IsTerminal::is_terminal(&std::io::stdin())
     }
 }
+
+/// `true`/`1`/`yes`/`on` or `false`/`0`/`no`/`off`, any case.
+pub fn parse_bool(value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => bail!("Invalid value '{}'. Use 'true' or 'false'.", value),
+    }
+}
+
+/// BCP 47-shaped tag: a 2-3 letter language, then optional alphanumeric
+/// subtags (`en`, `en-US`, `zh-Hant-TW`).
+fn is_locale(value: &str) -> bool {
+    let mut parts = value.split('-');
+    let language = parts.next().unwrap_or_default();
+    (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|p| (2..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_get_validate_per_key() {
+        let mut config = UserConfig::default();
+        for key in KEYS {
+            assert_eq!(config.get(key).unwrap(), None);
+        }
+
+        config.set("language", "uk-UA").unwrap();
+        config.set("power_user", "on").unwrap();
+        config.set("jobs", "4").unwrap();
+        config.set("registry_url", "https://registry.example/").unwrap();
+        config.set("channel", "Beta").unwrap();
+        assert_eq!(config.get("language").unwrap().as_deref(), Some("uk-UA"));
+        assert_eq!(config.get("power_user").unwrap().as_deref(), Some("true"));
+        assert_eq!(config.get("jobs").unwrap().as_deref(), Some("4"));
+        assert_eq!(config.get("registry_url").unwrap().as_deref(), Some("https://registry.example"));
+        assert_eq!(config.get("channel").unwrap().as_deref(), Some("beta"));
+
+        assert!(config.set("language", "english please").is_err());
+        assert!(config.set("theme", "no-such-theme").is_err());
+        assert!(config.set("power_user", "maybe").is_err());
+        assert!(config.set("jobs", "0").is_err());
+        assert!(config.set("registry_url", "ftp://registry.example").is_err());
+        assert!(config.set("channel", "canary").is_err());
+        assert!(config.set("colour", "red").is_err());
+        assert!(config.get("colour").is_err());
+        assert_eq!(config.get("language").unwrap().as_deref(), Some("uk-UA"));
+    }
+}